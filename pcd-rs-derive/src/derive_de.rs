@@ -1,4 +1,8 @@
-use crate::{common::*, parse::ItemStruct, utils::parse_field_attributes};
+use crate::{
+    common::*,
+    parse::ItemStruct,
+    utils::{derive_pod_check, parse_field_attributes, parse_field_shape, FieldShape},
+};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::{
@@ -21,42 +25,155 @@ pub fn f_pcd_record_read_derive(item: ItemStruct) -> syn::Result<TokenStream> {
         text_read_tokens,
     } = derive_named_fields(struct_name, &item.fields)?;
 
+    let pod_check = derive_pod_check(&item, quote! { ::pcd_rs::record::PcdDeserialize })?;
+    let pod_tokens = pod_check.is_some().then(|| {
+        quote! {
+            fn is_pod() -> bool {
+                true
+            }
+
+            fn read_pod_slice(bytes: &[u8], output: &mut Vec<Self>) -> bool {
+                // SAFETY: The layout is checked at compile time.
+                unsafe { ::pcd_rs::record::pod_extend_from_bytes(bytes, output) }
+            }
+        }
+    });
+
+    let dyn_record_tokens = derive_try_from_dyn_record(&item)?;
+
     let expanded = quote! {
+        #pod_check
+
+        #dyn_record_tokens
+
         impl ::pcd_rs::record::PcdDeserialize for #struct_name {
+            const FIELDS: &'static [::pcd_rs::metas::StaticFieldDef] = #read_spec_tokens;
+
             fn is_dynamic() -> bool {
                 false
             }
 
-            fn read_spec() -> Vec<(Option<String>, ::pcd_rs::metas::ValueKind, Option<usize>)> {
-                #read_spec_tokens
-            }
-
-           fn read_chunk<R: std::io::BufRead>(reader: &mut R, field_defs: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<#struct_name> {
+            fn decode(bytes: &[u8], field_defs: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<#struct_name> {
                 use ::pcd_rs::byteorder::{LittleEndian, ReadBytesExt};
+                let mut reader = bytes;
                 let result = { #bin_read_tokens };
                 Ok(result)
             }
 
-            fn read_line<R: std::io::BufRead>(reader: &mut R, field_defs: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<#struct_name> {
-                let mut line = String::new();
-                let mut tokens = {
-                    let read_size = reader.read_line(&mut line)?;
-                    let tokens = line.split_ascii_whitespace().collect::<Vec<_>>();
-                    tokens.into_iter()
-                };
+            fn decode_tokens<'a, I>(tokens: &mut I, field_defs: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<#struct_name>
+            where
+                I: Iterator<Item = &'a str>,
+            {
+                let mut tokens = ::pcd_rs::record::TokenReader::new(tokens);
+                let result = { #text_read_tokens };
+                Ok(result)
+            }
 
-                {
-                    let expect = field_defs.iter().fold(0, |sum, def| sum + def.count as usize);
-                    let (found, _) = tokens.size_hint();
-                    if expect != found {
-                        use ::pcd_rs::error::Error;
-                        let error = Error::new_text_token_mismatch_error(expect, found);
-                        return Err(error.into());
-                    }
+            fn read_chunk<R: ::std::io::BufRead>(reader: &mut R, field_defs: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<#struct_name> {
+                ::pcd_rs::record::read_chunk_by_decode(reader, field_defs)
+            }
+
+            fn read_line<R: ::std::io::BufRead>(reader: &mut R, field_defs: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<#struct_name> {
+                ::pcd_rs::record::read_line_by_decode(reader, field_defs)
+            }
+
+            #pod_tokens
+        }
+    };
+
+    Ok(expanded)
+}
+
+fn derive_try_from_dyn_record(item: &ItemStruct) -> syn::Result<TokenStream> {
+    let struct_name = &item.ident;
+
+    let fields: Vec<_> = item
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(field_index, field)| -> syn::Result<_> {
+            let field_ident = field.ident.as_ref().unwrap();
+            let opts = parse_field_attributes(&field.attrs)?;
+            let (elem, shape) = parse_field_shape(&field.ty).ok_or_else(|| {
+                Error::new(
+                    field.span(),
+                    "expect a primitive type, array of primitive type, or Vec<_> of primitive type",
+                )
+            })?;
+
+            let (name_tokens, display_name) = match (opts.ignore, opts.rename) {
+                (true, _) => (quote! { None }, field_ident.to_string()),
+                (false, None) => {
+                    let name = field_ident.to_string();
+                    (quote! { Some(#name) }, name)
                 }
+                (false, Some(rename)) => (quote! { Some(#rename) }, rename),
+            };
 
-                let result = { #text_read_tokens };
-                Ok(result)
+            let (count_tokens, value_tokens) = match shape {
+                FieldShape::Scalar => (quote! { Some(1) }, quote! { values[0] }),
+                FieldShape::Array(len) => (
+                    quote! { Some((#len) as usize) },
+                    quote! {
+                        let mut array = [Default::default(); #len];
+                        array.copy_from_slice(values);
+                        array
+                    },
+                ),
+                FieldShape::Vec => (quote! { None }, quote! { values.to_vec() }),
+            };
+
+            let tokens = quote! {
+                let #field_ident = {
+                    let field = ::pcd_rs::record::dyn_record_field(
+                        record,
+                        schema,
+                        #field_index,
+                        #name_tokens,
+                        #display_name,
+                    )?;
+                    let values = ::pcd_rs::record::dyn_field_values::<#elem>(
+                        field,
+                        #display_name,
+                        #count_tokens,
+                    )?;
+                    #value_tokens
+                };
+            };
+
+            Ok((field_ident, tokens))
+        })
+        .try_collect()?;
+    let (field_idents, field_tokens): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+
+    let body = quote! {
+        #(#field_tokens)*
+
+        Ok(#struct_name {
+            #(#field_idents),*
+        })
+    };
+
+    let expanded = quote! {
+        impl ::std::convert::TryFrom<&::pcd_rs::record::DynRecord> for #struct_name {
+            type Error = ::pcd_rs::Error;
+
+            fn try_from(record: &::pcd_rs::record::DynRecord) -> ::pcd_rs::Result<Self> {
+                let schema: Option<&::pcd_rs::metas::Schema> = None;
+                #body
+            }
+        }
+
+        impl ::std::convert::TryFrom<(&::pcd_rs::record::DynRecord, &::pcd_rs::metas::Schema)>
+            for #struct_name
+        {
+            type Error = ::pcd_rs::Error;
+
+            fn try_from(
+                (record, schema): (&::pcd_rs::record::DynRecord, &::pcd_rs::metas::Schema),
+            ) -> ::pcd_rs::Result<Self> {
+                let schema = Some(schema);
+                #body
             }
         }
     };
@@ -106,8 +223,12 @@ fn derive_named_fields(
         .map(|(field_ident, pcd_name_opt, tokens)| {
             let read_spec_tokens = tokens.read_spec_tokens;
             let read_spec = match pcd_name_opt {
-                Some(name) => quote! { (Some(#name.to_owned()), #read_spec_tokens) },
-                None => quote! { (None, #read_spec_tokens) },
+                Some(name) => quote! {
+                    ::pcd_rs::metas::StaticFieldDef::new(Some(#name), #read_spec_tokens)
+                },
+                None => quote! {
+                    ::pcd_rs::metas::StaticFieldDef::new(None, #read_spec_tokens)
+                },
             };
 
             (
@@ -119,7 +240,7 @@ fn derive_named_fields(
         })
         .unzip_n_vec();
 
-    let read_spec_tokens = quote! { vec![#(#read_specs),*] };
+    let read_spec_tokens = quote! { &[#(#read_specs),*] };
     let bin_read_tokens = quote! {
         #(#bin_read_fields)*
 
@@ -156,7 +277,7 @@ fn derive_array_field(var_ident: &Ident, array: &TypeArray) -> Option<DerivedTok
         text_read_tokens: text_read,
     } = make_rw_expr(type_ident)?;
 
-    let read_spec_tokens = quote! { #read_spec, Some(#len) };
+    let read_spec_tokens = quote! { #read_spec, Some((#len) as u64) };
     let bin_read_tokens = quote! {
         let mut #var_ident = [Default::default(); #len];
 
@@ -169,7 +290,7 @@ fn derive_array_field(var_ident: &Ident, array: &TypeArray) -> Option<DerivedTok
 
         for idx in 0..(#len) {
             #var_ident [idx] = {
-                let token = tokens.next().unwrap();
+                let token = tokens.next_token(field_defs)?;
                 #text_read
             };
         }
@@ -252,7 +373,7 @@ fn derive_primitive_field(var_ident: &Ident, type_ident: &Ident) -> Option<Deriv
     };
     let text_read_tokens = quote! {
         let #var_ident = {
-            let token = tokens.next().unwrap();
+            let token = tokens.next_token(field_defs)?;
             #text_read
         };
     };
@@ -296,7 +417,7 @@ fn derive_vec_field(
             (0..count)
                 .into_iter()
                 .map(|_| {
-                    let token = tokens.next().unwrap();
+                    let token = tokens.next_token(field_defs)?;
                     let value = { #text_read };
                     Ok(value)
                 })
@@ -319,42 +440,42 @@ fn make_rw_expr(type_ident: &Ident) -> Option<DerivedTokens> {
             "u8" => (
                 quote! { ::pcd_rs::metas::ValueKind::U8 },
                 quote! { reader.read_u8()? },
-                quote! { token.parse::<u8>()? },
+                quote! { <u8 as ::pcd_rs::traits::Value>::parse_token(token)? },
             ),
             "u16" => (
                 quote! { ::pcd_rs::metas::ValueKind::U16 },
                 quote! { reader.read_u16::<LittleEndian>()? },
-                quote! { token.parse::<u16>()? },
+                quote! { <u16 as ::pcd_rs::traits::Value>::parse_token(token)? },
             ),
             "u32" => (
                 quote! { ::pcd_rs::metas::ValueKind::U32 },
                 quote! { reader.read_u32::<LittleEndian>()? },
-                quote! { token.parse::<u32>()? },
+                quote! { <u32 as ::pcd_rs::traits::Value>::parse_token(token)? },
             ),
             "i8" => (
                 quote! { ::pcd_rs::metas::ValueKind::I8 },
                 quote! { reader.read_i8()? },
-                quote! { token.parse::<i8>()? },
+                quote! { <i8 as ::pcd_rs::traits::Value>::parse_token(token)? },
             ),
             "i16" => (
                 quote! { ::pcd_rs::metas::ValueKind::I16 },
                 quote! { reader.read_i16::<LittleEndian>()? },
-                quote! { token.parse::<i16>()? },
+                quote! { <i16 as ::pcd_rs::traits::Value>::parse_token(token)? },
             ),
             "i32" => (
                 quote! { ::pcd_rs::metas::ValueKind::I32 },
                 quote! { reader.read_i32::<LittleEndian>()? },
-                quote! { token.parse::<i32>()? },
+                quote! { <i32 as ::pcd_rs::traits::Value>::parse_token(token)? },
             ),
             "f32" => (
                 quote! { ::pcd_rs::metas::ValueKind::F32 },
                 quote! { reader.read_f32::<LittleEndian>()? },
-                quote! { token.parse::<f32>()? },
+                quote! { <f32 as ::pcd_rs::traits::Value>::parse_token(token)? },
             ),
             "f64" => (
                 quote! { ::pcd_rs::metas::ValueKind::F64 },
                 quote! { reader.read_f64::<LittleEndian>()? },
-                quote! { token.parse::<f64>()? },
+                quote! { <f64 as ::pcd_rs::traits::Value>::parse_token(token)? },
             ),
             _ => return None,
         };
@@ -1,4 +1,8 @@
-use crate::{common::*, parse::ItemStruct, utils::parse_field_attributes};
+use crate::{
+    common::*,
+    parse::ItemStruct,
+    utils::{derive_pod_check, parse_field_attributes, parse_field_shape, FieldShape},
+};
 use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 use syn::{
@@ -20,29 +24,93 @@ pub fn f_pcd_record_write_derive(item: ItemStruct) -> syn::Result<TokenStream> {
         text_write_tokens,
     } = derive_named_fields(struct_name, &item.fields)?;
 
+    let pod_check = derive_pod_check(&item, quote! { ::pcd_rs::record::PcdSerialize })?;
+    let pod_tokens = pod_check.is_some().then(|| {
+        quote! {
+            fn is_pod() -> bool {
+                true
+            }
+
+            fn pod_bytes(records: &[Self]) -> Option<&[u8]> {
+                // SAFETY: The layout is checked at compile time.
+                unsafe { ::pcd_rs::record::pod_as_bytes(records) }
+            }
+        }
+    });
+
+    let dyn_record_tokens = derive_into_dyn_record(&item)?;
+
     let expanded = quote! {
+        #pod_check
+
+        #dyn_record_tokens
+
         impl ::pcd_rs::record::PcdSerialize for #struct_name {
+            const FIELDS: &'static [::pcd_rs::metas::StaticFieldDef] = #write_spec_tokens;
+
             fn is_dynamic() -> bool {
                 false
             }
 
-            fn write_spec() -> ::pcd_rs::metas::Schema {
-                #write_spec_tokens
-            }
-
-            fn write_chunk<R: std::io::Write>(&self, writer: &mut R, _: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<()> {
-                use ::pcd_rs::byteorder::{LittleEndian, WriteBytesExt};
+            fn encode(&self, output: &mut Vec<u8>, _: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<()> {
                 { #bin_write_tokens };
                 Ok(())
             }
 
-            fn write_line<R: std::io::Write>(&self, writer: &mut R, _: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<()> {
-                let mut tokens = Vec::<String>::new();
+            fn encode_tokens(&self, tokens: &mut ::pcd_rs::record::TokenWriter<'_>, _: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<()> {
                 { #text_write_tokens };
-                let line = tokens.join(" ");
-                writeln!(writer, "{}", line)?;
                 Ok(())
             }
+
+            fn write_chunk<R: ::std::io::Write>(&self, writer: &mut R, spec: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<()> {
+                ::pcd_rs::record::write_chunk_by_encode(self, writer, spec)
+            }
+
+            fn write_line<R: ::std::io::Write>(&self, writer: &mut R, spec: &::pcd_rs::metas::Schema) -> ::pcd_rs::Result<()> {
+                ::pcd_rs::record::write_line_by_encode(self, writer, spec)
+            }
+
+            #pod_tokens
+        }
+    };
+
+    Ok(expanded)
+}
+
+fn derive_into_dyn_record(item: &ItemStruct) -> syn::Result<TokenStream> {
+    let struct_name = &item.ident;
+
+    let fields: Vec<_> = item
+        .fields
+        .iter()
+        .map(|field| {
+            let field_ident = field.ident.as_ref().unwrap();
+            let field_error = syn::Error::new(
+                field.span(),
+                "Type of struct field must be a primitive type or array of primitive type.",
+            );
+
+            let tokens = match parse_field_shape(&field.ty).ok_or(field_error.clone())? {
+                (elem, FieldShape::Scalar) => quote! {
+                    <#elem as ::pcd_rs::traits::Value>::into_field(vec![*#field_ident])
+                },
+                (elem, FieldShape::Array(_)) => quote! {
+                    <#elem as ::pcd_rs::traits::Value>::into_field(#field_ident.to_vec())
+                },
+                (_, FieldShape::Vec) => return Err(field_error),
+            };
+
+            Ok((field_ident, tokens))
+        })
+        .try_collect()?;
+    let (field_idents, field_tokens): (Vec<_>, Vec<_>) = fields.into_iter().unzip();
+
+    let expanded = quote! {
+        impl ::std::convert::From<&#struct_name> for ::pcd_rs::record::DynRecord {
+            fn from(record: &#struct_name) -> Self {
+                let #struct_name { #(#field_idents),* } = record;
+                ::pcd_rs::record::DynRecord(vec![#(#field_tokens),*])
+            }
         }
     };
 
@@ -89,20 +157,24 @@ fn derive_named_fields(
         .into_iter()
         .map(|(field_ident, pcd_name, tokens)| {
             let write_spec_tokens = tokens.write_spec_tokens;
+            let write_spec = match pcd_name {
+                Some(name) => quote! {
+                    ::pcd_rs::metas::StaticFieldDef::new(Some(#name), #write_spec_tokens)
+                },
+                None => quote! {
+                    ::pcd_rs::metas::StaticFieldDef::new(None, #write_spec_tokens)
+                },
+            };
             (
                 field_ident,
-                quote! { (#pcd_name.to_owned(), #write_spec_tokens) },
+                write_spec,
                 tokens.bin_write_tokens,
                 tokens.text_write_tokens,
             )
         })
         .unzip_n_vec();
 
-    let write_spec_tokens = quote! {
-        vec![#(#write_specs),*]
-            .into_iter()
-            .collect::<::pcd_rs::metas::Schema>()
-    };
+    let write_spec_tokens = quote! { &[#(#write_specs),*] };
     let bin_write_tokens = quote! {
         let #struct_name { #(#field_idents),* } = self;
         #(#bin_write_fields)*
@@ -133,7 +205,7 @@ fn derive_array_field(var_ident: &Ident, array: &TypeArray) -> Option<DerivedTok
         text_write_tokens: text_write,
     } = make_rw_expr(type_ident)?;
 
-    let write_spec_tokens = quote! { #write_spec, #len };
+    let write_spec_tokens = quote! { #write_spec, Some((#len) as u64) };
     let bin_write_tokens = quote! {
         for value_ref in #var_ident.iter() {
             let value = *value_ref;
@@ -172,7 +244,7 @@ fn derive_primitive_field(var_ident: &Ident, type_ident: &Ident) -> Option<Deriv
         text_write_tokens: text_write,
     } = make_rw_expr(type_ident)?;
 
-    let write_spec_tokens = quote! { #write_spec, 1 };
+    let write_spec_tokens = quote! { #write_spec, Some(1) };
     let bin_write_tokens = quote! {
         {
             let value = *#var_ident;
@@ -200,43 +272,43 @@ fn make_rw_expr(type_ident: &Ident) -> Option<DerivedTokens> {
         match type_ident.to_string().as_str() {
             "u8" => (
                 quote! { ::pcd_rs::metas::ValueKind::U8 },
-                quote! { writer.write_u8(value)? },
-                quote! { tokens.push(u8::to_string(&value)) },
+                quote! { output.extend_from_slice(&value.to_le_bytes()) },
+                quote! { tokens.push(value) },
             ),
             "u16" => (
                 quote! { ::pcd_rs::metas::ValueKind::U16 },
-                quote! { writer.write_u16::<LittleEndian>(value)? },
-                quote! { tokens.push(u16::to_string(&value)) },
+                quote! { output.extend_from_slice(&value.to_le_bytes()) },
+                quote! { tokens.push(value) },
             ),
             "u32" => (
                 quote! { ::pcd_rs::metas::ValueKind::U32 },
-                quote! { writer.write_u32::<LittleEndian>(value)? },
-                quote! { tokens.push(u32::to_string(&value)) },
+                quote! { output.extend_from_slice(&value.to_le_bytes()) },
+                quote! { tokens.push(value) },
             ),
             "i8" => (
                 quote! { ::pcd_rs::metas::ValueKind::I8 },
-                quote! { writer.write_i8(value)? },
-                quote! { tokens.push(i8::to_string(&value)) },
+                quote! { output.extend_from_slice(&value.to_le_bytes()) },
+                quote! { tokens.push(value) },
             ),
             "i16" => (
                 quote! { ::pcd_rs::metas::ValueKind::I16 },
-                quote! { writer.write_i16::<LittleEndian>(value)? },
-                quote! { tokens.push(i16::to_string(&value)) },
+                quote! { output.extend_from_slice(&value.to_le_bytes()) },
+                quote! { tokens.push(value) },
             ),
             "i32" => (
                 quote! { ::pcd_rs::metas::ValueKind::I32 },
-                quote! { writer.write_i32::<LittleEndian>(value)? },
-                quote! { tokens.push(i32::to_string(&value)) },
+                quote! { output.extend_from_slice(&value.to_le_bytes()) },
+                quote! { tokens.push(value) },
             ),
             "f32" => (
                 quote! { ::pcd_rs::metas::ValueKind::F32 },
-                quote! { writer.write_f32::<LittleEndian>(value)? },
-                quote! { tokens.push(f32::to_string(&value)) },
+                quote! { output.extend_from_slice(&value.to_le_bytes()) },
+                quote! { tokens.push(value) },
             ),
             "f64" => (
                 quote! { ::pcd_rs::metas::ValueKind::F64 },
-                quote! { writer.write_f64::<LittleEndian>(value)? },
-                quote! { tokens.push(f64::to_string(&value)) },
+                quote! { output.extend_from_slice(&value.to_le_bytes()) },
+                quote! { tokens.push(value) },
             ),
             _ => return None,
         };
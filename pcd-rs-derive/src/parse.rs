@@ -4,27 +4,28 @@ use syn::{
     braced,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    token, Attribute, Error, Field, Ident, LitStr, Result, Token, Visibility,
+    Attribute, Error, Field, Ident, LitStr, Result, Token, Visibility,
 };
 
 pub struct ItemStruct {
     pub attrs: Vec<Attribute>,
-    pub vis: Visibility,
-    pub struct_token: Token![struct],
     pub ident: Ident,
-    pub brace_token: token::Brace,
     pub fields: Punctuated<Field, Token![,]>,
 }
 
 impl Parse for ItemStruct {
     fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        // The visibility and the struct keyword are not used.
+        input.parse::<Visibility>()?;
+        input.parse::<Token![struct]>()?;
+        let ident = input.parse()?;
         let content;
+        braced!(content in input);
+
         Ok(ItemStruct {
-            attrs: input.call(Attribute::parse_outer)?,
-            vis: input.parse()?,
-            struct_token: input.parse()?,
-            ident: input.parse()?,
-            brace_token: braced!(content in input),
+            attrs,
+            ident,
             fields: content.parse_terminated(Field::parse_named, Token![,])?,
         })
     }
@@ -45,6 +46,7 @@ impl Parse for AttrList {
 pub enum AttrOption {
     Rename(RenameAttr),
     Ignore(IgnoreAttr),
+    Pod(PodAttr),
 }
 
 impl AttrOption {
@@ -63,6 +65,22 @@ impl AttrOption {
             None
         }
     }
+
+    pub fn as_pod(&self) -> Option<&PodAttr> {
+        if let Self::Pod(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    pub fn ident(&self) -> &Ident {
+        match self {
+            Self::Rename(v) => &v.ident,
+            Self::Ignore(v) => &v.ident,
+            Self::Pod(v) => &v.ident,
+        }
+    }
 }
 
 impl From<RenameAttr> for AttrOption {
@@ -77,10 +95,14 @@ impl From<IgnoreAttr> for AttrOption {
     }
 }
 
+impl From<PodAttr> for AttrOption {
+    fn from(v: PodAttr) -> Self {
+        Self::Pod(v)
+    }
+}
+
 pub struct RenameAttr {
     pub ident: Ident,
-    pub eq_token: Token![=],
-    pub lit: LitStr,
     pub rename: String,
 }
 
@@ -88,6 +110,10 @@ pub struct IgnoreAttr {
     pub ident: Ident,
 }
 
+pub struct PodAttr {
+    pub ident: Ident,
+}
+
 impl Parse for AttrOption {
     fn parse(input: ParseStream) -> Result<Self> {
         let ident: Ident = input.parse()?;
@@ -97,7 +123,7 @@ impl Parse for AttrOption {
                 static NAME_REGEX: Lazy<Regex> =
                     Lazy::new(|| Regex::new(r"^[[:word:]]+$").unwrap());
 
-                let eq_token = input.parse()?;
+                input.parse::<Token![=]>()?;
                 let lit: LitStr = input.parse()?;
                 let rename = lit.value();
 
@@ -105,15 +131,10 @@ impl Parse for AttrOption {
                     .find(&rename)
                     .ok_or_else(|| Error::new(lit.span(), "invalid name"))?;
 
-                RenameAttr {
-                    ident,
-                    eq_token,
-                    lit,
-                    rename,
-                }
-                .into()
+                RenameAttr { ident, rename }.into()
             }
             "ignore" => IgnoreAttr { ident }.into(),
+            "pod" => PodAttr { ident }.into(),
             name => {
                 return Err(Error::new(
                     ident.span(),
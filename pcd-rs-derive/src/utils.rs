@@ -1,30 +1,23 @@
-use crate::common::*;
-use syn::{spanned::Spanned, AttrStyle, Attribute, Error};
+use crate::{common::*, parse::ItemStruct};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    spanned::Spanned, AttrStyle, Attribute, Error, Expr, GenericArgument, Ident, PathArguments,
+    Type,
+};
 
 use crate::parse::{AttrList, AttrOption};
 
 pub fn parse_field_attributes(attrs: &[Attribute]) -> syn::Result<Options> {
     {
-        let options: Vec<_> = attrs
-            .iter()
-            .filter(|attr| attr.path().is_ident("pcd"))
-            .map(|attr| {
-                if attr.style == AttrStyle::Outer {
-                    Ok(attr)
-                } else {
-                    Err(Error::new(
-                        attr.span(),
-                        "inner pcd attribute is not supported",
-                    ))
-                }
-            })
-            .map(|attr| -> syn::Result<_> {
-                let attr = attr?;
-                let attr_list: AttrList = attr.parse_args()?;
-                Ok(attr_list)
-            })
-            .try_collect()?;
-        let options: Vec<AttrOption> = options.into_iter().flat_map(|list| list.options).collect();
+        let options = parse_pcd_attributes(attrs)?;
+
+        if let Some(opt) = options.iter().find(|opt| opt.as_pod().is_some()) {
+            return Err(syn::Error::new(
+                opt.ident().span(),
+                "pod option is only allowed on the struct",
+            ));
+        }
 
         let ignore_option = {
             let mut ignore_opts = options.iter().filter_map(|opt| opt.as_ignore()).fuse();
@@ -60,3 +53,169 @@ pub struct Options {
     pub ignore: bool,
     pub rename: Option<String>,
 }
+
+pub struct StructOptions {
+    pub pod: bool,
+}
+
+pub fn parse_struct_attributes(attrs: &[Attribute]) -> syn::Result<StructOptions> {
+    let options = parse_pcd_attributes(attrs)?;
+
+    if let Some(opt) = options.iter().find(|opt| opt.as_pod().is_none()) {
+        return Err(syn::Error::new(
+            opt.ident().span(),
+            "only pod option is allowed on the struct",
+        ));
+    }
+
+    let mut pod_opts = options.iter().filter_map(|opt| opt.as_pod()).fuse();
+    let pod_opt = pod_opts.next();
+    if let Some(opt) = pod_opts.next() {
+        return Err(syn::Error::new(
+            opt.ident.span(),
+            "pod option cannot specified more than once",
+        ));
+    }
+
+    Ok(StructOptions {
+        pod: pod_opt.is_some(),
+    })
+}
+
+/// Checks the `#[pcd(pod)]` requirements on the struct and generates
+/// the compile-time layout check. It returns `None` if the struct is
+/// not marked as pod.
+pub fn derive_pod_check(
+    item: &ItemStruct,
+    trait_path: TokenStream,
+) -> syn::Result<Option<TokenStream>> {
+    let struct_name = &item.ident;
+
+    if !parse_struct_attributes(&item.attrs)?.pod {
+        return Ok(None);
+    }
+
+    if !is_repr_c(&item.attrs)? {
+        return Err(Error::new(
+            struct_name.span(),
+            "#[pcd(pod)] requires the struct to be #[repr(C)]",
+        ));
+    }
+
+    for field in &item.fields {
+        let is_primitive = match &field.ty {
+            Type::Path(path) => path.path.get_ident().is_some(),
+            Type::Array(_) => true,
+            _ => false,
+        };
+
+        if !is_primitive {
+            return Err(Error::new(
+                field.span(),
+                "#[pcd(pod)] only allows primitive types or arrays of primitive types",
+            ));
+        }
+    }
+
+    let tokens = quote! {
+        const _: () = ::std::assert!(
+            ::std::mem::size_of::<#struct_name>()
+                == ::pcd_rs::metas::StaticFieldDef::point_size(<#struct_name as #trait_path>::FIELDS),
+            "#[pcd(pod)] record must not contain padding bytes"
+        );
+    };
+
+    Ok(Some(tokens))
+}
+
+/// Checks whether the struct is annotated with `#[repr(C)]`.
+pub fn is_repr_c(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut repr_c = false;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("C") {
+                repr_c = true;
+            }
+
+            // Skip the arguments of align(N) and packed(N).
+            if meta.input.peek(syn::token::Paren) {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                content.parse::<TokenStream>()?;
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(repr_c)
+}
+
+fn parse_pcd_attributes(attrs: &[Attribute]) -> syn::Result<Vec<AttrOption>> {
+    let options: Vec<_> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("pcd"))
+        .map(|attr| {
+            if attr.style == AttrStyle::Outer {
+                Ok(attr)
+            } else {
+                Err(Error::new(
+                    attr.span(),
+                    "inner pcd attribute is not supported",
+                ))
+            }
+        })
+        .map(|attr| -> syn::Result<_> {
+            let attr = attr?;
+            let attr_list: AttrList = attr.parse_args()?;
+            Ok(attr_list)
+        })
+        .try_collect()?;
+    let options: Vec<AttrOption> = options.into_iter().flat_map(|list| list.options).collect();
+    Ok(options)
+}
+
+/// The shape of a struct field type.
+pub enum FieldShape {
+    Scalar,
+    Array(Expr),
+    Vec,
+}
+
+/// Parses the field type into the element type and its shape.
+pub fn parse_field_shape(ty: &Type) -> Option<(Ident, FieldShape)> {
+    match ty {
+        Type::Array(array) => {
+            let elem = match &*array.elem {
+                Type::Path(path) => path.path.get_ident()?.clone(),
+                _ => return None,
+            };
+            Some((elem, FieldShape::Array(array.len.clone())))
+        }
+        Type::Path(path) => {
+            if let Some(ident) = path.path.get_ident() {
+                return Some((ident.clone(), FieldShape::Scalar));
+            }
+
+            let last = path.path.segments.last()?;
+            if last.ident != "Vec" {
+                return None;
+            }
+
+            let args = match &last.arguments {
+                PathArguments::AngleBracketed(args) => &args.args,
+                _ => return None,
+            };
+            if args.len() != 1 {
+                return None;
+            }
+
+            let elem = match &args[0] {
+                GenericArgument::Type(Type::Path(path)) => path.path.get_ident()?.clone(),
+                _ => return None,
+            };
+            Some((elem, FieldShape::Vec))
+        }
+        _ => None,
+    }
+}
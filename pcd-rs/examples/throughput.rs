@@ -0,0 +1,214 @@
+//! Measures reading and writing throughput on 1M-point clouds.
+//!
+//! The derived record, which decodes from and encodes into byte slices,
+//! is compared with a record implemented against the reader- and
+//! writer-based methods only, which reads and writes each scalar
+//! through the I/O traits. Both read binary points from a buffer of
+//! the whole point, so the gap is mostly in ASCII data, where the
+//! latter allocates a line and a token list per point.
+//!
+//! Run it in release mode:
+//!
+//! ```sh
+//! cargo run --release --features derive --example throughput
+//! ```
+
+use eyre::Result;
+use pcd_rs::{
+    byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt},
+    DataKind, DynReader, DynRecord, Error, PcdDeserialize, PcdSerialize, Reader, Schema,
+    StaticFieldDef, WriterInit,
+};
+use std::{
+    io::{prelude::*, Cursor},
+    time::{Duration, Instant},
+};
+
+const NUM_POINTS: usize = 1_000_000;
+/// The best time of this many runs is reported.
+const NUM_RUNS: usize = 3;
+
+#[derive(Debug, Clone, PcdDeserialize, PcdSerialize, PartialEq)]
+pub struct Point {
+    x: f32,
+    y: f32,
+    z: f32,
+    rgb: u32,
+    normal: [f32; 3],
+}
+
+/// The point implemented with the reader- and writer-based methods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegacyPoint(Point);
+
+impl PcdDeserialize for LegacyPoint {
+    const FIELDS: &'static [StaticFieldDef] = <Point as PcdDeserialize>::FIELDS;
+
+    fn is_dynamic() -> bool {
+        false
+    }
+
+    fn read_chunk<R: BufRead>(reader: &mut R, _: &Schema) -> pcd_rs::Result<Self> {
+        Ok(Self(Point {
+            x: reader.read_f32::<LittleEndian>()?,
+            y: reader.read_f32::<LittleEndian>()?,
+            z: reader.read_f32::<LittleEndian>()?,
+            rgb: reader.read_u32::<LittleEndian>()?,
+            normal: [
+                reader.read_f32::<LittleEndian>()?,
+                reader.read_f32::<LittleEndian>()?,
+                reader.read_f32::<LittleEndian>()?,
+            ],
+        }))
+    }
+
+    fn read_line<R: BufRead>(reader: &mut R, _: &Schema) -> pcd_rs::Result<Self> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let tokens: Vec<&str> = line.split_ascii_whitespace().collect();
+        if tokens.len() != 7 {
+            return Err(Error::new_text_token_mismatch_error(7, tokens.len()));
+        }
+
+        Ok(Self(Point {
+            x: tokens[0].parse()?,
+            y: tokens[1].parse()?,
+            z: tokens[2].parse()?,
+            rgb: tokens[3].parse()?,
+            normal: [tokens[4].parse()?, tokens[5].parse()?, tokens[6].parse()?],
+        }))
+    }
+}
+
+impl PcdSerialize for LegacyPoint {
+    const FIELDS: &'static [StaticFieldDef] = <Point as PcdSerialize>::FIELDS;
+
+    fn is_dynamic() -> bool {
+        false
+    }
+
+    fn write_chunk<R: Write>(&self, writer: &mut R, _: &Schema) -> pcd_rs::Result<()> {
+        let Point {
+            x,
+            y,
+            z,
+            rgb,
+            normal,
+        } = &self.0;
+        for value in [x, y, z] {
+            writer.write_f32::<LittleEndian>(*value)?;
+        }
+        writer.write_u32::<LittleEndian>(*rgb)?;
+        for value in normal {
+            writer.write_f32::<LittleEndian>(*value)?;
+        }
+        Ok(())
+    }
+
+    fn write_line<R: Write>(&self, writer: &mut R, _: &Schema) -> pcd_rs::Result<()> {
+        let Point {
+            x,
+            y,
+            z,
+            rgb,
+            normal: [nx, ny, nz],
+        } = &self.0;
+        writeln!(writer, "{x} {y} {z} {rgb} {nx} {ny} {nz}")?;
+        Ok(())
+    }
+}
+
+/// Writes the points and reads them back, returning the best times
+/// taken.
+fn measure<T>(points: &[T], data_kind: DataKind) -> Result<(Duration, Duration)>
+where
+    T: PcdSerialize + PcdDeserialize,
+{
+    let mut best = (Duration::MAX, Duration::MAX);
+    for _ in 0..NUM_RUNS {
+        let (write_time, read_time) = measure_once(points, data_kind)?;
+        best = (best.0.min(write_time), best.1.min(read_time));
+    }
+    Ok(best)
+}
+
+fn measure_once<T>(points: &[T], data_kind: DataKind) -> Result<(Duration, Duration)>
+where
+    T: PcdSerialize + PcdDeserialize,
+{
+    let mut buffer = vec![];
+
+    let instant = Instant::now();
+    let mut writer = WriterInit {
+        width: NUM_POINTS as u64,
+        height: 1,
+        viewpoint: Default::default(),
+        data_kind,
+        schema: None,
+    }
+    .build_from_writer(Cursor::new(&mut buffer))?;
+    for point in points {
+        writer.push(point)?;
+    }
+    writer.finish()?;
+    let write_time = instant.elapsed();
+
+    let instant = Instant::now();
+    let loaded: Vec<T> = Reader::from_bytes(&buffer)?.collect::<Result<_, _>>()?;
+    let read_time = instant.elapsed();
+    assert_eq!(loaded.len(), NUM_POINTS);
+
+    Ok((write_time, read_time))
+}
+
+pub fn main() -> Result<()> {
+    let points: Vec<_> = (0..NUM_POINTS)
+        .map(|idx| {
+            let t = idx as f32 * 1e-3;
+            Point {
+                x: t.sin(),
+                y: t.cos(),
+                z: t,
+                rgb: idx as u32,
+                normal: [0.0, 0.5, 1.0],
+            }
+        })
+        .collect();
+    let legacy_points: Vec<_> = points.iter().cloned().map(LegacyPoint).collect();
+
+    println!("Times of the reader- and writer-based methods -> the slice-based methods");
+    for data_kind in [
+        DataKind::Binary,
+        DataKind::BinaryCompressed,
+        DataKind::Ascii,
+    ] {
+        let (legacy_write_time, legacy_read_time) = measure(&legacy_points, data_kind)?;
+        let (write_time, read_time) = measure(&points, data_kind)?;
+
+        let mut buffer = vec![];
+        let mut writer = WriterInit {
+            width: NUM_POINTS as u64,
+            height: 1,
+            viewpoint: Default::default(),
+            data_kind,
+            schema: None,
+        }
+        .build_from_writer(Cursor::new(&mut buffer))?;
+        for point in &points {
+            writer.push(point)?;
+        }
+        writer.finish()?;
+
+        let instant = Instant::now();
+        let loaded: Vec<DynRecord> = DynReader::from_bytes(&buffer)?.collect::<Result<_, _>>()?;
+        let dyn_read_time = instant.elapsed();
+        assert_eq!(loaded.len(), NUM_POINTS);
+
+        println!("{data_kind:?}:");
+        println!("  write {legacy_write_time:.2?} -> {write_time:.2?}");
+        println!("  read {legacy_read_time:.2?} -> {read_time:.2?}");
+        println!("  dynamic read {dyn_read_time:.2?}");
+    }
+
+    Ok(())
+}
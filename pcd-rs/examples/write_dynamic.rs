@@ -7,9 +7,9 @@ fn main() -> Result<()> {
     let path = "test_files/dump_ascii_untyped.pcd";
 
     // point data
-    let dump_points = vec![
+    let dump_points = [
         DynRecord(vec![
-            Field::F32(vec![3.14159]),
+            Field::F32(vec![3.5]),
             Field::U8(vec![2, 1, 7]),
             Field::I32(vec![-5]),
         ]),
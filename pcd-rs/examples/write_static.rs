@@ -14,9 +14,9 @@ pub fn main() -> Result<()> {
     let path = "test_files/dump_ascii_static.pcd";
 
     // point data
-    let dump_points = vec![
+    let dump_points = [
         Point {
-            x: 3.14159,
+            x: 3.5,
             y: [2, 1, 7],
             z: -5,
         },
@@ -43,7 +43,7 @@ pub fn main() -> Result<()> {
     .create::<Point, _>(path)?;
 
     for point in dump_points.iter() {
-        writer.push(&point)?;
+        writer.push(point)?;
     }
 
     writer.finish()?;
@@ -0,0 +1,493 @@
+//! Asynchronous reader and writer on tokio I/O, enabled by the `async`
+//! feature.
+//!
+//! [AsyncReader] loads points as a [Stream] from an
+//! [AsyncBufRead], and [AsyncWriter] writes points to an
+//! [AsyncWrite] + [AsyncSeek] writer. Both support all data kinds.
+//!
+//! ```rust
+//! use futures::TryStreamExt;
+//! use pcd_rs::{async_io::DynAsyncReader, DynRecord};
+//!
+//! # async fn run() -> pcd_rs::Result<()> {
+//! let reader = DynAsyncReader::open("test_files/ascii.pcd").await?;
+//! let points: Vec<DynRecord> = reader.try_collect().await?;
+//! assert_eq!(points.len(), 213);
+//! # Ok(())
+//! # }
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(run()).unwrap();
+//! ```
+
+use crate::{
+    error::{Error, LimitKind},
+    lzf,
+    metas::{DataKind, PcdMeta, Schema},
+    reader::{check_compressed_sizes, check_schema, point_error, ReaderLimits},
+    record::{DynRecord, PcdDeserialize, PcdSerialize, TokenWriter},
+    traits::TokenFormat,
+    writer::{compress_data, points_arg, AsciiFormat, FormattedWriterInit, Header, WriterInit},
+    Result,
+};
+use futures_core::Stream;
+use std::{
+    io::{self, Cursor, SeekFrom},
+    marker::PhantomData,
+    path::Path,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::{
+    fs::File,
+    io::{
+        AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncSeek, AsyncSeekExt, AsyncWrite,
+        AsyncWriteExt, BufReader, BufWriter,
+    },
+};
+
+/// The `DynAsyncReader` struct loads points with schema determined in
+/// runtime.
+pub type DynAsyncReader<R> = AsyncReader<DynRecord, R>;
+
+/// The `DynAsyncWriter` struct writes points with schema determined in
+/// runtime.
+pub type DynAsyncWriter<W> = AsyncWriter<DynRecord, W>;
+
+/// The `AsyncReader<T, R>` struct loads points into type `T` from
+/// asynchronous reader `R`.
+pub struct AsyncReader<T, R> {
+    meta: PcdMeta,
+    reader: R,
+    record_count: usize,
+    finished: bool,
+    decompressed_buffer: Option<Cursor<Vec<u8>>>,
+    /// The bytes of the current binary point or ASCII line.
+    chunk: Vec<u8>,
+    /// The number of bytes filled in the binary chunk.
+    filled: usize,
+    max_line_length: usize,
+    /// The byte offset of the next ASCII line in the data section.
+    data_offset: u64,
+    _phantom: PhantomData<fn() -> T>,
+}
+
+impl<Record, R> AsyncReader<Record, R>
+where
+    Record: PcdDeserialize,
+    R: AsyncBufRead + Unpin,
+{
+    pub async fn from_reader(reader: R) -> Result<Self> {
+        Self::from_reader_with_limits(reader, ReaderLimits::default()).await
+    }
+
+    /// Creates a reader which checks the input against the limits.
+    pub async fn from_reader_with_limits(mut reader: R, limits: ReaderLimits) -> Result<Self> {
+        // Collect the header lines and parse them with the sync loader.
+        let mut header = String::new();
+        let line_limit = (limits.max_line_length as u64).saturating_add(1);
+        for _ in 0..limits.max_header_lines {
+            let begin = header.len();
+            let read_size = (&mut reader)
+                .take(line_limit)
+                .read_line(&mut header)
+                .await?;
+            if read_size == 0 {
+                break;
+            }
+            let line = &header[begin..];
+            if read_size as u64 == line_limit && !line.ends_with('\n') {
+                return Err(Error::new_limit_exceeded_error(
+                    LimitKind::LineLength,
+                    limits.max_line_length as u64,
+                    line_limit,
+                ));
+            }
+            let entry = line.split('#').next().unwrap_or("");
+            if entry.split_ascii_whitespace().next() == Some("DATA") {
+                break;
+            }
+        }
+
+        let mut line_count = 0;
+        let meta = crate::utils::load_meta(&mut header.as_bytes(), &mut line_count, &limits)?;
+        check_schema::<Record>(&meta)?;
+
+        // For compressed data, read and decompress the entire data section
+        let decompressed_buffer = if meta.data == DataKind::BinaryCompressed {
+            let compressed_size = reader.read_u32_le().await?;
+            let uncompressed_size = reader.read_u32_le().await?;
+            check_compressed_sizes(compressed_size, uncompressed_size, &limits)?;
+
+            if compressed_size == 0 && uncompressed_size == 0 {
+                Some(Cursor::new(Vec::new()))
+            } else {
+                let mut compressed_data = vec![0u8; compressed_size as usize];
+                reader.read_exact(&mut compressed_data).await?;
+                let decompressed = lzf::decompress(&compressed_data, uncompressed_size as usize)?;
+                Some(Cursor::new(decompressed))
+            }
+        } else {
+            None
+        };
+
+        let chunk = match meta.data {
+            DataKind::Ascii => vec![],
+            _ => vec![0; meta.field_defs.point_size()],
+        };
+
+        Ok(Self {
+            meta,
+            reader,
+            record_count: 0,
+            finished: false,
+            decompressed_buffer,
+            chunk,
+            filled: 0,
+            max_line_length: limits.max_line_length,
+            data_offset: 0,
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<Record> AsyncReader<Record, BufReader<File>>
+where
+    Record: PcdDeserialize,
+{
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_limits(path, ReaderLimits::default()).await
+    }
+
+    /// Opens a file with a reader which checks it against the limits.
+    pub async fn open_with_limits(path: impl AsRef<Path>, limits: ReaderLimits) -> Result<Self> {
+        let file = BufReader::new(File::open(path.as_ref()).await?);
+        Self::from_reader_with_limits(file, limits).await
+    }
+}
+
+impl<Record, R> AsyncReader<Record, R> {
+    /// Get meta data.
+    pub fn meta(&self) -> &PcdMeta {
+        &self.meta
+    }
+}
+
+impl<Record, R> AsyncReader<Record, R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    /// Reads an ASCII line, including the line break, into the chunk.
+    fn poll_line(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        loop {
+            if self.chunk.len() > self.max_line_length {
+                return Poll::Ready(Err(Error::new_limit_exceeded_error(
+                    LimitKind::LineLength,
+                    self.max_line_length as u64,
+                    self.chunk.len() as u64,
+                )));
+            }
+
+            let buf = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+            if buf.is_empty() {
+                return Poll::Ready(Ok(()));
+            }
+
+            // Stop just after the limit to detect long lines.
+            let remaining = (self.max_line_length - self.chunk.len()).saturating_add(1);
+            let (amount, done) = match buf.iter().position(|&byte| byte == b'\n') {
+                Some(index) if index < remaining => (index + 1, true),
+                _ => (buf.len().min(remaining), false),
+            };
+            self.chunk.extend_from_slice(&buf[..amount]);
+            Pin::new(&mut self.reader).consume(amount);
+
+            if done {
+                return Poll::Ready(Ok(()));
+            }
+        }
+    }
+
+    /// Fills the chunk with the bytes of a binary point.
+    fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.filled < self.chunk.len() {
+            let buf = ready!(Pin::new(&mut self.reader).poll_fill_buf(cx))?;
+            if buf.is_empty() {
+                return Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()));
+            }
+
+            let amount = buf.len().min(self.chunk.len() - self.filled);
+            self.chunk[self.filled..self.filled + amount].copy_from_slice(&buf[..amount]);
+            Pin::new(&mut self.reader).consume(amount);
+            self.filled += amount;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Record, R> Stream for AsyncReader<Record, R>
+where
+    Record: PcdDeserialize,
+    R: AsyncBufRead + Unpin,
+{
+    type Item = Result<Record>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.finished {
+            return Poll::Ready(None);
+        }
+
+        // Check if we've already read all points or if there are no points
+        if this.record_count >= this.meta.num_points as usize {
+            this.finished = true;
+            return Poll::Ready(None);
+        }
+
+        let index = this.record_count;
+        let byte_offset = match &this.decompressed_buffer {
+            _ if this.meta.data == DataKind::Ascii => this.data_offset,
+            Some(buffer) => buffer.position(),
+            None => (index * this.chunk.len()) as u64,
+        };
+
+        let record_result = match this.meta.data {
+            DataKind::Ascii => {
+                let result = ready!(this.poll_line(cx));
+                let schema = &this.meta.field_defs;
+                let line = std::str::from_utf8(&this.chunk);
+                let record = result
+                    .and_then(|()| {
+                        let line = line.map_err(|error| {
+                            io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+                        })?;
+                        Record::decode_line(line, schema)
+                    })
+                    .map_err(|error| point_error(error, index, byte_offset, line.ok(), schema));
+                this.data_offset += this.chunk.len() as u64;
+                this.chunk.clear();
+                record
+            }
+            DataKind::Binary => {
+                let result = ready!(this.poll_chunk(cx));
+                let schema = &this.meta.field_defs;
+                this.filled = 0;
+                result
+                    .map_err(Into::into)
+                    .and_then(|()| Record::decode(&this.chunk, schema))
+                    .map_err(|error| point_error(error, index, byte_offset, None, schema))
+            }
+            DataKind::BinaryCompressed => {
+                let schema = &this.meta.field_defs;
+                let buffer = this
+                    .decompressed_buffer
+                    .as_mut()
+                    .expect("compressed data buffer is initialized");
+                let begin = buffer.position() as usize;
+                let end = begin + this.chunk.len();
+                let len = buffer.get_ref().len();
+                if end <= len {
+                    buffer.set_position(end as u64);
+                    Record::decode(&buffer.get_ref()[begin..end], schema)
+                } else {
+                    Err(Error::new_truncated_data_error(end as u64, len as u64))
+                }
+                .map_err(|error| point_error(error, index, byte_offset, None, schema))
+            }
+        };
+
+        match record_result {
+            Ok(_) => {
+                this.record_count += 1;
+                if this.record_count == this.meta.num_points as usize {
+                    this.finished = true;
+                }
+            }
+            Err(_) => {
+                this.finished = true;
+            }
+        }
+
+        Poll::Ready(Some(record_result))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = (self.meta.num_points as usize).saturating_sub(self.record_count);
+        (size, Some(size))
+    }
+}
+
+impl WriterInit {
+    /// Builds new [AsyncWriter] object from an asynchronous writer.
+    pub async fn build_from_async_writer<Record, W>(
+        self,
+        writer: W,
+    ) -> Result<AsyncWriter<Record, W>>
+    where
+        Record: PcdSerialize,
+        W: AsyncWrite + AsyncSeek + Unpin,
+    {
+        self.with_ascii_format(AsciiFormat::default())
+            .build_from_async_writer(writer)
+            .await
+    }
+
+    /// Builds new [AsyncWriter] by creating a new file.
+    pub async fn create_async<Record, P>(
+        self,
+        path: P,
+    ) -> Result<AsyncWriter<Record, BufWriter<File>>>
+    where
+        Record: PcdSerialize,
+        P: AsRef<Path>,
+    {
+        self.with_ascii_format(AsciiFormat::default())
+            .create_async(path)
+            .await
+    }
+}
+
+impl FormattedWriterInit {
+    /// Builds new [AsyncWriter] object from an asynchronous writer.
+    pub async fn build_from_async_writer<Record, W>(
+        self,
+        writer: W,
+    ) -> Result<AsyncWriter<Record, W>>
+    where
+        Record: PcdSerialize,
+        W: AsyncWrite + AsyncSeek + Unpin,
+    {
+        let (data_kind, record_spec, header) = self.prepare::<Record>("0.7")?;
+        AsyncWriter::new(data_kind, record_spec, header, writer).await
+    }
+
+    /// Builds new [AsyncWriter] by creating a new file.
+    pub async fn create_async<Record, P>(
+        self,
+        path: P,
+    ) -> Result<AsyncWriter<Record, BufWriter<File>>>
+    where
+        Record: PcdSerialize,
+        P: AsRef<Path>,
+    {
+        // The file is not created if the configuration is invalid.
+        let (data_kind, record_spec, header) = self.prepare::<Record>("0.7")?;
+        let writer = BufWriter::new(File::create(path.as_ref()).await?);
+        AsyncWriter::new(data_kind, record_spec, header, writer).await
+    }
+}
+
+/// The `AsyncWriter` struct writes points in type `T` to asynchronous
+/// writer `W`.
+pub struct AsyncWriter<T, W>
+where
+    W: AsyncWrite + AsyncSeek + Unpin,
+{
+    data_kind: DataKind,
+    record_spec: Schema,
+    writer: W,
+    num_records: usize,
+    points_arg_begin: u64,
+    points_arg_width: usize,
+    compressed_buffer: Option<Vec<u8>>,
+    /// Reused for encoding each point.
+    chunk: Vec<u8>,
+    token_format: TokenFormat,
+    /// The decimal places of each token in an ASCII line.
+    token_precisions: Vec<Option<usize>>,
+    _phantom: PhantomData<fn(&T)>,
+}
+
+impl<W, Record> AsyncWriter<Record, W>
+where
+    Record: PcdSerialize,
+    W: AsyncWrite + AsyncSeek + Unpin,
+{
+    async fn new(
+        data_kind: DataKind,
+        record_spec: Schema,
+        header: Header,
+        mut writer: W,
+    ) -> Result<Self> {
+        let Header {
+            bytes,
+            points_arg_offset,
+            points_arg_width,
+            token_format,
+            token_precisions,
+            ..
+        } = header;
+
+        let points_arg_begin = writer.stream_position().await? + points_arg_offset;
+        writer.write_all(&bytes).await?;
+
+        let compressed_buffer = if data_kind == DataKind::BinaryCompressed {
+            Some(Vec::new())
+        } else {
+            None
+        };
+
+        Ok(Self {
+            data_kind,
+            record_spec,
+            writer,
+            num_records: 0,
+            points_arg_begin,
+            points_arg_width,
+            compressed_buffer,
+            chunk: vec![],
+            token_format,
+            token_precisions,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Finish the writer.
+    ///
+    /// The method consumes the writer must be called once when finished.
+    /// Unlike [Writer](crate::Writer), the writer does not panic when it
+    /// drops, since a future may be cancelled at any await point, but
+    /// the output is incomplete without the POINTS in the header.
+    pub async fn finish(mut self) -> Result<()> {
+        if let Some(ref uncompressed_data) = self.compressed_buffer {
+            let data = compress_data(uncompressed_data)?;
+            self.writer.write_all(&data).await?;
+        }
+
+        // Update the points count in the header
+        self.writer
+            .seek(SeekFrom::Start(self.points_arg_begin))
+            .await?;
+        self.writer
+            .write_all(points_arg(self.num_records, self.points_arg_width).as_bytes())
+            .await?;
+        self.writer.flush().await?;
+        Ok(())
+    }
+
+    /// Writes a new point to PCD data.
+    pub async fn push(&mut self, record: &Record) -> Result<()> {
+        match (self.data_kind, &mut self.compressed_buffer) {
+            (DataKind::Ascii, _) => {
+                self.chunk.clear();
+                let mut tokens = TokenWriter::with_format(
+                    &mut self.chunk,
+                    self.token_format,
+                    &self.token_precisions,
+                );
+                record.encode_tokens(&mut tokens, &self.record_spec)?;
+                tokens.finish();
+                self.writer.write_all(&self.chunk).await?;
+            }
+            // Buffer the binary data for compression
+            (_, Some(buffer)) => record.encode(buffer, &self.record_spec)?,
+            (_, None) => {
+                self.chunk.clear();
+                record.encode(&mut self.chunk, &self.record_spec)?;
+                self.writer.write_all(&self.chunk).await?;
+            }
+        }
+
+        self.num_records += 1;
+        Ok(())
+    }
+}
@@ -0,0 +1,328 @@
+//! Concatenating PCD files with the same schema.
+//!
+//! [concat()] merges the points of several files into one unorganized
+//! point cloud. The points are copied as bytes without decoding.
+//!
+//! ```rust
+//! # fn main() -> pcd_rs::Result<()> {
+//! use pcd_rs::{concat::ConcatInit, DataKind};
+//! use std::{fs::File, io::BufWriter};
+//!
+//! let output = BufWriter::new(File::create("test_files/concat_doc.pcd")?);
+//! let num_points = pcd_rs::concat(
+//!     ["test_files/binary.pcd", "test_files/binary.pcd"],
+//!     ConcatInit {
+//!         writer: output,
+//!         data_kind: DataKind::Binary,
+//!         ascii_format: Default::default(),
+//!         transform_viewpoint: false,
+//!     },
+//! )?;
+//! assert_eq!(num_points, 2 * 28944);
+//! # std::fs::remove_file("test_files/concat_doc.pcd")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    metas::{DataKind, PcdMeta, Schema, ValueKind, ViewPoint},
+    reader::DynReader,
+    record::RawPoint,
+    writer::{AsciiFormat, DynWriter, WriterInit},
+    Error, Result,
+};
+use byteorder::{ByteOrder, LittleEndian};
+use std::{
+    fs::File,
+    io::{prelude::*, SeekFrom},
+    path::Path,
+};
+
+/// The number of points copied at once from binary inputs.
+const COPY_BATCH_POINTS: usize = 4096;
+
+/// The output configuration of [concat()].
+pub struct ConcatInit<W> {
+    /// The output, which must implement both [Write] and [Seek].
+    pub writer: W,
+    /// The data kind of the output.
+    pub data_kind: DataKind,
+    /// The formatting of floats in ASCII output.
+    pub ascii_format: AsciiFormat,
+    /// Transforms the `x`, `y` and `z` fields of each input by its
+    /// VIEWPOINT into the common frame, and writes the identity
+    /// VIEWPOINT. Otherwise, the inputs must have the same VIEWPOINT,
+    /// which is written to the output.
+    pub transform_viewpoint: bool,
+}
+
+/// Concatenates the points of the input files in order, returning the
+/// number of points written.
+///
+/// The inputs must have the same fields by name, kind and count, which
+/// is checked before anything is written. They can have any data kind
+/// and version. The output is an unorganized point cloud, whose WIDTH
+/// equals POINTS and HEIGHT is 1.
+///
+/// The data sections of binary inputs are copied in chunks. The data
+/// of binary_compressed inputs is decompressed, and ASCII inputs are
+/// parsed into bytes. For binary_compressed output, all points are
+/// compressed at once on finish.
+///
+/// If an input is broken, the points copied before the error are kept
+/// in the output, whose WIDTH and POINTS are set to their number, and
+/// the error is returned.
+pub fn concat<I, P, W>(inputs: I, output: ConcatInit<W>) -> Result<u64>
+where
+    I: IntoIterator<Item = P>,
+    P: AsRef<Path>,
+    W: Write + Seek,
+{
+    let ConcatInit {
+        writer,
+        data_kind,
+        ascii_format,
+        transform_viewpoint,
+    } = output;
+
+    let inputs: Vec<(P, PcdMeta, u64)> = inputs
+        .into_iter()
+        .map(|path| {
+            let (meta, data_offset) = PcdMeta::open(path.as_ref())?;
+            Ok((path, meta, data_offset))
+        })
+        .collect::<Result<_>>()?;
+    let Some((_, first, _)) = inputs.first() else {
+        return Err(Error::new_invalid_argument_error(
+            "no input files are given",
+        ));
+    };
+
+    let schema = first.field_defs.clone();
+    for (_, meta, _) in &inputs[1..] {
+        if meta.field_defs != schema {
+            return Err(Error::new_reader_schema_mismatch_error(
+                schema.diff(&meta.field_defs),
+            ));
+        }
+    }
+
+    let (viewpoint, transforms) = if transform_viewpoint {
+        let transforms = inputs
+            .iter()
+            .map(|(_, meta, _)| Transform::new(&schema, &meta.viewpoint).map(Some))
+            .collect::<Result<Vec<_>>>()?;
+        (ViewPoint::default(), transforms)
+    } else {
+        if inputs
+            .iter()
+            .any(|(_, meta, _)| meta.viewpoint != first.viewpoint)
+        {
+            return Err(Error::new_invalid_argument_error(
+                "the inputs have different VIEWPOINTs, which requires transform_viewpoint",
+            ));
+        }
+        (first.viewpoint.clone(), vec![None; inputs.len()])
+    };
+
+    let num_points = inputs
+        .iter()
+        .try_fold(0u64, |sum, (_, meta, _)| sum.checked_add(meta.num_points))
+        .ok_or_else(|| Error::new_invalid_argument_error("the total POINTS overflows"))?;
+
+    let mut writer: DynWriter<W> = WriterInit {
+        width: num_points,
+        height: 1,
+        viewpoint,
+        data_kind,
+        schema: Some(schema.clone()),
+    }
+    .with_ascii_format(ascii_format)
+    .build_from_writer(writer)?;
+
+    let mut output = Output {
+        writer: &mut writer,
+        data_kind,
+        schema: &schema,
+        offsets: schema.offsets(),
+    };
+    let result =
+        inputs
+            .iter()
+            .zip(&transforms)
+            .try_for_each(|((path, meta, data_offset), transform)| {
+                output.copy_points(path.as_ref(), meta, *data_offset, transform.as_ref())
+            });
+    let num_written = writer.num_records() as u64;
+
+    match result {
+        Ok(()) => {
+            writer.finish()?;
+            Ok(num_written)
+        }
+        Err(error) => {
+            // WIDTH is set to the points copied before the error. The
+            // input error is returned even if finishing fails.
+            let _ = writer.finish_unorganized();
+            Err(error)
+        }
+    }
+}
+
+/// The writer and the layout of the output points.
+struct Output<'a, W>
+where
+    W: Write + Seek,
+{
+    writer: &'a mut DynWriter<W>,
+    data_kind: DataKind,
+    schema: &'a Schema,
+    offsets: Vec<usize>,
+}
+
+impl<W> Output<'_, W>
+where
+    W: Write + Seek,
+{
+    /// Copies all points of an input file.
+    fn copy_points(
+        &mut self,
+        path: &Path,
+        meta: &PcdMeta,
+        data_offset: u64,
+        transform: Option<&Transform>,
+    ) -> Result<()> {
+        let mut chunk = vec![];
+
+        if meta.data == DataKind::Binary {
+            let mut file = File::open(path)?;
+            file.seek(SeekFrom::Start(data_offset))?;
+
+            let mut remaining = meta.num_points as usize;
+            while remaining > 0 {
+                let count = remaining.min(COPY_BATCH_POINTS);
+                chunk.resize(count * meta.point_size(), 0);
+                file.read_exact(&mut chunk)?;
+                self.push_points(&mut chunk, transform)?;
+                remaining -= count;
+            }
+        } else {
+            let mut reader = DynReader::open(path)?;
+            let mut points = reader.raw_points();
+            while let Some(point) = points.next() {
+                chunk.clear();
+                chunk.extend_from_slice(point?.bytes());
+                self.push_points(&mut chunk, transform)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Transforms the points in the chunk if requested and writes them.
+    fn push_points(&mut self, chunk: &mut [u8], transform: Option<&Transform>) -> Result<()> {
+        let point_size = self.schema.point_size();
+
+        if let Some(transform) = transform {
+            for point in chunk.chunks_exact_mut(point_size) {
+                transform.apply(point);
+            }
+        }
+
+        match self.data_kind {
+            DataKind::Binary | DataKind::BinaryCompressed => self.writer.push_binary(chunk),
+            DataKind::Ascii => chunk.chunks_exact(point_size).try_for_each(|point| {
+                let point = RawPoint::new(point, self.schema, &self.offsets);
+                self.writer.push_raw(&point)
+            }),
+        }
+    }
+}
+
+/// Transforms the `x`, `y` and `z` fields of binary points by a
+/// VIEWPOINT, that is, rotates them and then translates them.
+#[derive(Debug, Clone)]
+struct Transform {
+    rotation: [[f64; 3]; 3],
+    translation: [f64; 3],
+    /// The offset and kind of the `x`, `y` and `z` fields.
+    fields: [(usize, ValueKind); 3],
+}
+
+impl Transform {
+    fn new(schema: &Schema, viewpoint: &ViewPoint) -> Result<Self> {
+        let offsets = schema.offsets();
+        let mut fields = [(0, ValueKind::F32); 3];
+
+        for (field, name) in fields.iter_mut().zip(["x", "y", "z"]) {
+            let index = schema
+                .index_of(name)
+                .ok_or_else(|| Error::new_field_not_found_error(name))?;
+            let def = &schema[index];
+
+            if def.count != 1 || !matches!(def.kind, ValueKind::F32 | ValueKind::F64) {
+                let desc = format!("field {name:?} must be a single F32 or F64 to be transformed");
+                return Err(Error::new_invalid_argument_error(&desc));
+            }
+            *field = (offsets[index], def.kind);
+        }
+
+        let ViewPoint {
+            tx,
+            ty,
+            tz,
+            qw,
+            qx,
+            qy,
+            qz,
+        } = *viewpoint;
+        let norm = (qw * qw + qx * qx + qy * qy + qz * qz).sqrt();
+        if !norm.is_normal() {
+            return Err(Error::new_invalid_argument_error(
+                "the VIEWPOINT quaternion must be nonzero",
+            ));
+        }
+        let [w, x, y, z] = [qw / norm, qx / norm, qy / norm, qz / norm];
+
+        let rotation = [
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - z * w),
+                2.0 * (x * z + y * w),
+            ],
+            [
+                2.0 * (x * y + z * w),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - x * w),
+            ],
+            [
+                2.0 * (x * z - y * w),
+                2.0 * (y * z + x * w),
+                1.0 - 2.0 * (x * x + y * y),
+            ],
+        ];
+
+        Ok(Self {
+            rotation,
+            translation: [tx, ty, tz],
+            fields,
+        })
+    }
+
+    fn apply(&self, point: &mut [u8]) {
+        let input = self.fields.map(|(offset, kind)| match kind {
+            ValueKind::F64 => LittleEndian::read_f64(&point[offset..]),
+            _ => LittleEndian::read_f32(&point[offset..]) as f64,
+        });
+
+        for ((row, translation), (offset, kind)) in
+            self.rotation.iter().zip(self.translation).zip(self.fields)
+        {
+            let value = row[0] * input[0] + row[1] * input[1] + row[2] * input[2] + translation;
+            match kind {
+                ValueKind::F64 => LittleEndian::write_f64(&mut point[offset..], value),
+                _ => LittleEndian::write_f32(&mut point[offset..], value as f32),
+            }
+        }
+    }
+}
@@ -0,0 +1,253 @@
+//! Editing the header of a PCD file without rewriting the points.
+//!
+//! [edit_header()] loads the header of a file, lets a closure modify
+//! the [PcdMeta], and writes the header back. The point data is
+//! neither decoded nor modified, though it is moved if the header
+//! grows.
+//!
+//! ```rust
+//! # fn main() -> pcd_rs::Result<()> {
+//! # std::fs::copy("test_files/ascii.pcd", "test_files/edit_doc.pcd")?;
+//! use pcd_rs::ViewPoint;
+//!
+//! pcd_rs::edit_header("test_files/edit_doc.pcd", |meta| {
+//!     meta.viewpoint = ViewPoint {
+//!         tx: 1.0,
+//!         ..Default::default()
+//!     };
+//! })?;
+//! # std::fs::remove_file("test_files/edit_doc.pcd")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{metas::PcdMeta, Error, Result};
+use std::{
+    collections::hash_map::RandomState,
+    fs::{self, File, OpenOptions},
+    hash::{BuildHasher, Hasher},
+    io::{self, prelude::*, BufWriter, SeekFrom},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The width the WIDTH and POINTS arguments are padded to, so that
+/// [Writer::append()](crate::writer::Writer::append) can update them
+/// in place with any count.
+const ARG_WIDTH: usize = 20;
+
+/// Edits the header of a PCD file.
+///
+/// The closure modifies the metadata loaded from the file. The DATA
+/// kind and the kinds and counts of the fields must be kept, since
+/// they determine how the point data is read. Everything else,
+/// including field names, can be changed. The new header must be
+/// valid, for example binary_compressed data requires version 0.7.
+///
+/// The comment lines of the old header are kept at the top of the new
+/// header, while comments after the entries are dropped. If the new
+/// header is not longer than the old one, it is padded with spaces and
+/// overwritten in place, so the point data is not moved. Otherwise, the
+/// point data is shifted towards the end of the file in place, which
+/// leaves a corrupt file if it is interrupted. If the file cannot be
+/// extended in place, for example because it is read-only, it is copied
+/// to a temporary file in the same directory, which then replaces the
+/// original.
+pub fn edit_header<P, F>(path: P, edit: F) -> Result<()>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&mut PcdMeta),
+{
+    let path = path.as_ref();
+    let (old_meta, data_offset) = PcdMeta::open(path)?;
+
+    let mut meta = old_meta.clone();
+    edit(&mut meta);
+    check_edit(&old_meta, &meta)?;
+
+    let mut header = header_comments(path, data_offset)?;
+    header.push_str(&meta.to_string());
+    // Make sure the file is still readable.
+    header.parse::<PcdMeta>()?;
+
+    match (data_offset as usize).checked_sub(header.len()) {
+        Some(pad) => {
+            let header = pad_header(&header, pad);
+            let mut file = OpenOptions::new().write(true).open(path)?;
+            file.write_all(header.as_bytes())?;
+            file.sync_all()?;
+        }
+        None => {
+            let shift = (header.len() as u64) - data_offset;
+            match extend_file(path, shift) {
+                Ok(file) => shift_data(&file, header.as_bytes(), data_offset)?,
+                // Resolve symbolic links, which the rename would replace.
+                Err(_) => rewrite(&fs::canonicalize(path)?, header.as_bytes(), data_offset)?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the comment lines of the header, each with a line break.
+fn header_comments(path: &Path, data_offset: u64) -> Result<String> {
+    let mut header = vec![0; data_offset as usize];
+    File::open(path)?.read_exact(&mut header)?;
+
+    let comments = String::from_utf8_lossy(&header)
+        .lines()
+        .filter(|line| line.trim_start().starts_with('#'))
+        .map(|line| format!("{}\n", line.trim_end()))
+        .collect();
+    Ok(comments)
+}
+
+/// Checks whether the edited header keeps the layout of the data.
+fn check_edit(old_meta: &PcdMeta, meta: &PcdMeta) -> Result<()> {
+    if meta.data != old_meta.data {
+        let desc = format!(
+            "DATA cannot be changed from {} to {} without rewriting the points",
+            old_meta.data, meta.data
+        );
+        return Err(Error::new_invalid_argument_error(&desc));
+    }
+
+    if !meta.field_defs.is_compatible_with(&old_meta.field_defs) {
+        return Err(Error::new_invalid_argument_error(
+            "the kinds and counts of the fields cannot be changed without rewriting the points",
+        ));
+    }
+
+    Ok(())
+}
+
+/// Appends `pad` spaces to the WIDTH and POINTS arguments, so that the
+/// header can be updated by [Writer::append()](crate::writer::Writer::append).
+fn pad_header(header: &str, mut pad: usize) -> String {
+    let mut output = String::with_capacity(header.len() + pad);
+
+    for line in header.lines() {
+        output.push_str(line);
+
+        let spaces = if let Some(arg) = line.strip_prefix("WIDTH ") {
+            ARG_WIDTH.saturating_sub(arg.len()).min(pad)
+        } else if line.starts_with("POINTS ") {
+            pad
+        } else {
+            0
+        };
+        output.extend(std::iter::repeat_n(' ', spaces));
+        pad -= spaces;
+
+        output.push('\n');
+    }
+
+    output
+}
+
+/// The size of the blocks in which the point data is shifted.
+const SHIFT_BLOCK_SIZE: usize = 1 << 16;
+
+/// Opens the file for writing and extends it by `len` bytes.
+fn extend_file(path: &Path, len: u64) -> io::Result<File> {
+    let file = OpenOptions::new().read(true).write(true).open(path)?;
+    let file_len = file.metadata()?.len();
+    let new_len = file_len
+        .checked_add(len)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the file is too large"))?;
+    file.set_len(new_len)?;
+    Ok(file)
+}
+
+/// Moves the data after `data_offset` to the end of the extended file,
+/// from the last block backwards, and writes the longer header before
+/// it.
+fn shift_data(mut file: &File, header: &[u8], data_offset: u64) -> Result<()> {
+    let shift = header.len() as u64 - data_offset;
+    let mut end = file.metadata()?.len() - shift;
+    let mut buffer = vec![0; SHIFT_BLOCK_SIZE];
+
+    while end > data_offset {
+        let begin = end.saturating_sub(SHIFT_BLOCK_SIZE as u64).max(data_offset);
+        let block = &mut buffer[..(end - begin) as usize];
+        file.seek(SeekFrom::Start(begin))?;
+        file.read_exact(block)?;
+        file.seek(SeekFrom::Start(begin + shift))?;
+        file.write_all(block)?;
+        end = begin;
+    }
+
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(header)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Writes the header and the data of the file to a temporary file,
+/// and renames it to the file.
+fn rewrite(path: &Path, header: &[u8], data_offset: u64) -> Result<()> {
+    let mut input = File::open(path)?;
+    input.seek(SeekFrom::Start(data_offset))?;
+
+    let (temp_path, temp) = create_temp_file(path)?;
+    let result = (|| -> Result<()> {
+        let mut writer = BufWriter::new(&temp);
+        writer.write_all(header)?;
+        io::copy(&mut input, &mut writer)?;
+        writer.flush()?;
+        drop(writer);
+
+        temp.sync_all()?;
+        fs::set_permissions(&temp_path, input.metadata()?.permissions())?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// The number of names tried for a temporary file.
+const TEMP_FILE_ATTEMPTS: usize = 16;
+
+/// Creates a new hidden file with a random name in the directory of
+/// the file.
+fn create_temp_file(path: &Path) -> io::Result<(PathBuf, File)> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+    for _ in 0..TEMP_FILE_ATTEMPTS {
+        let temp_path = dir.join(format!(".{name}.{:016x}.tmp", random_suffix()));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp_path)
+        {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "cannot find an unused temporary file name",
+    ))
+}
+
+/// Generates a random number from the randomly keyed hasher of the
+/// standard library, the process ID and the time.
+fn random_suffix() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(std::process::id());
+    if let Ok(time) = SystemTime::now().duration_since(UNIX_EPOCH) {
+        hasher.write_u128(time.as_nanos());
+    }
+    hasher.finish()
+}
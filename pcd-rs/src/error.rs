@@ -1,6 +1,6 @@
 //! The module defines most error type used by this crate.
 
-use crate::metas::{FieldDef, ValueKind};
+use crate::metas::{SchemaDiff, ValueKind};
 use std::{
     io,
     num::{ParseFloatError, ParseIntError},
@@ -8,23 +8,56 @@ use std::{
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// The resource limit of [ReaderLimits](crate::reader::ReaderLimits)
+/// exceeded by an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    Points,
+    DecompressedBytes,
+    Fields,
+    HeaderLines,
+    LineLength,
+}
+
+/// The category of an [Error], for handling errors programmatically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// A malformed header, or a token that cannot be parsed.
+    Parse,
+    /// The record type or writer schema does not match the data.
+    SchemaMismatch,
+    /// A field is missing or has a different kind or size.
+    Field,
+    /// A value cannot be represented in the target kind.
+    ValueCast,
+    /// An ASCII line has the wrong number of tokens.
+    TokenCount,
+    /// A [ReaderLimits](crate::reader::ReaderLimits) limit is exceeded.
+    LimitExceeded,
+    /// The data is shorter than the header declares.
+    Truncated,
+    /// The data is longer than the header declares.
+    TrailingData,
+    /// The header fields are inconsistent with each other or the data.
+    Corrupt,
+    /// The compressed data cannot be decompressed.
+    Lzf,
+    /// An argument or writer configuration is invalid.
+    InvalidArgument,
+    Io,
+}
+
 /// The error returned from the crate.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("parsing error at line {line}: {desc}")]
     ParseError { line: usize, desc: String },
 
-    #[error("reader schema mismatch error, expect {expect:?}, but found {found:?}")]
-    ReaderSchemaMismatchError {
-        expect: Vec<(Option<String>, ValueKind, Option<usize>)>,
-        found: Vec<FieldDef>,
-    },
+    #[error("reader schema mismatch error: {diff}")]
+    ReaderSchemaMismatchError { diff: Box<SchemaDiff> },
 
-    #[error("writer schema mismatch error, expect {expect:?}, but found {found:?}")]
-    WriterSchemaMismatchError {
-        expect: Vec<FieldDef>,
-        found: Vec<FieldDef>,
-    },
+    #[error("writer schema mismatch error: {diff}")]
+    WriterSchemaMismatchError { diff: Box<SchemaDiff> },
 
     #[error(
         r#"field size mismatch, expect {expect} elements in "{field_name}" field, but found {found} elements in record"#,
@@ -35,9 +68,68 @@ pub enum Error {
         found: usize,
     },
 
+    #[error(r#"field "{field_name}" is not found in record"#)]
+    FieldNotFoundError { field_name: String },
+
+    #[error(r#"field kind mismatch, expect {expect:?} in "{field_name}" field, but found {found:?} in record"#)]
+    FieldKindMismatchError {
+        field_name: String,
+        expect: ValueKind,
+        found: ValueKind,
+    },
+
+    #[error("value {value} of kind {from:?} cannot be represented as {to:?}")]
+    ValueCastError {
+        value: String,
+        from: ValueKind,
+        to: ValueKind,
+    },
+
     #[error("record has {expect} fields, but the line has {found} tokens")]
     TextTokenMismatchError { expect: usize, found: usize },
 
+    #[error("{kind:?} limit exceeded, the limit is {limit}, but found {found}")]
+    LimitExceededError {
+        kind: LimitKind,
+        limit: u64,
+        found: u64,
+    },
+
+    #[error("data is truncated, expect {expected} bytes, but found {actual} bytes")]
+    TruncatedDataError { expected: u64, actual: u64 },
+
+    #[error("found {trailing} bytes of trailing data after the points")]
+    TrailingDataError { trailing: u64 },
+
+    #[error("WIDTH {width} times HEIGHT {height} does not equal to POINTS {points}")]
+    PointCountMismatchError {
+        width: u64,
+        height: u64,
+        points: u64,
+    },
+
+    #[error("corrupt data: {desc}")]
+    CorruptDataError { desc: String },
+
+    #[error("LZF decompression error: {desc}")]
+    LzfError { desc: String },
+
+    #[error(
+        "failed to read point {index} at data offset {byte_offset}{}: {source}",
+        field_suffix(.field_name)
+    )]
+    PointError {
+        /// The index of the point.
+        index: usize,
+        /// The byte offset of the point in the data section. For
+        /// binary_compressed data, it is the offset in the decompressed
+        /// data.
+        byte_offset: u64,
+        /// The field that cannot be decoded, if known.
+        field_name: Option<String>,
+        source: Box<Error>,
+    },
+
     #[error("Invalid argument: {desc}")]
     InvalidArgumentError { desc: String },
 
@@ -54,7 +146,91 @@ pub enum Error {
     ParseFloatError(#[from] ParseFloatError),
 }
 
+fn field_suffix(field_name: &Option<String>) -> String {
+    match field_name {
+        Some(name) => format!(r#" in "{name}" field"#),
+        None => String::new(),
+    }
+}
+
 impl Error {
+    /// Returns the category of the error. For a
+    /// [PointError](Error::PointError), it is the category of its
+    /// source.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::ParseError { .. } | Error::ParseIntError(_) | Error::ParseFloatError(_) => {
+                ErrorKind::Parse
+            }
+            Error::ReaderSchemaMismatchError { .. } | Error::WriterSchemaMismatchError { .. } => {
+                ErrorKind::SchemaMismatch
+            }
+            Error::FieldSizeMismatchError { .. }
+            | Error::FieldNotFoundError { .. }
+            | Error::FieldKindMismatchError { .. } => ErrorKind::Field,
+            Error::ValueCastError { .. } => ErrorKind::ValueCast,
+            Error::TextTokenMismatchError { .. } => ErrorKind::TokenCount,
+            Error::LimitExceededError { .. } => ErrorKind::LimitExceeded,
+            Error::TruncatedDataError { .. } => ErrorKind::Truncated,
+            Error::TrailingDataError { .. } => ErrorKind::TrailingData,
+            Error::PointCountMismatchError { .. } | Error::CorruptDataError { .. } => {
+                ErrorKind::Corrupt
+            }
+            Error::LzfError { .. } => ErrorKind::Lzf,
+            Error::PointError { source, .. } => source.kind(),
+            Error::InvalidArgumentError { .. } | Error::InvalidWriterConfiguration { .. } => {
+                ErrorKind::InvalidArgument
+            }
+            Error::IoError(_) => ErrorKind::Io,
+        }
+    }
+
+    /// Returns the error without the point context.
+    pub fn root(&self) -> &Error {
+        match self {
+            Error::PointError { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    /// Returns the index of the point where the error occurs.
+    pub fn point_index(&self) -> Option<usize> {
+        match self {
+            Error::PointError { index, .. } => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Returns the byte offset in the data section where the error
+    /// occurs.
+    pub fn byte_offset(&self) -> Option<u64> {
+        match self {
+            Error::PointError { byte_offset, .. } => Some(*byte_offset),
+            _ => None,
+        }
+    }
+
+    /// Returns the header line where the error occurs.
+    pub fn header_line(&self) -> Option<usize> {
+        match self {
+            Error::ParseError { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the field related to the error.
+    pub fn field_name(&self) -> Option<&str> {
+        match self {
+            Error::FieldSizeMismatchError { field_name, .. }
+            | Error::FieldNotFoundError { field_name }
+            | Error::FieldKindMismatchError { field_name, .. } => Some(field_name),
+            Error::PointError {
+                field_name, source, ..
+            } => field_name.as_deref().or_else(|| source.field_name()),
+            _ => None,
+        }
+    }
+
     pub fn new_parse_error(line: usize, desc: &str) -> Error {
         Error::ParseError {
             line,
@@ -62,15 +238,16 @@ impl Error {
         }
     }
 
-    pub fn new_reader_schema_mismatch_error(
-        expect: Vec<(Option<String>, ValueKind, Option<usize>)>,
-        found: Vec<FieldDef>,
-    ) -> Error {
-        Error::ReaderSchemaMismatchError { expect, found }
+    pub fn new_reader_schema_mismatch_error(diff: SchemaDiff) -> Error {
+        Error::ReaderSchemaMismatchError {
+            diff: Box::new(diff),
+        }
     }
 
-    pub fn new_writer_schema_mismatch_error(expect: Vec<FieldDef>, found: Vec<FieldDef>) -> Error {
-        Error::WriterSchemaMismatchError { expect, found }
+    pub fn new_writer_schema_mismatch_error(diff: SchemaDiff) -> Error {
+        Error::WriterSchemaMismatchError {
+            diff: Box::new(diff),
+        }
     }
 
     pub fn new_field_size_mismatch_error(field_name: &str, expect: usize, found: usize) -> Error {
@@ -81,10 +258,82 @@ impl Error {
         }
     }
 
+    pub fn new_field_not_found_error(field_name: &str) -> Error {
+        Error::FieldNotFoundError {
+            field_name: field_name.to_owned(),
+        }
+    }
+
+    pub fn new_field_kind_mismatch_error(
+        field_name: &str,
+        expect: ValueKind,
+        found: ValueKind,
+    ) -> Error {
+        Error::FieldKindMismatchError {
+            field_name: field_name.to_owned(),
+            expect,
+            found,
+        }
+    }
+
+    pub fn new_value_cast_error(value: &str, from: ValueKind, to: ValueKind) -> Error {
+        Error::ValueCastError {
+            value: value.to_owned(),
+            from,
+            to,
+        }
+    }
+
     pub fn new_text_token_mismatch_error(expect: usize, found: usize) -> Error {
         Error::TextTokenMismatchError { expect, found }
     }
 
+    pub fn new_limit_exceeded_error(kind: LimitKind, limit: u64, found: u64) -> Error {
+        Error::LimitExceededError { kind, limit, found }
+    }
+
+    pub fn new_truncated_data_error(expected: u64, actual: u64) -> Error {
+        Error::TruncatedDataError { expected, actual }
+    }
+
+    pub fn new_trailing_data_error(trailing: u64) -> Error {
+        Error::TrailingDataError { trailing }
+    }
+
+    pub fn new_point_count_mismatch_error(width: u64, height: u64, points: u64) -> Error {
+        Error::PointCountMismatchError {
+            width,
+            height,
+            points,
+        }
+    }
+
+    pub fn new_corrupt_data_error(desc: &str) -> Error {
+        Error::CorruptDataError {
+            desc: desc.to_owned(),
+        }
+    }
+
+    pub fn new_lzf_error(desc: &str) -> Error {
+        Error::LzfError {
+            desc: desc.to_owned(),
+        }
+    }
+
+    pub fn new_point_error(
+        index: usize,
+        byte_offset: u64,
+        field_name: Option<&str>,
+        source: Error,
+    ) -> Error {
+        Error::PointError {
+            index,
+            byte_offset,
+            field_name: field_name.map(str::to_owned),
+            source: Box::new(source),
+        }
+    }
+
     pub fn new_invalid_argument_error(desc: &str) -> Error {
         Error::InvalidArgumentError {
             desc: desc.to_owned(),
@@ -97,3 +346,21 @@ impl Error {
         }
     }
 }
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> Self {
+        let kind = match error.root() {
+            Error::IoError(error) => error.kind(),
+            root => match root.kind() {
+                ErrorKind::Truncated => io::ErrorKind::UnexpectedEof,
+                ErrorKind::InvalidArgument => io::ErrorKind::InvalidInput,
+                _ => io::ErrorKind::InvalidData,
+            },
+        };
+
+        match error {
+            Error::IoError(error) => error,
+            error => io::Error::new(kind, error),
+        }
+    }
+}
@@ -4,7 +4,16 @@
 //! (Point Cloud Data) file format. The [DynReader] and [DynWriter]
 //! can read and write PCD files with any valid schemas. It also
 //! supports deserializing to static types if the `derive` feature is
-//! enabled.
+//! enabled. The `parallel` feature enables decoding the data section
+//! on a rayon pool, see the [parallel] module. The `async` feature
+//! enables reading and writing on tokio I/O, see the [async_io] module.
+//! [validate()] checks a file against the format, and the `serde`
+//! feature makes its [ValidationReport] serializable.
+//! [edit_header()] modifies the header of a file without rewriting
+//! the points, [transcode()] converts the points to another
+//! [DataKind], and [concat()] merges files with the same schema.
+//! [OrganizedCloud] indexes the points of organized point clouds by
+//! row and column.
 //!
 //! # Supported Format Versions
 //!
@@ -38,6 +47,25 @@
 //! # }
 //! ```
 //!
+//! The fields can be accessed by name with [RecordView], which pairs
+//! a record with the schema. The [DynReader::views()] iterates over
+//! the loaded records as views.
+//!
+//! ```rust
+//! # fn main() -> pcd_rs::Result<()>  {
+//! use pcd_rs::DynReader;
+//!
+//! let mut reader = DynReader::open("test_files/binary.pcd")?;
+//!
+//! for view in reader.views() {
+//!     let view = view?;
+//!     let [x, y, z]: [f32; 3] = view.xyz()?;
+//!     let rgb: u32 = view.get("rgb")?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ## Writer
 //!
 //! The writer is first configured by [WriterInit], and then call
@@ -127,7 +155,7 @@ pub struct Point {
 }
 
 let reader = Reader::open("test_files/ascii.pcd")?;
-let points: Result<Vec<Point>> = reader.collect();
+let points: pcd_rs::Result<Vec<Point>> = reader.collect();
 println!("{} points found", points?.len());
 # Ok(())
 # }
@@ -195,27 +223,65 @@ attributes.
 
 - `#[pcd(rename = "NEW_NAME")]` sets the field name on the written PCD data.
 - `#[pcd(ignore)]` instructs the de/serializer to ignore the field.
+
+The [PcdDeserialize] derive also implements `TryFrom<&DynRecord>`,
+which converts the fields by position, and `TryFrom<(&DynRecord,
+&Schema)>`, which looks up the fields by name. The [PcdSerialize]
+derive implements `From<&T> for DynRecord`.
+
+The `#[pcd(pod)]` struct attribute enables the memcpy fast path in
+[Reader::read_batch()] and [Writer::push_batch()] for binary data. The
+struct must be `#[repr(C)]`, and its layout must not contain padding,
+which is checked at compile time.
+
+The derived types expose their field definitions in the associated
+`FIELDS` constant, a slice of [StaticFieldDef], which can be inspected
+in `const` context or converted to a [Schema].
 "##
 )]
 
 #[doc(hidden)]
 pub use byteorder;
 
+#[cfg(feature = "async")]
+pub mod async_io;
+pub mod concat;
+pub mod edit;
 pub mod error;
 mod lzf;
 pub mod metas;
+pub mod organized;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 pub mod prelude;
 pub mod reader;
 pub mod record;
 pub mod traits;
+pub mod transcode;
 mod utils;
+pub mod validate;
 pub mod writer;
 
-pub use error::{Error, Result};
-pub use metas::{DataKind, FieldDef, PcdMeta, Schema, TypeKind, ValueKind, ViewPoint};
+pub use concat::concat;
+pub use edit::edit_header;
+pub use error::{Error, ErrorKind, LimitKind, Result};
+pub use metas::{
+    DataKind, FieldDef, FieldEntry, FieldPair, FieldShape, PcdMeta, Schema, SchemaBuilder,
+    SchemaDiff, StaticFieldDef, TypeKind, ValueKind, ViewPoint,
+};
+pub use organized::OrganizedCloud;
 #[cfg(feature = "derive")]
 pub use pcd_rs_derive::{PcdDeserialize, PcdSerialize};
-pub use reader::{DynReader, Reader};
-pub use record::{DynRecord, Field, PcdDeserialize, PcdSerialize};
+pub use reader::{
+    DynReader, Prefetching, RawPoints, Reader, ReaderInit, ReaderLimits, RecoveryOptions,
+    RecoveryReport, SkippedLine, Views,
+};
+pub use record::{
+    DynRecord, Field, PcdDeserialize, PcdSerialize, RawPoint, RecordView, TokenWriter,
+};
 pub use traits::Value;
-pub use writer::{DynWriter, Writer, WriterInit};
+pub use transcode::transcode;
+pub use validate::{validate, validate_reader, ValidationReport};
+pub use writer::{
+    AsciiFormat, DynWriter, FormattedWriterInit, NanSpelling, ThreadedWriter, Writer, WriterInit,
+};
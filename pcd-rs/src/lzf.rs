@@ -21,6 +21,38 @@ const MAX_REF: usize = 264; // 255 + 8 + 1
 /// The decompressed data as a Vec<u8>
 pub fn decompress(input: &[u8], output_len: usize) -> Result<Vec<u8>> {
     let mut output = vec![0u8; output_len];
+    let out_pos = decompress_into(input, &mut output).map_err(|(_, error)| error)?;
+
+    if out_pos != output_len {
+        return Err(Error::new_lzf_error(&format!(
+            "expected {} bytes, got {}",
+            output_len, out_pos
+        )));
+    }
+
+    Ok(output)
+}
+
+/// Decompress as much of LZF compressed data as possible, such as the
+/// data that is cut short.
+///
+/// # Returns
+/// The bytes decompressed before the end of input or the first error,
+/// and the error if any
+pub fn decompress_prefix(input: &[u8], output_len: usize) -> (Vec<u8>, Option<Error>) {
+    let mut output = vec![0u8; output_len];
+    let (out_pos, error) = match decompress_into(input, &mut output) {
+        Ok(out_pos) => (out_pos, None),
+        Err((out_pos, error)) => (out_pos, Some(error)),
+    };
+    output.truncate(out_pos);
+    (output, error)
+}
+
+/// Decompresses the input into the output, returning the decompressed
+/// length. On errors, the length of the runs decompressed before the
+/// error is returned with the error.
+fn decompress_into(input: &[u8], output: &mut [u8]) -> Result<usize, (usize, Error)> {
     let mut in_pos = 0;
     let mut out_pos = 0;
 
@@ -33,17 +65,14 @@ pub fn decompress(input: &[u8], output_len: usize) -> Result<Vec<u8>> {
             let len = ctrl as usize + 1;
 
             if in_pos + len > input.len() {
-                return Err(Error::ParseError {
-                    line: 0,
-                    desc: "LZF decompression error: literal run exceeds input size".into(),
-                });
+                return Err((
+                    out_pos,
+                    Error::new_lzf_error("literal run exceeds input size"),
+                ));
             }
 
-            if out_pos + len > output_len {
-                return Err(Error::ParseError {
-                    line: 0,
-                    desc: "LZF decompression error: output buffer overflow".into(),
-                });
+            if out_pos + len > output.len() {
+                return Err((out_pos, Error::new_lzf_error("output buffer overflow")));
             }
 
             output[out_pos..out_pos + len].copy_from_slice(&input[in_pos..in_pos + len]);
@@ -55,10 +84,10 @@ pub fn decompress(input: &[u8], output_len: usize) -> Result<Vec<u8>> {
             if len == 7 {
                 // Long match
                 if in_pos >= input.len() {
-                    return Err(Error::ParseError {
-                        line: 0,
-                        desc: "LZF decompression error: long match length exceeds input".into(),
-                    });
+                    return Err((
+                        out_pos,
+                        Error::new_lzf_error("long match length exceeds input"),
+                    ));
                 }
                 len += input[in_pos] as usize;
                 in_pos += 1;
@@ -66,10 +95,10 @@ pub fn decompress(input: &[u8], output_len: usize) -> Result<Vec<u8>> {
             len += 2;
 
             if in_pos >= input.len() {
-                return Err(Error::ParseError {
-                    line: 0,
-                    desc: "LZF decompression error: reference offset exceeds input size".into(),
-                });
+                return Err((
+                    out_pos,
+                    Error::new_lzf_error("reference offset exceeds input size"),
+                ));
             }
 
             let high_offset = ((ctrl & 0x1f) as usize) << 8;
@@ -77,20 +106,17 @@ pub fn decompress(input: &[u8], output_len: usize) -> Result<Vec<u8>> {
             in_pos += 1;
 
             if offset > out_pos {
-                return Err(Error::ParseError {
-                    line: 0,
-                    desc: format!(
-                        "LZF decompression error: invalid back reference (offset {} > position {})",
+                return Err((
+                    out_pos,
+                    Error::new_lzf_error(&format!(
+                        "invalid back reference (offset {} > position {})",
                         offset, out_pos
-                    ),
-                });
+                    )),
+                ));
             }
 
-            if out_pos + len > output_len {
-                return Err(Error::ParseError {
-                    line: 0,
-                    desc: "LZF decompression error: output buffer overflow".into(),
-                });
+            if out_pos + len > output.len() {
+                return Err((out_pos, Error::new_lzf_error("output buffer overflow")));
             }
 
             // Copy from back reference (handle overlapping copies)
@@ -108,17 +134,7 @@ pub fn decompress(input: &[u8], output_len: usize) -> Result<Vec<u8>> {
         }
     }
 
-    if out_pos != output_len {
-        return Err(Error::ParseError {
-            line: 0,
-            desc: format!(
-                "LZF decompression error: expected {} bytes, got {}",
-                output_len, out_pos
-            ),
-        });
-    }
-
-    Ok(output)
+    Ok(out_pos)
 }
 
 /// Compress data using LZF algorithm.
@@ -238,6 +254,22 @@ mod tests {
         assert_eq!(decompressed, original);
     }
 
+    #[test]
+    fn test_decompress_prefix() {
+        let original = b"Hello, world! This is a test of LZF compression. Hello, world!";
+        let compressed = compress(original).unwrap();
+
+        let (decompressed, error) = decompress_prefix(&compressed, original.len());
+        assert_eq!(decompressed, original);
+        assert!(error.is_none());
+
+        let cut = &compressed[..compressed.len() - 4];
+        let (decompressed, error) = decompress_prefix(cut, original.len());
+        assert!(original.starts_with(&decompressed));
+        assert!(decompressed.len() < original.len());
+        assert!(error.is_some());
+    }
+
     #[test]
     fn test_empty_data() {
         let original = b"";
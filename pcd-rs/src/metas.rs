@@ -1,6 +1,22 @@
 //! Types for PCD metadata.
 
-use std::{iter::FromIterator, ops::Index};
+use crate::{
+    reader::ReaderLimits,
+    record::Field,
+    traits::Value,
+    utils::{load_meta, CountingReader},
+    Error, Result,
+};
+use itertools::Itertools;
+use std::{
+    fmt,
+    fs::File,
+    io::{prelude::*, BufReader},
+    iter::FromIterator,
+    ops::Index,
+    path::Path,
+    str::FromStr,
+};
 
 /// The struct keep meta data of PCD file.
 #[derive(Debug, Clone, PartialEq)]
@@ -14,6 +30,77 @@ pub struct PcdMeta {
     pub field_defs: Schema,
 }
 
+impl PcdMeta {
+    /// Reads the header from the reader, stopping after the DATA line.
+    ///
+    /// It returns the metadata and the byte offset of the data section
+    /// from where the reader started. The data section is not read.
+    pub fn read_from<R: BufRead>(reader: R) -> Result<(Self, u64)> {
+        let mut reader = CountingReader::new(reader);
+        let mut line_count = 0;
+        let meta = load_meta(&mut reader, &mut line_count, &ReaderLimits::default())?;
+        Ok((meta, reader.count()))
+    }
+
+    /// Reads the header of a PCD file. See [read_from()](PcdMeta::read_from).
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<(Self, u64)> {
+        let file = BufReader::new(File::open(path.as_ref())?);
+        Self::read_from(file)
+    }
+
+    /// Writes the header, ending with the DATA line.
+    pub fn write_header<W: Write>(&self, writer: &mut W) -> Result<()> {
+        write!(writer, "{}", self)?;
+        Ok(())
+    }
+
+    /// Returns the size of a point in binary data in bytes.
+    pub fn point_size(&self) -> usize {
+        self.field_defs.point_size()
+    }
+
+    /// Returns true if the VIEWPOINT line is part of the header, which
+    /// is introduced in version 0.7.
+    fn has_viewpoint(&self) -> bool {
+        self.version != "0.5" && self.version != "0.6"
+    }
+}
+
+/// Formats the header lines from VERSION to DATA.
+impl fmt::Display for PcdMeta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "VERSION {}", self.version)?;
+        write!(f, "{}", self.field_defs)?;
+        writeln!(f, "WIDTH {}", self.width)?;
+        writeln!(f, "HEIGHT {}", self.height)?;
+        if self.has_viewpoint() {
+            writeln!(f, "VIEWPOINT {}", self.viewpoint)?;
+        }
+        writeln!(f, "POINTS {}", self.num_points)?;
+        writeln!(f, "DATA {}", self.data)?;
+        Ok(())
+    }
+}
+
+/// Parses the header lines from VERSION to DATA. Only whitespace may
+/// follow the DATA line.
+impl FromStr for PcdMeta {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        let mut bytes = text.as_bytes();
+        let (meta, _) = Self::read_from(&mut bytes)?;
+
+        if !bytes.iter().all(u8::is_ascii_whitespace) {
+            return Err(Error::new_invalid_argument_error(
+                "unexpected text after the DATA line",
+            ));
+        }
+
+        Ok(meta)
+    }
+}
+
 /// Represents VIEWPOINT field in meta data.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ViewPoint {
@@ -40,6 +127,16 @@ impl Default for ViewPoint {
     }
 }
 
+/// Formats the VIEWPOINT arguments separated by spaces.
+impl fmt::Display for ViewPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args = [
+            self.tx, self.ty, self.tz, self.qw, self.qx, self.qy, self.qz,
+        ];
+        write!(f, "{}", args.iter().format(" "))
+    }
+}
+
 /// The enum indicates whether the point cloud data is encoded in Ascii, binary, or compressed binary.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DataKind {
@@ -48,6 +145,17 @@ pub enum DataKind {
     BinaryCompressed,
 }
 
+impl fmt::Display for DataKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            DataKind::Ascii => "ascii",
+            DataKind::Binary => "binary",
+            DataKind::BinaryCompressed => "binary_compressed",
+        };
+        f.write_str(text)
+    }
+}
+
 /// The enum specifies one of signed, unsigned integers, and floating point number type to the field.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TypeKind {
@@ -69,6 +177,42 @@ pub enum ValueKind {
     F64,
 }
 
+impl ValueKind {
+    /// Returns the size of the value type in bytes.
+    pub const fn size(&self) -> usize {
+        use ValueKind::*;
+
+        match self {
+            U8 | I8 => 1,
+            U16 | I16 => 2,
+            U32 | I32 | F32 => 4,
+            F64 => 8,
+        }
+    }
+
+    /// Returns the type category of the value type.
+    pub const fn type_kind(&self) -> TypeKind {
+        use ValueKind::*;
+
+        match self {
+            U8 | U16 | U32 => TypeKind::U,
+            I8 | I16 | I32 => TypeKind::I,
+            F32 | F64 => TypeKind::F,
+        }
+    }
+}
+
+impl fmt::Display for TypeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            TypeKind::I => "I",
+            TypeKind::U => "U",
+            TypeKind::F => "F",
+        };
+        f.write_str(text)
+    }
+}
+
 /// Define the properties of a PCD field.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FieldDef {
@@ -77,6 +221,79 @@ pub struct FieldDef {
     pub count: u64,
 }
 
+impl FieldDef {
+    /// Returns the size of the field in bytes. It saturates at
+    /// `usize::MAX` if the size overflows.
+    pub fn size(&self) -> usize {
+        self.checked_size().unwrap_or(usize::MAX)
+    }
+
+    /// Returns the size of the field in bytes, or `None` if it
+    /// overflows.
+    pub fn checked_size(&self) -> Option<usize> {
+        usize::try_from(self.count)
+            .ok()?
+            .checked_mul(self.kind.size())
+    }
+}
+
+/// A compile-time field definition emitted by the derive macros.
+///
+/// The `name` is `None` if the name is not checked against the
+/// `FIELDS` header, for example a field with `#[pcd(ignore)]`. The
+/// `count` is `None` if the element count is determined by the data,
+/// for example a `Vec<_>` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticFieldDef {
+    pub name: Option<&'static str>,
+    pub kind: ValueKind,
+    pub count: Option<u64>,
+}
+
+impl StaticFieldDef {
+    pub const fn new(name: Option<&'static str>, kind: ValueKind, count: Option<u64>) -> Self {
+        Self { name, kind, count }
+    }
+
+    /// Computes the total size of the fields in bytes. Fields without
+    /// a fixed count are not counted. It saturates at `usize::MAX`.
+    pub const fn point_size(defs: &[StaticFieldDef]) -> usize {
+        let mut size: usize = 0;
+        let mut idx = 0;
+
+        while idx < defs.len() {
+            if let Some(count) = defs[idx].count {
+                size = size.saturating_add(defs[idx].kind.size().saturating_mul(count as usize));
+            }
+            idx += 1;
+        }
+
+        size
+    }
+
+    /// Checks whether the field definition from a PCD header matches
+    /// this definition.
+    pub fn matches(&self, def: &FieldDef) -> bool {
+        if self.kind != def.kind {
+            return false;
+        }
+
+        if let Some(name) = self.name {
+            if name != def.name {
+                return false;
+            }
+        }
+
+        if let Some(count) = self.count {
+            if count != def.count {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
 /// Define the schema of PCD format.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Schema {
@@ -95,6 +312,458 @@ impl Schema {
     pub fn iter(&self) -> std::slice::Iter<'_, FieldDef> {
         self.into_iter()
     }
+
+    /// Creates a builder that constructs a schema field by field.
+    pub fn builder() -> SchemaBuilder {
+        SchemaBuilder::default()
+    }
+
+    /// Finds the field with the given name.
+    pub fn find(&self, name: &str) -> Option<&FieldDef> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Returns the position of the field with the given name.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.name == name)
+    }
+
+    /// Returns the size of a point in binary data in bytes. It
+    /// saturates at `usize::MAX` if the size overflows.
+    pub fn point_size(&self) -> usize {
+        self.checked_point_size().unwrap_or(usize::MAX)
+    }
+
+    /// Returns the size of a point in binary data in bytes, or `None`
+    /// if it overflows.
+    pub fn checked_point_size(&self) -> Option<usize> {
+        self.fields
+            .iter()
+            .try_fold(0usize, |sum, field| sum.checked_add(field.checked_size()?))
+    }
+
+    /// Returns the byte offset of each field in a binary point.
+    pub fn offsets(&self) -> Vec<usize> {
+        self.fields
+            .iter()
+            .scan(0, |offset, field| {
+                let begin = *offset;
+                *offset += field.size();
+                Some(begin)
+            })
+            .collect()
+    }
+
+    /// Creates a schema with the given fields in the given order. It
+    /// fails if a name is given more than once.
+    pub fn select(&self, names: &[&str]) -> Result<Schema> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                if names[..index].contains(name) {
+                    let desc = format!("field name {:?} is specified more than once", name);
+                    return Err(Error::new_invalid_argument_error(&desc));
+                }
+                self.find(name)
+                    .cloned()
+                    .ok_or_else(|| Error::new_field_not_found_error(name))
+            })
+            .collect()
+    }
+
+    /// Creates a schema without the given fields.
+    pub fn without(&self, names: &[&str]) -> Result<Schema> {
+        if let Some(name) = names.iter().find(|name| self.find(name).is_none()) {
+            return Err(Error::new_field_not_found_error(name));
+        }
+
+        let fields = self
+            .fields
+            .iter()
+            .filter(|field| !names.contains(&field.name.as_str()))
+            .cloned()
+            .collect();
+        Ok(Schema { fields })
+    }
+
+    /// Creates a schema with the field `from` renamed to `to`.
+    pub fn rename(&self, from: &str, to: &str) -> Result<Schema> {
+        let index = self
+            .index_of(from)
+            .ok_or_else(|| Error::new_field_not_found_error(from))?;
+        check_field_name(to)?;
+
+        if from != to && self.find(to).is_some() {
+            let desc = format!("field name {:?} is specified more than once", to);
+            return Err(Error::new_invalid_argument_error(&desc));
+        }
+
+        let mut schema = self.clone();
+        schema.fields[index].name = to.to_owned();
+        Ok(schema)
+    }
+
+    /// Creates a schema with the fields of `self` followed by the
+    /// fields of `other` not found in `self`.
+    ///
+    /// Fields with the same name must have the same kind and count.
+    pub fn union(&self, other: &Schema) -> Result<Schema> {
+        let mut schema = self.clone();
+
+        for field in other {
+            match self.find(&field.name) {
+                Some(prev) if prev == field => {}
+                Some(prev) => {
+                    let desc = format!(
+                        "field {:?} is defined as {:?} x {} and {:?} x {}",
+                        field.name, prev.kind, prev.count, field.kind, field.count
+                    );
+                    return Err(Error::new_invalid_argument_error(&desc));
+                }
+                None => schema.fields.push(field.clone()),
+            }
+        }
+
+        Ok(schema)
+    }
+
+    /// Compares the other schema with this expected schema.
+    pub fn diff(&self, other: &Schema) -> SchemaDiff {
+        let expected: Vec<FieldShape> = self.iter().map(FieldShape::from).collect();
+        let found: Vec<FieldShape> = other.iter().map(FieldShape::from).collect();
+        SchemaDiff::new(&expected, &found)
+    }
+
+    /// Checks whether the points of both schemas have the same binary
+    /// layout, that is, the same field kinds and counts in order.
+    /// Field names are not compared.
+    pub fn is_compatible_with(&self, other: &Schema) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(lhs, rhs)| lhs.kind == rhs.kind && lhs.count == rhs.count)
+    }
+}
+
+/// Formats the schema as `FIELDS`, `SIZE`, `TYPE` and `COUNT` header lines.
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = self.iter().map(|field| field.name.as_str());
+        let sizes = self.iter().map(|field| field.kind.size());
+        let types = self.iter().map(|field| field.kind.type_kind());
+        let counts = self.iter().map(|field| field.count);
+
+        writeln!(f, "FIELDS {}", names.format(" "))?;
+        writeln!(f, "SIZE {}", sizes.format(" "))?;
+        writeln!(f, "TYPE {}", types.format(" "))?;
+        writeln!(f, "COUNT {}", counts.format(" "))?;
+        Ok(())
+    }
+}
+
+/// A field compared by [SchemaDiff].
+///
+/// The `name` is `None` if the field has no name, for example a field
+/// of a [DynRecord](crate::record::DynRecord), or the name is not
+/// checked. The `count` is `None` if any count is accepted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldShape {
+    pub name: Option<String>,
+    pub kind: ValueKind,
+    pub count: Option<u64>,
+}
+
+impl From<&FieldDef> for FieldShape {
+    fn from(def: &FieldDef) -> Self {
+        Self {
+            name: Some(def.name.clone()),
+            kind: def.kind,
+            count: Some(def.count),
+        }
+    }
+}
+
+impl From<&StaticFieldDef> for FieldShape {
+    fn from(def: &StaticFieldDef) -> Self {
+        Self {
+            name: def.name.map(str::to_owned),
+            kind: def.kind,
+            count: def.count,
+        }
+    }
+}
+
+impl From<&Field> for FieldShape {
+    fn from(field: &Field) -> Self {
+        Self {
+            name: None,
+            kind: field.kind(),
+            count: Some(field.count() as u64),
+        }
+    }
+}
+
+impl fmt::Display for FieldShape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "{name:?} ")?;
+        }
+        match self.count {
+            Some(count) => write!(f, "({:?} x {count})", self.kind),
+            None => write!(f, "({:?})", self.kind),
+        }
+    }
+}
+
+/// A field that exists in one of the compared schemas only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldEntry {
+    pub position: usize,
+    pub field: FieldShape,
+}
+
+/// A pair of corresponding fields in the compared schemas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldPair {
+    pub expected_position: usize,
+    pub expected: FieldShape,
+    pub found_position: usize,
+    pub found: FieldShape,
+}
+
+impl FieldPair {
+    fn label(&self) -> String {
+        match self.expected.name.as_ref().or(self.found.name.as_ref()) {
+            Some(name) => format!("field {name:?} at position {}", self.found_position),
+            None => format!("field at position {}", self.found_position),
+        }
+    }
+}
+
+/// The differences between an expected and a found schema.
+///
+/// Named fields correspond by name. A field without a name on either
+/// side corresponds to the field at the same position.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SchemaDiff {
+    /// The expected fields not found.
+    pub missing: Vec<FieldEntry>,
+    /// The found fields not expected.
+    pub extra: Vec<FieldEntry>,
+    /// The corresponding fields with different value kinds.
+    pub kind_mismatches: Vec<FieldPair>,
+    /// The corresponding fields with different element counts.
+    pub count_mismatches: Vec<FieldPair>,
+    /// The corresponding fields at different positions.
+    pub moved: Vec<FieldPair>,
+}
+
+impl SchemaDiff {
+    /// Compares the found fields with the expected fields.
+    pub fn new(expected: &[FieldShape], found: &[FieldShape]) -> Self {
+        let mut diff = Self::default();
+        let mut claimed = vec![false; found.len()];
+
+        // Pair the named fields by name first, so positional pairing
+        // does not take their fields.
+        let by_name: Vec<Option<usize>> = expected
+            .iter()
+            .map(|field| {
+                let name = field.name.as_ref()?;
+                let position = found
+                    .iter()
+                    .position(|other| other.name.as_ref() == Some(name))?;
+                claimed[position] = true;
+                Some(position)
+            })
+            .collect();
+
+        for (expected_position, field) in expected.iter().enumerate() {
+            let found_position = by_name[expected_position].or_else(|| {
+                let other = found.get(expected_position)?;
+                let positional = field.name.is_none() || other.name.is_none();
+                (positional && !claimed[expected_position]).then(|| {
+                    claimed[expected_position] = true;
+                    expected_position
+                })
+            });
+
+            let Some(found_position) = found_position else {
+                diff.missing.push(FieldEntry {
+                    position: expected_position,
+                    field: field.clone(),
+                });
+                continue;
+            };
+
+            let other = &found[found_position];
+            let pair = FieldPair {
+                expected_position,
+                expected: field.clone(),
+                found_position,
+                found: other.clone(),
+            };
+
+            if field.kind != other.kind {
+                diff.kind_mismatches.push(pair.clone());
+            }
+            if let (Some(lhs), Some(rhs)) = (field.count, other.count) {
+                if lhs != rhs {
+                    diff.count_mismatches.push(pair.clone());
+                }
+            }
+            if expected_position != found_position {
+                diff.moved.push(pair);
+            }
+        }
+
+        diff.extra = found
+            .iter()
+            .zip(claimed)
+            .enumerate()
+            .filter(|(_, (_, claimed))| !claimed)
+            .map(|(position, (field, _))| FieldEntry {
+                position,
+                field: field.clone(),
+            })
+            .collect();
+
+        diff
+    }
+
+    /// Returns true if the schemas match.
+    pub fn is_empty(&self) -> bool {
+        self.missing.is_empty()
+            && self.extra.is_empty()
+            && self.kind_mismatches.is_empty()
+            && self.count_mismatches.is_empty()
+            && self.moved.is_empty()
+    }
+}
+
+/// Lists the differences separated by semicolons.
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("no differences");
+        }
+
+        let missing = self
+            .missing
+            .iter()
+            .map(|entry| format!("missing {} at position {}", entry.field, entry.position));
+        let extra = self
+            .extra
+            .iter()
+            .map(|entry| format!("extra {} at position {}", entry.field, entry.position));
+        let kinds = self.kind_mismatches.iter().map(|pair| {
+            format!(
+                "{} expects kind {:?}, but found {:?}",
+                pair.label(),
+                pair.expected.kind,
+                pair.found.kind
+            )
+        });
+        let counts = self.count_mismatches.iter().map(|pair| {
+            format!(
+                "{} expects count {}, but found {}",
+                pair.label(),
+                pair.expected.count.unwrap_or_default(),
+                pair.found.count.unwrap_or_default()
+            )
+        });
+        let moved = self.moved.iter().map(|pair| {
+            format!(
+                "{} is expected at position {}",
+                pair.label(),
+                pair.expected_position
+            )
+        });
+
+        let lines = missing.chain(extra).chain(kinds).chain(counts).chain(moved);
+        write!(f, "{}", lines.format("; "))
+    }
+}
+
+/// Builds a [Schema] field by field, created by [Schema::builder()].
+///
+/// The field names are checked as the fields are added, and the first
+/// error is returned by [build()](SchemaBuilder::build).
+#[derive(Debug, Default)]
+pub struct SchemaBuilder {
+    fields: Vec<FieldDef>,
+    error: Option<Error>,
+}
+
+impl SchemaBuilder {
+    /// Adds a single-element field of type `T`.
+    pub fn field<T: Value>(self, name: &str) -> Self {
+        self.field_def(name, T::KIND, 1)
+    }
+
+    /// Adds a field with `N` elements of type `T`.
+    pub fn array<T: Value, const N: usize>(self, name: &str) -> Self {
+        self.field_def(name, T::KIND, N as u64)
+    }
+
+    /// Adds a field with kind and count determined in runtime.
+    pub fn field_def(mut self, name: &str, kind: ValueKind, count: u64) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+
+        if let Err(err) = self.check_field(name, count) {
+            self.error = Some(err);
+            return self;
+        }
+
+        self.fields.push(FieldDef {
+            name: name.to_owned(),
+            kind,
+            count,
+        });
+        self
+    }
+
+    /// Finishes the schema, returning the first error if any.
+    pub fn build(self) -> Result<Schema> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(Schema {
+                fields: self.fields,
+            }),
+        }
+    }
+
+    fn check_field(&self, name: &str, count: u64) -> Result<()> {
+        check_field_name(name)?;
+
+        if self.fields.iter().any(|field| field.name == name) {
+            let desc = format!("field name {:?} is specified more than once", name);
+            return Err(Error::new_invalid_argument_error(&desc));
+        }
+
+        if count == 0 {
+            let desc = format!("the count of field {:?} must be nonzero", name);
+            return Err(Error::new_invalid_argument_error(&desc));
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks whether the name can be written to the `FIELDS` header.
+fn check_field_name(name: &str) -> Result<()> {
+    let is_valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_graphic() && c != '#');
+
+    if !is_valid {
+        let desc = format!("invalid field name {:?}", name);
+        return Err(Error::new_invalid_argument_error(&desc));
+    }
+
+    Ok(())
 }
 
 impl Index<usize> for Schema {
@@ -149,6 +818,29 @@ impl FromIterator<FieldDef> for Schema {
     }
 }
 
+/// Converts static field definitions to a schema.
+///
+/// Unnamed fields are labeled as `unknown_field_{idx}` in the same
+/// way the reader labels `_` fields. Fields without a fixed count are
+/// given a zero count, which is rejected by the writer.
+impl From<&[StaticFieldDef]> for Schema {
+    fn from(defs: &[StaticFieldDef]) -> Self {
+        let fields = defs
+            .iter()
+            .enumerate()
+            .map(|(idx, def)| FieldDef {
+                name: match def.name {
+                    Some(name) => name.to_owned(),
+                    None => format!("unknown_field_{idx}"),
+                },
+                kind: def.kind,
+                count: def.count.unwrap_or(0),
+            })
+            .collect();
+        Self { fields }
+    }
+}
+
 impl<'a> FromIterator<&'a FieldDef> for Schema {
     fn from_iter<T: IntoIterator<Item = &'a FieldDef>>(iter: T) -> Self {
         Self {
@@ -0,0 +1,285 @@
+//! Organized point clouds indexed by rows and columns.
+//!
+//! A PCD file with HEIGHT greater than 1 stores an organized point
+//! cloud, such as the output of a depth camera, whose points are laid
+//! out in rows of WIDTH points. [OrganizedCloud] loads such a cloud and
+//! indexes the points by `(row, col)`. Pixels without a measurement
+//! keep their place in the grid, and are marked invalid by a NaN `x`,
+//! `y` or `z` coordinate as PCL does.
+//!
+//! ```rust
+//! # fn main() -> pcd_rs::Result<()> {
+//! use pcd_rs::{DynReader, OrganizedCloud};
+//!
+//! let reader = DynReader::open("test_files/binary.pcd")?;
+//! let cloud = OrganizedCloud::from_reader(reader)?;
+//!
+//! assert_eq!((cloud.width(), cloud.height()), (1809, 16));
+//! for points in cloud.rows() {
+//!     assert_eq!(points.len(), 1809);
+//! }
+//!
+//! // The valid points around the point at row 0, column 5
+//! for (row, col, point) in cloud.window(0, 5, 1) {
+//!     assert!(cloud.is_valid(row, col));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Organized point clouds are written by a [Writer](crate::Writer) in
+//! the [organized](crate::Writer::set_organized) mode, which checks that
+//! the points fill the grid.
+
+use crate::{
+    metas::ValueKind,
+    reader::Reader,
+    record::{PcdDeserialize, RawPoint},
+    Error, Result,
+};
+use std::io::BufRead;
+
+/// A point cloud of `height` rows of `width` points each.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrganizedCloud<T> {
+    width: usize,
+    height: usize,
+    points: Vec<T>,
+    /// Whether each point has valid coordinates.
+    valid: Vec<bool>,
+}
+
+impl<T> OrganizedCloud<T> {
+    /// Creates a cloud from points in row-major order, all of which are
+    /// valid.
+    ///
+    /// The number of points must be `width` times `height`.
+    pub fn new(width: usize, height: usize, points: Vec<T>) -> Result<Self> {
+        if width.checked_mul(height) != Some(points.len()) {
+            return Err(Error::new_point_count_mismatch_error(
+                width as u64,
+                height as u64,
+                points.len() as u64,
+            ));
+        }
+
+        let valid = vec![true; points.len()];
+        Ok(Self {
+            width,
+            height,
+            points,
+            valid,
+        })
+    }
+
+    /// Loads all points of the reader as a cloud with the WIDTH and
+    /// HEIGHT of the header.
+    ///
+    /// A point is invalid if any element of its `x`, `y` or `z` field
+    /// is NaN. Clouds without these fields have no invalid points. It
+    /// fails if POINTS does not equal WIDTH times HEIGHT.
+    pub fn from_reader<R>(mut reader: Reader<T, R>) -> Result<Self>
+    where
+        R: BufRead,
+        T: PcdDeserialize,
+    {
+        let meta = reader.meta();
+        let (width, height, num_points) = (meta.width, meta.height, meta.num_points);
+        if width.checked_mul(height) != Some(num_points) {
+            return Err(Error::new_point_count_mismatch_error(
+                width, height, num_points,
+            ));
+        }
+
+        let schema = meta.field_defs.clone();
+        let coords: Vec<usize> = ["x", "y", "z"]
+            .into_iter()
+            .filter_map(|name| schema.index_of(name))
+            .collect();
+
+        // The header is untrusted, so the reservation is bounded.
+        let capacity = num_points.min(1 << 16) as usize;
+        let mut points = Vec::with_capacity(capacity);
+        let mut valid = Vec::with_capacity(capacity);
+        let mut raw_points = reader.raw_points();
+        while let Some(point) = raw_points.next() {
+            let point = point?;
+            points.push(T::decode(point.bytes(), &schema)?);
+            valid.push(!coords.iter().any(|&index| has_nan(&point, index)));
+        }
+
+        Ok(Self {
+            width: width as usize,
+            height: height as usize,
+            points,
+            valid,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Gets the points in row-major order.
+    pub fn points(&self) -> &[T] {
+        &self.points
+    }
+
+    pub fn into_points(self) -> Vec<T> {
+        self.points
+    }
+
+    /// Gets the point at `(row, col)`, or `None` if it is out of range.
+    /// Invalid points are returned as well.
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        let index = self.index(row, col)?;
+        Some(&self.points[index])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        let index = self.index(row, col)?;
+        Some(&mut self.points[index])
+    }
+
+    /// Gets the point at `(row, col)` if it is in range and valid.
+    pub fn get_valid(&self, row: usize, col: usize) -> Option<&T> {
+        let index = self.index(row, col)?;
+        self.valid[index].then(|| &self.points[index])
+    }
+
+    /// Checks whether the point at `(row, col)` is in range and valid.
+    pub fn is_valid(&self, row: usize, col: usize) -> bool {
+        self.index(row, col).is_some_and(|index| self.valid[index])
+    }
+
+    /// Marks the point at `(row, col)` valid or invalid. It returns
+    /// `false` if the position is out of range.
+    pub fn set_valid(&mut self, row: usize, col: usize, valid: bool) -> bool {
+        let Some(index) = self.index(row, col) else {
+            return false;
+        };
+        self.valid[index] = valid;
+        true
+    }
+
+    /// Gets the points of a row, or `None` if it is out of range.
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        if row >= self.height {
+            return None;
+        }
+        let begin = row * self.width;
+        Some(&self.points[begin..begin + self.width])
+    }
+
+    /// Iterates over the rows from top to bottom.
+    pub fn rows(&self) -> Rows<'_, T> {
+        Rows {
+            cloud: self,
+            range: 0..self.height,
+        }
+    }
+
+    /// Iterates over the valid points within `radius` rows and columns
+    /// of `(row, col)`, including the point itself, in row-major order.
+    ///
+    /// The window is clipped at the borders of the cloud. Each item is
+    /// the row, the column and the point.
+    pub fn window(&self, row: usize, col: usize, radius: usize) -> Window<'_, T> {
+        let rows = row.saturating_sub(radius)
+            ..row
+                .saturating_add(radius.saturating_add(1))
+                .min(self.height);
+        let cols = col.saturating_sub(radius)
+            ..col.saturating_add(radius.saturating_add(1)).min(self.width);
+        Window {
+            cloud: self,
+            row: rows.start,
+            col: cols.start,
+            rows,
+            cols,
+        }
+    }
+
+    fn index(&self, row: usize, col: usize) -> Option<usize> {
+        (row < self.height && col < self.width).then(|| row * self.width + col)
+    }
+}
+
+/// Checks whether any element of a float field is NaN.
+fn has_nan(point: &RawPoint<'_>, index: usize) -> bool {
+    let def = &point.schema()[index];
+    (0..def.count as usize).any(|element| match def.kind {
+        ValueKind::F32 => point
+            .get_element::<f32>(index, element)
+            .is_ok_and(f32::is_nan),
+        ValueKind::F64 => point
+            .get_element::<f64>(index, element)
+            .is_ok_and(f64::is_nan),
+        _ => false,
+    })
+}
+
+/// An iterator over the rows of an [OrganizedCloud], created by
+/// [OrganizedCloud::rows()].
+#[derive(Debug, Clone)]
+pub struct Rows<'a, T> {
+    cloud: &'a OrganizedCloud<T>,
+    range: std::ops::Range<usize>,
+}
+
+impl<'a, T> Iterator for Rows<'a, T> {
+    type Item = &'a [T];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.range.next()?;
+        self.cloud.row(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for Rows<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let row = self.range.next_back()?;
+        self.cloud.row(row)
+    }
+}
+
+impl<T> ExactSizeIterator for Rows<'_, T> {}
+
+/// An iterator over the valid points in a neighbourhood, created by
+/// [OrganizedCloud::window()].
+#[derive(Debug, Clone)]
+pub struct Window<'a, T> {
+    cloud: &'a OrganizedCloud<T>,
+    rows: std::ops::Range<usize>,
+    cols: std::ops::Range<usize>,
+    row: usize,
+    col: usize,
+}
+
+impl<'a, T> Iterator for Window<'a, T> {
+    type Item = (usize, usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.row < self.rows.end && !self.cols.is_empty() {
+            let (row, col) = (self.row, self.col);
+            self.col += 1;
+            if self.col == self.cols.end {
+                self.col = self.cols.start;
+                self.row += 1;
+            }
+
+            if let Some(point) = self.cloud.get_valid(row, col) {
+                return Some((row, col, point));
+            }
+        }
+        None
+    }
+}
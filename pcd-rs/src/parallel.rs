@@ -0,0 +1,343 @@
+//! Parallel decoding of the data section, enabled by the `parallel` feature.
+//!
+//! The data section is split into line-aligned (ASCII) or
+//! stride-aligned (binary) chunks, which are decoded on a rayon pool.
+//! The points keep the order in the file. See
+//! [Reader::par_collect()](crate::reader::Reader::par_collect) and
+//! [Reader::par_collect_columns()](crate::reader::Reader::par_collect_columns).
+
+use crate::{
+    error::Error,
+    metas::{DataKind, PcdMeta, Schema},
+    reader::point_error,
+    record::{Field, PcdDeserialize, TokenReader},
+    traits::{match_field, Value},
+    Result,
+};
+use rayon::prelude::*;
+use std::io;
+
+/// Options of parallel decoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParallelOptions {
+    /// The number of threads. The global rayon pool is used if it is
+    /// not set.
+    pub num_threads: Option<usize>,
+    /// The number of points decoded by a task.
+    pub chunk_points: usize,
+}
+
+impl Default for ParallelOptions {
+    fn default() -> Self {
+        Self {
+            num_threads: None,
+            chunk_points: 16384,
+        }
+    }
+}
+
+/// The points stored field by field, created by
+/// [Reader::par_collect_columns()](crate::reader::Reader::par_collect_columns).
+///
+/// Each column holds the values of a field of all points in point
+/// order, so a field with count `n` has `n * len()` values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnarCloud {
+    schema: Schema,
+    columns: Vec<Field>,
+    len: usize,
+}
+
+impl ColumnarCloud {
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Returns the number of points.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn columns(&self) -> &[Field] {
+        &self.columns
+    }
+
+    /// Gets the column of the field with the given name.
+    pub fn column(&self, name: &str) -> Option<&Field> {
+        let index = self.schema.index_of(name)?;
+        Some(&self.columns[index])
+    }
+
+    pub fn into_columns(self) -> Vec<Field> {
+        self.columns
+    }
+}
+
+/// The undecoded points of the data section.
+pub(crate) enum Chunks<'a> {
+    Binary(Vec<Chunk<&'a [u8]>>),
+    Ascii(Vec<Chunk<&'a str>>),
+}
+
+/// The data of consecutive points and the position of the first one.
+pub(crate) struct Chunk<T> {
+    index: usize,
+    byte_offset: u64,
+    data: T,
+}
+
+/// Runs the work on the pool given by the options.
+pub(crate) fn install<T, F>(options: &ParallelOptions, work: F) -> Result<T>
+where
+    T: Send,
+    F: FnOnce() -> Result<T> + Send,
+{
+    if options.chunk_points == 0 {
+        return Err(Error::new_invalid_argument_error(
+            "chunk_points must be nonzero",
+        ));
+    }
+
+    match options.num_threads {
+        Some(num_threads) => {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(num_threads)
+                .build()
+                .map_err(|error| Error::new_invalid_argument_error(&error.to_string()))?;
+            pool.install(work)
+        }
+        None => work(),
+    }
+}
+
+/// Splits the bytes of `num_points` points into chunks. The data
+/// starts with the point at `first_index` and `first_offset`.
+pub(crate) fn split_chunks<'a>(
+    meta: &PcdMeta,
+    data: &'a [u8],
+    num_points: usize,
+    (first_index, first_offset): (usize, u64),
+    options: &ParallelOptions,
+) -> Result<Chunks<'a>> {
+    let chunk_points = options.chunk_points;
+
+    match meta.data {
+        DataKind::Binary | DataKind::BinaryCompressed => {
+            let point_size = meta.field_defs.point_size();
+            let expected = num_points.checked_mul(point_size);
+            let Some(data) = expected.and_then(|expected| data.get(..expected)) else {
+                let found = data.len() / point_size;
+                let expected = (num_points as u64).saturating_mul(point_size as u64);
+                let error = Error::new_truncated_data_error(expected, data.len() as u64);
+                return Err(point_error(
+                    error,
+                    first_index + found,
+                    first_offset + (found * point_size) as u64,
+                    None,
+                    &meta.field_defs,
+                ));
+            };
+
+            let chunks = match point_size {
+                0 => vec![],
+                _ => data
+                    .chunks(chunk_points.saturating_mul(point_size))
+                    .enumerate()
+                    .map(|(nth, data)| Chunk {
+                        index: first_index + nth * chunk_points,
+                        byte_offset: first_offset + (nth * chunk_points * point_size) as u64,
+                        data,
+                    })
+                    .collect(),
+            };
+            Ok(Chunks::Binary(chunks))
+        }
+        DataKind::Ascii => {
+            let text = std::str::from_utf8(data)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+
+            let mut chunks = vec![];
+            let mut begin = 0;
+            let mut num_lines = 0;
+            let mut line_ends = text.match_indices('\n').map(|(index, _)| index + 1);
+
+            while num_lines < num_points {
+                let lines = chunk_points.min(num_points - num_lines);
+
+                // The last line may not end with a line break.
+                let end = match line_ends.nth(lines - 1) {
+                    Some(end) => end,
+                    None if begin < text.len() && line_ends_count(&text[begin..]) == lines - 1 => {
+                        text.len()
+                    }
+                    None => {
+                        let error = io::Error::from(io::ErrorKind::UnexpectedEof).into();
+                        let index = first_index + num_lines + line_ends_count(&text[begin..]);
+                        let offset = first_offset + text.len() as u64;
+                        return Err(point_error(error, index, offset, None, &meta.field_defs));
+                    }
+                };
+
+                chunks.push(Chunk {
+                    index: first_index + num_lines,
+                    byte_offset: first_offset + begin as u64,
+                    data: &text[begin..end],
+                });
+                begin = end;
+                num_lines += lines;
+            }
+
+            Ok(Chunks::Ascii(chunks))
+        }
+    }
+}
+
+fn line_ends_count(text: &str) -> usize {
+    text.bytes().filter(|&byte| byte == b'\n').count()
+}
+
+impl<'a> Chunk<&'a str> {
+    /// Iterates the lines with the index and byte offset of each.
+    fn lines(&self) -> impl Iterator<Item = (usize, u64, &'a str)> {
+        let index = self.index;
+        let mut byte_offset = self.byte_offset;
+        self.data
+            .split_inclusive('\n')
+            .enumerate()
+            .map(move |(nth, line)| {
+                let item = (index + nth, byte_offset, line);
+                byte_offset += line.len() as u64;
+                item
+            })
+    }
+}
+
+/// Decodes the chunks into records in parallel.
+pub(crate) fn decode_records<Record>(chunks: Chunks<'_>, schema: &Schema) -> Result<Vec<Record>>
+where
+    Record: PcdDeserialize + Send,
+{
+    let batches: Vec<Vec<Record>> = match chunks {
+        Chunks::Binary(chunks) => {
+            let point_size = schema.point_size();
+            chunks
+                .into_par_iter()
+                .map(|chunk| {
+                    let mut records = Vec::with_capacity(chunk.data.len() / point_size);
+                    if !Record::read_pod_slice(chunk.data, &mut records) {
+                        for (nth, bytes) in chunk.data.chunks_exact(point_size).enumerate() {
+                            let record = Record::decode(bytes, schema).map_err(|error| {
+                                let offset = chunk.byte_offset + (nth * point_size) as u64;
+                                point_error(error, chunk.index + nth, offset, None, schema)
+                            })?;
+                            records.push(record);
+                        }
+                    }
+                    Ok(records)
+                })
+                .collect::<Result<_>>()?
+        }
+        Chunks::Ascii(chunks) => chunks
+            .into_par_iter()
+            .map(|chunk| {
+                chunk
+                    .lines()
+                    .map(|(index, offset, line)| {
+                        Record::decode_line(line, schema)
+                            .map_err(|error| point_error(error, index, offset, Some(line), schema))
+                    })
+                    .collect()
+            })
+            .collect::<Result<_>>()?,
+    };
+
+    Ok(batches.into_iter().flatten().collect())
+}
+
+/// Decodes the chunks into columns in parallel.
+pub(crate) fn decode_columns(chunks: Chunks<'_>, schema: &Schema) -> Result<ColumnarCloud> {
+    let empty_columns =
+        || -> Vec<Field> { schema.iter().map(|def| Field::zeros(def.kind, 0)).collect() };
+
+    let batches: Vec<(Vec<Field>, usize)> = match chunks {
+        Chunks::Binary(chunks) => {
+            let point_size = schema.point_size();
+            let offsets = schema.offsets();
+
+            chunks
+                .into_par_iter()
+                .map(|Chunk { data: chunk, .. }| {
+                    let mut columns = empty_columns();
+                    for (column, (def, &offset)) in
+                        columns.iter_mut().zip(schema.iter().zip(&offsets))
+                    {
+                        let size = def.kind.size();
+                        match_field!(column, values => {
+                            for point in chunk.chunks_exact(point_size) {
+                                let bytes = &point[offset..offset + def.size()];
+                                for value in bytes.chunks_exact(size) {
+                                    values.push(Value::from_le_slice(value));
+                                }
+                            }
+                        });
+                    }
+                    Ok((columns, chunk.len() / point_size))
+                })
+                .collect::<Result<_>>()?
+        }
+        Chunks::Ascii(chunks) => chunks
+            .into_par_iter()
+            .map(|chunk| {
+                let mut columns = empty_columns();
+                let mut len = 0;
+                let expect: usize = schema.iter().map(|def| def.count as usize).sum();
+
+                for (index, offset, line) in chunk.lines() {
+                    let decode_line = |columns: &mut Vec<Field>| -> Result<()> {
+                        let found = line.split_ascii_whitespace().count();
+                        if found != expect {
+                            return Err(Error::new_text_token_mismatch_error(expect, found));
+                        }
+
+                        let mut tokens = line.split_ascii_whitespace();
+                        let mut tokens = TokenReader::new(&mut tokens);
+                        for (column, def) in columns.iter_mut().zip(schema.iter()) {
+                            match_field!(column, values => {
+                                for _ in 0..def.count {
+                                    values.push(Value::parse_token(tokens.next_token(schema)?)?);
+                                }
+                            });
+                        }
+                        Ok(())
+                    };
+                    decode_line(&mut columns)
+                        .map_err(|error| point_error(error, index, offset, Some(line), schema))?;
+                    len += 1;
+                }
+
+                Ok((columns, len))
+            })
+            .collect::<Result<_>>()?,
+    };
+
+    let mut columns = empty_columns();
+    let mut len = 0;
+    for (batch, batch_len) in batches {
+        for (column, values) in columns.iter_mut().zip(batch) {
+            match_field!(column, column => {
+                column.extend_from_slice(Value::field_values(&values).unwrap());
+            });
+        }
+        len += batch_len;
+    }
+
+    Ok(ColumnarCloud {
+        schema: schema.clone(),
+        columns,
+        len,
+    })
+}
@@ -21,7 +21,7 @@ pub struct Point {
 
 fn main() -> pcd_rs::Result<()> {
     let reader = Reader::open("test_files/ascii.pcd")?;
-    let points: Result<Vec<Point>> = reader.collect();
+    let points: pcd_rs::Result<Vec<Point>> = reader.collect();
     assert_eq!(points?.len(), 213);
     Ok(())
 }
@@ -30,20 +30,61 @@ fn main() -> pcd_rs::Result<()> {
 )]
 
 use crate::{
-    error::Error,
+    error::{Error, ErrorKind, LimitKind},
     lzf,
-    metas::{DataKind, FieldDef, PcdMeta},
-    record::{DynRecord, PcdDeserialize},
+    metas::{DataKind, FieldShape, PcdMeta, Schema, SchemaDiff},
+    record::{DynRecord, PcdDeserialize, RawPoint, RecordView},
+    traits::{match_kind, Value},
+    utils::read_line_limited,
     Result,
 };
 use byteorder::{LittleEndian, ReadBytesExt};
 use std::{
     fs::File,
-    io::{prelude::*, BufReader, Cursor},
+    io::{self, prelude::*, BufReader, Cursor, SeekFrom},
     marker::PhantomData,
     path::Path,
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
 };
 
+/// The number of points sent at once by [Prefetching].
+const PREFETCH_BATCH_SIZE: usize = 1024;
+
+/// Resource limits of a reader, protecting it from untrusted input.
+///
+/// The limits are checked before the corresponding buffers are
+/// allocated, and a violation returns
+/// [Error::LimitExceededError](crate::error::Error::LimitExceededError).
+/// The default limits are unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReaderLimits {
+    /// The maximum POINTS in the header.
+    pub max_points: u64,
+    /// The maximum bytes of the compressed and decompressed data of a
+    /// binary_compressed file, and of a single point.
+    pub max_decompressed_bytes: u64,
+    /// The maximum number of fields in the header.
+    pub max_fields: usize,
+    /// The maximum number of header lines, including comments.
+    pub max_header_lines: usize,
+    /// The maximum length of a header or ASCII data line in bytes,
+    /// excluding the line break.
+    pub max_line_length: usize,
+}
+
+impl Default for ReaderLimits {
+    fn default() -> Self {
+        Self {
+            max_points: u64::MAX,
+            max_decompressed_bytes: u64::MAX,
+            max_fields: usize::MAX,
+            max_header_lines: usize::MAX,
+            max_line_length: usize::MAX,
+        }
+    }
+}
+
 /// The `DynReader` struct loads points with schema determined in runtime.
 pub type DynReader<R> = Reader<DynRecord, R>;
 
@@ -53,11 +94,28 @@ where
     R: Read,
 {
     meta: PcdMeta,
+    state: DataState<R>,
+    _phantom: PhantomData<T>,
+}
+
+/// The progress of reading the data section.
+struct DataState<R> {
+    reader: R,
     record_count: usize,
     finished: bool,
-    reader: R,
     decompressed_buffer: Option<Cursor<Vec<u8>>>,
-    _phantom: PhantomData<T>,
+    /// The error that cut the decompressed data short, which is
+    /// reported by [Reader::recover()].
+    decompress_error: Option<Error>,
+    /// Reused for each binary point.
+    chunk: Vec<u8>,
+    /// Reused for each ASCII line.
+    line: String,
+    max_line_length: usize,
+    /// The byte offset of the next ASCII line in the data section.
+    data_offset: u64,
+    /// Rejects trailing data after the last point.
+    strict: bool,
 }
 
 impl<'a, Record> Reader<Record, BufReader<Cursor<&'a [u8]>>>
@@ -75,62 +133,63 @@ where
     Record: PcdDeserialize,
     R: BufRead,
 {
-    pub fn from_reader(mut reader: R) -> Result<Self> {
-        let mut line_count = 0;
-        let meta = crate::utils::load_meta(&mut reader, &mut line_count)?;
-
-        // Checks whether the record schema matches the file meta
-        if !Record::is_dynamic() {
-            let record_spec = Record::read_spec();
-
-            macro_rules! bail {
-                () => {
-                    return Err(Error::new_reader_schema_mismatch_error(
-                        record_spec.clone(),
-                        meta.field_defs.fields.clone(),
-                    ));
-                };
-            }
-
-            if record_spec.len() != meta.field_defs.len() {
-                bail!();
-            }
+    pub fn from_reader(reader: R) -> Result<Self> {
+        Self::from_reader_with_limits(reader, ReaderLimits::default())
+    }
 
-            for (record_field, meta_field) in record_spec.iter().zip(meta.field_defs.iter()) {
-                let (ref name_opt, record_kind, record_count_opt) = *record_field;
-                let FieldDef {
-                    name: ref meta_name,
-                    kind: meta_kind,
-                    count: meta_count,
-                } = *meta_field;
+    /// Creates a reader which checks the input against the limits.
+    pub fn from_reader_with_limits(reader: R, limits: ReaderLimits) -> Result<Self> {
+        ReaderInit {
+            limits,
+            strict: false,
+        }
+        .build_from_reader(reader)
+    }
 
-                if record_kind != meta_kind {
-                    bail!();
-                }
+    /// Creates a reader. If `recovering`, binary_compressed data that is
+    /// cut short or corrupted is decompressed as far as possible.
+    fn new(mut reader: R, init: &ReaderInit, recovering: bool) -> Result<Self> {
+        let ReaderInit { limits, strict } = *init;
+        let mut line_count = 0;
+        let meta = crate::utils::load_meta(&mut reader, &mut line_count, &limits)?;
 
-                if let Some(name) = &name_opt {
-                    if name != meta_name {
-                        bail!();
-                    }
-                }
+        check_schema::<Record>(&meta)?;
 
-                if let Some(record_count) = record_count_opt {
-                    if record_count != meta_count as usize {
-                        bail!();
-                    }
-                }
-            }
+        let expected_len = meta
+            .num_points
+            .saturating_mul(meta.field_defs.point_size() as u64);
+        if strict && meta.width.checked_mul(meta.height) != Some(meta.num_points) {
+            return Err(Error::new_point_count_mismatch_error(
+                meta.width,
+                meta.height,
+                meta.num_points,
+            ));
         }
 
         // For compressed data, read and decompress the entire data section
+        let mut decompress_error = None;
         let decompressed_buffer = if meta.data == DataKind::BinaryCompressed {
             // Read compressed size and uncompressed size
             let compressed_size = reader.read_u32::<LittleEndian>()?;
             let uncompressed_size = reader.read_u32::<LittleEndian>()?;
+            check_compressed_sizes(compressed_size, uncompressed_size, &limits)?;
+            if strict {
+                check_data_len(expected_len, uncompressed_size as u64)?;
+            }
 
             if compressed_size == 0 && uncompressed_size == 0 {
                 // Empty compressed data
                 Some(Cursor::new(Vec::new()))
+            } else if recovering {
+                // Keep the data decompressed before the damage.
+                let mut compressed_data = vec![];
+                (&mut reader)
+                    .take(compressed_size as u64)
+                    .read_to_end(&mut compressed_data)?;
+                let (decompressed, error) =
+                    lzf::decompress_prefix(&compressed_data, uncompressed_size as usize);
+                decompress_error = error;
+                Some(Cursor::new(decompressed))
             } else {
                 // Read compressed data
                 let mut compressed_data = vec![0u8; compressed_size as usize];
@@ -144,12 +203,21 @@ where
             None
         };
 
+        let chunk = vec![0; meta.field_defs.point_size()];
         let pcd_reader = Reader {
             meta,
-            reader,
-            record_count: 0,
-            finished: false,
-            decompressed_buffer,
+            state: DataState {
+                reader,
+                record_count: 0,
+                finished: false,
+                decompressed_buffer,
+                decompress_error,
+                chunk,
+                line: String::new(),
+                max_line_length: limits.max_line_length,
+                data_offset: 0,
+                strict,
+            },
             _phantom: PhantomData,
         };
 
@@ -157,13 +225,194 @@ where
     }
 }
 
+impl<Record, R> Reader<Record, R>
+where
+    Record: PcdDeserialize,
+    R: BufRead + Seek,
+{
+    /// Compares the remaining length of the source with the length of
+    /// the binary data. ASCII data is not checked.
+    fn check_remaining_len(&mut self) -> Result<()> {
+        let reader = &mut self.state.reader;
+        let position = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(position))?;
+        let remaining = end.saturating_sub(position);
+
+        match self.meta.data {
+            DataKind::Binary => {
+                let point_size = self.meta.field_defs.point_size() as u64;
+                check_data_len(self.meta.num_points.saturating_mul(point_size), remaining)
+            }
+            DataKind::BinaryCompressed => check_data_len(0, remaining),
+            DataKind::Ascii => Ok(()),
+        }
+    }
+}
+
+/// Rejects `trailing` bytes after the last point, except for
+/// whitespace after ASCII points.
+fn check_trailing_data(data_kind: DataKind, trailing: u64, is_blank: bool) -> Result<()> {
+    if trailing == 0 || (data_kind == DataKind::Ascii && is_blank) {
+        Ok(())
+    } else {
+        Err(Error::new_trailing_data_error(trailing))
+    }
+}
+
+/// Compares the expected and actual lengths of binary data.
+fn check_data_len(expected: u64, actual: u64) -> Result<()> {
+    if actual < expected {
+        Err(Error::new_truncated_data_error(expected, actual))
+    } else if actual > expected {
+        Err(Error::new_trailing_data_error(actual - expected))
+    } else {
+        Ok(())
+    }
+}
+
+/// A builder type that builds [Reader](crate::reader::Reader).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReaderInit {
+    pub limits: ReaderLimits,
+    /// Rejects inconsistent files.
+    ///
+    /// It checks that WIDTH×HEIGHT equals POINTS, and that the length
+    /// of binary data matches POINTS. The length of binary data is
+    /// checked when a reader is built from a seekable source. For other
+    /// sources, and for non-whitespace data after ASCII points, the
+    /// error is returned after the last point is read.
+    pub strict: bool,
+}
+
+impl ReaderInit {
+    /// Builds new [Reader](crate::reader::Reader) object from a reader.
+    pub fn build_from_reader<Record, R>(self, reader: R) -> Result<Reader<Record, R>>
+    where
+        Record: PcdDeserialize,
+        R: BufRead,
+    {
+        Reader::new(reader, &self, false)
+    }
+
+    /// Builds a reader for [Reader::recover()], which also accepts
+    /// binary_compressed data that is cut short or corrupted.
+    ///
+    /// The data is decompressed up to the damage, so the points before
+    /// it are recovered. Other readers fail on such data when they are
+    /// built.
+    pub fn build_for_recovery<Record, R>(self, reader: R) -> Result<Reader<Record, R>>
+    where
+        Record: PcdDeserialize,
+        R: BufRead,
+    {
+        Reader::new(reader, &self, true)
+    }
+
+    /// Builds new [Reader](crate::reader::Reader) object from a
+    /// seekable reader, which allows checking the data length.
+    pub fn build_from_seekable_reader<Record, R>(self, reader: R) -> Result<Reader<Record, R>>
+    where
+        Record: PcdDeserialize,
+        R: BufRead + Seek,
+    {
+        let mut reader = Reader::new(reader, &self, false)?;
+        if self.strict {
+            reader.check_remaining_len()?;
+        }
+        Ok(reader)
+    }
+
+    /// Builds new [Reader](crate::reader::Reader) by opening a file.
+    pub fn open<Record, P>(self, path: P) -> Result<Reader<Record, BufReader<File>>>
+    where
+        Record: PcdDeserialize,
+        P: AsRef<Path>,
+    {
+        let file = BufReader::new(File::open(path.as_ref())?);
+        self.build_from_seekable_reader(file)
+    }
+}
+
+/// Checks the sizes of binary_compressed data before allocation.
+pub(crate) fn check_compressed_sizes(
+    compressed_size: u32,
+    uncompressed_size: u32,
+    limits: &ReaderLimits,
+) -> Result<()> {
+    let size = compressed_size.max(uncompressed_size) as u64;
+    if size > limits.max_decompressed_bytes {
+        return Err(Error::new_limit_exceeded_error(
+            LimitKind::DecompressedBytes,
+            limits.max_decompressed_bytes,
+            size,
+        ));
+    }
+    Ok(())
+}
+
+/// Checks whether the record schema matches the file meta.
+pub(crate) fn check_schema<Record: PcdDeserialize>(meta: &PcdMeta) -> Result<()> {
+    if Record::is_dynamic() {
+        return Ok(());
+    }
+
+    let (is_matched, expected) = if Record::FIELDS.is_empty() {
+        // The fields are described by read_spec() instead.
+        let expected: Vec<FieldShape> = Record::read_spec()
+            .into_iter()
+            .map(|(name, kind, count)| FieldShape {
+                name,
+                kind,
+                count: count.map(|count| count as u64),
+            })
+            .collect();
+        let is_matched = expected.len() == meta.field_defs.len()
+            && expected
+                .iter()
+                .zip(meta.field_defs.iter())
+                .all(|(shape, def)| {
+                    shape.kind == def.kind
+                        && shape.name.as_ref().is_none_or(|name| *name == def.name)
+                        && shape.count.is_none_or(|count| count == def.count)
+                });
+        (is_matched, Some(expected))
+    } else {
+        let record_spec = Record::FIELDS;
+        let is_matched = record_spec.len() == meta.field_defs.len()
+            && record_spec
+                .iter()
+                .zip(meta.field_defs.iter())
+                .all(|(record_field, meta_field)| record_field.matches(meta_field));
+        (is_matched, None)
+    };
+
+    if !is_matched {
+        let expected =
+            expected.unwrap_or_else(|| Record::FIELDS.iter().map(FieldShape::from).collect());
+        let found: Vec<FieldShape> = meta.field_defs.iter().map(FieldShape::from).collect();
+        return Err(Error::new_reader_schema_mismatch_error(SchemaDiff::new(
+            &expected, &found,
+        )));
+    }
+    Ok(())
+}
+
 impl<Record> Reader<Record, BufReader<File>>
 where
     Record: PcdDeserialize,
 {
     pub fn open(path: impl AsRef<Path>) -> Result<Self> {
-        let file = BufReader::new(File::open(path.as_ref())?);
-        Self::from_reader(file)
+        Self::open_with_limits(path, ReaderLimits::default())
+    }
+
+    /// Opens a file with a reader which checks it against the limits.
+    pub fn open_with_limits(path: impl AsRef<Path>, limits: ReaderLimits) -> Result<Self> {
+        ReaderInit {
+            limits,
+            strict: false,
+        }
+        .open(path)
     }
 }
 
@@ -175,6 +424,406 @@ where
     pub fn meta(&self) -> &PcdMeta {
         &self.meta
     }
+
+    /// Returns a lending iterator of [RawPoint]s, which borrow the
+    /// undecoded bytes of each point.
+    ///
+    /// The points are not decoded into records. Binary points are
+    /// copied into the reusable buffer of the reader, and ASCII points
+    /// are parsed into it, so no allocation happens per point.
+    pub fn raw_points(&mut self) -> RawPoints<'_, R> {
+        RawPoints {
+            meta: &self.meta,
+            state: &mut self.state,
+            offsets: self.meta.field_defs.offsets(),
+        }
+    }
+}
+
+impl<R, Record> Reader<Record, R>
+where
+    R: BufRead,
+    Record: PcdDeserialize,
+{
+    /// Reads at most `max` points at once.
+    ///
+    /// For binary data, records derived with `#[pcd(pod)]` are copied
+    /// from the data with a single memcpy per batch. Otherwise, the
+    /// points are decoded one by one. It returns an empty vector when
+    /// all points are read.
+    pub fn read_batch(&mut self, max: usize) -> Result<Vec<Record>> {
+        let state = &mut self.state;
+        let remaining = (self.meta.num_points as usize).saturating_sub(state.record_count);
+        let count = max.min(remaining);
+
+        if state.finished || count == 0 {
+            if !state.finished && remaining == 0 {
+                state.finished = true;
+                state.check_end(self.meta.data)?;
+            }
+            return Ok(vec![]);
+        }
+
+        if !Record::is_pod() || self.meta.data == DataKind::Ascii {
+            return self.by_ref().take(count).collect();
+        }
+
+        let result = self.read_pod_batch(count);
+        let state = &mut self.state;
+        match result {
+            Ok(_) => state.record_count += count,
+            Err(_) => state.finished = true,
+        }
+        result
+    }
+
+    fn read_pod_batch(&mut self, count: usize) -> Result<Vec<Record>> {
+        let point_size = self.state.chunk.len();
+        let index = self.state.record_count;
+        let byte_offset = self.state.byte_offset(self.meta.data);
+        let schema = &self.meta.field_defs;
+
+        // The count comes from the header, so the size is checked
+        // before reading.
+        let Some(num_bytes) = count.checked_mul(point_size) else {
+            let error = Error::new_limit_exceeded_error(
+                LimitKind::Points,
+                (usize::MAX / point_size) as u64,
+                count as u64,
+            );
+            return Err(point_error(error, index, byte_offset, None, schema));
+        };
+
+        let mut buffer = vec![];
+        let bytes: &[u8] = match self.state.decompressed_buffer {
+            Some(ref mut cursor) => {
+                let begin = cursor.position() as usize;
+                let end = begin.saturating_add(num_bytes);
+                let data = cursor.get_ref();
+
+                if end > data.len() {
+                    let error = Error::new_truncated_data_error(end as u64, data.len() as u64);
+                    return Err(point_error(error, index, byte_offset, None, schema));
+                }
+
+                cursor.set_position(end as u64);
+                &cursor.get_ref()[begin..end]
+            }
+            None => {
+                // The buffer grows with the data actually read instead
+                // of being sized by the header.
+                (&mut self.state.reader)
+                    .take(num_bytes as u64)
+                    .read_to_end(&mut buffer)
+                    .map_err(|error| point_error(error.into(), index, byte_offset, None, schema))?;
+                if buffer.len() < num_bytes {
+                    let error =
+                        Error::new_truncated_data_error(num_bytes as u64, buffer.len() as u64);
+                    return Err(point_error(error, index, byte_offset, None, schema));
+                }
+                &buffer
+            }
+        };
+
+        let mut output = Vec::with_capacity(bytes.len().checked_div(point_size).unwrap_or(0));
+        if !Record::read_pod_slice(bytes, &mut output) {
+            for (nth, chunk) in bytes.chunks_exact(point_size).enumerate() {
+                let record = Record::decode(chunk, schema).map_err(|error| {
+                    let offset = byte_offset + (nth * point_size) as u64;
+                    point_error(error, index + nth, offset, None, schema)
+                })?;
+                output.push(record);
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Takes the data of the remaining points and their count.
+    #[cfg(feature = "parallel")]
+    fn take_remaining(&mut self) -> Result<(Vec<u8>, usize)> {
+        let state = &mut self.state;
+        let remaining = (self.meta.num_points as usize).saturating_sub(state.record_count);
+        if state.finished || remaining == 0 {
+            state.finished = true;
+            return Ok((vec![], 0));
+        }
+        state.finished = true;
+
+        let data = match state.decompressed_buffer {
+            Some(ref mut cursor) => {
+                let position = cursor.position() as usize;
+                let mut data = std::mem::take(cursor.get_mut());
+                data.drain(..position.min(data.len()));
+                data
+            }
+            None => {
+                let mut data = vec![];
+                state.reader.read_to_end(&mut data)?;
+                data
+            }
+        };
+
+        if state.strict {
+            // Truncated data is reported when the points are split.
+            let end = match self.meta.data {
+                DataKind::Ascii => data
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &byte)| byte == b'\n')
+                    .nth(remaining - 1)
+                    .map_or(data.len(), |(index, _)| index + 1),
+                DataKind::Binary => remaining.saturating_mul(state.chunk.len()).min(data.len()),
+                DataKind::BinaryCompressed => data.len(),
+            };
+            let trailing = &data[end..];
+            let is_blank = trailing.iter().all(u8::is_ascii_whitespace);
+            check_trailing_data(self.meta.data, trailing.len() as u64, is_blank)?;
+            state.check_end(self.meta.data)?;
+        }
+
+        Ok((data, remaining))
+    }
+
+    /// Reads the remaining points in parallel.
+    ///
+    /// The data is split into chunks of
+    /// [chunk_points](crate::parallel::ParallelOptions::chunk_points)
+    /// points, which are decoded on the rayon pool. The points are
+    /// returned in the order of the file.
+    #[cfg(feature = "parallel")]
+    pub fn par_collect(&mut self, options: &crate::parallel::ParallelOptions) -> Result<Vec<Record>>
+    where
+        Record: Send,
+    {
+        use crate::parallel;
+
+        let first = (
+            self.state.record_count,
+            self.state.byte_offset(self.meta.data),
+        );
+        let (data, count) = self.take_remaining()?;
+        let meta = &self.meta;
+        let records = parallel::install(options, || {
+            let chunks = parallel::split_chunks(meta, &data, count, first, options)?;
+            parallel::decode_records(chunks, &meta.field_defs)
+        })?;
+        self.state.record_count += records.len();
+        Ok(records)
+    }
+
+    /// Reads the remaining points in parallel into a
+    /// [ColumnarCloud](crate::parallel::ColumnarCloud).
+    #[cfg(feature = "parallel")]
+    pub fn par_collect_columns(
+        &mut self,
+        options: &crate::parallel::ParallelOptions,
+    ) -> Result<crate::parallel::ColumnarCloud> {
+        use crate::parallel;
+
+        let first = (
+            self.state.record_count,
+            self.state.byte_offset(self.meta.data),
+        );
+        let (data, count) = self.take_remaining()?;
+        let meta = &self.meta;
+        let cloud = parallel::install(options, || {
+            let chunks = parallel::split_chunks(meta, &data, count, first, options)?;
+            parallel::decode_columns(chunks, &meta.field_defs)
+        })?;
+        self.state.record_count += cloud.len();
+        Ok(cloud)
+    }
+}
+
+impl<R, Record> Reader<Record, R>
+where
+    R: BufRead + Send + 'static,
+    Record: PcdDeserialize + Send + 'static,
+{
+    /// Reads and decodes the points on a worker thread.
+    ///
+    /// The worker sends batches of points through a bounded channel,
+    /// which holds at most `depth` batches. It stops on the first
+    /// error, which is returned by the iterator.
+    pub fn prefetching(self, depth: usize) -> Prefetching<Record> {
+        let meta = self.meta.clone();
+        let (sender, receiver) = mpsc::sync_channel(depth);
+
+        let handle = thread::spawn(move || {
+            let mut reader = self;
+            loop {
+                match reader.read_batch(PREFETCH_BATCH_SIZE) {
+                    Ok(batch) if batch.is_empty() => break,
+                    Ok(batch) => {
+                        if sender.send(Ok(batch)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(error) => {
+                        let _ = sender.send(Err(error));
+                        break;
+                    }
+                }
+            }
+        });
+
+        Prefetching {
+            meta,
+            receiver: Some(receiver),
+            handle: Some(handle),
+            batch: vec![].into_iter(),
+        }
+    }
+}
+
+/// Options of [Reader::recover()].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecoveryOptions {
+    /// Skips ASCII lines that cannot be decoded instead of stopping.
+    /// The errors are collected in [RecoveryReport::skipped_lines].
+    pub skip_malformed_lines: bool,
+}
+
+/// The outcome of [Reader::recover()].
+///
+/// The indices and byte offsets count from the start of the data
+/// section. For binary_compressed data, the offsets are in the
+/// decompressed data.
+#[derive(Debug, Default)]
+pub struct RecoveryReport {
+    /// The index of the last decoded point.
+    pub last_good_index: Option<usize>,
+    /// The byte offset where reading stopped, which is the end of the
+    /// last decoded point or skipped line.
+    pub byte_offset: u64,
+    /// The error that stopped reading. It is `None` if all points were
+    /// read.
+    pub cause: Option<Error>,
+    /// The malformed ASCII lines skipped.
+    pub skipped_lines: Vec<SkippedLine>,
+}
+
+/// An ASCII line skipped by [Reader::recover()].
+#[derive(Debug)]
+pub struct SkippedLine {
+    /// The index of the point on the line.
+    pub index: usize,
+    /// The byte offset of the line.
+    pub byte_offset: u64,
+    pub error: Error,
+}
+
+impl<R, Record> Reader<Record, R>
+where
+    R: BufRead,
+    Record: PcdDeserialize,
+{
+    /// Reads all decodable points of a possibly corrupted file.
+    ///
+    /// Unlike the iterator, which stops on the first error, it returns
+    /// the points decoded before the error along with a report of where
+    /// and why reading stopped. It should be called on a reader that
+    /// has not been iterated.
+    ///
+    /// binary_compressed data is decompressed when the reader is built,
+    /// which fails if the data is cut short or corrupted, unless the
+    /// reader is built by [ReaderInit::build_for_recovery()].
+    pub fn recover(mut self, options: RecoveryOptions) -> (Vec<Record>, RecoveryReport) {
+        let mut records = vec![];
+        let mut report = RecoveryReport::default();
+        let num_points = self.meta.num_points as usize;
+        let field_defs = &self.meta.field_defs;
+        let state = &mut self.state;
+        let point_size = state.chunk.len() as u64;
+
+        let mut index = state.record_count;
+        let mut offset = state.byte_offset(self.meta.data);
+
+        while !state.finished && index < num_points {
+            let result = match self.meta.data {
+                DataKind::Ascii => {
+                    state.line.clear();
+                    match read_line_limited(
+                        &mut state.reader,
+                        &mut state.line,
+                        state.max_line_length,
+                    ) {
+                        Ok(0) => Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                        Ok(read_size) => {
+                            match Record::decode_line(&state.line, field_defs).map_err(|error| {
+                                point_error(error, index, offset, Some(&state.line), field_defs)
+                            }) {
+                                Ok(record) => {
+                                    offset += read_size as u64;
+                                    Ok(record)
+                                }
+                                Err(error) if options.skip_malformed_lines => {
+                                    report.skipped_lines.push(SkippedLine {
+                                        index,
+                                        byte_offset: offset,
+                                        error,
+                                    });
+                                    offset += read_size as u64;
+                                    index += 1;
+                                    continue;
+                                }
+                                Err(error) => Err(error),
+                            }
+                        }
+                        Err(error) => Err(point_error(error, index, offset, None, field_defs)),
+                    }
+                }
+                DataKind::Binary => state
+                    .reader
+                    .read_exact(&mut state.chunk)
+                    .map_err(Error::from)
+                    .and_then(|()| Record::decode(&state.chunk, field_defs))
+                    .map_err(|error| point_error(error, index, offset, None, field_defs)),
+                DataKind::BinaryCompressed => {
+                    let cursor = state
+                        .decompressed_buffer
+                        .as_mut()
+                        .expect("compressed data buffer is initialized");
+                    let begin = cursor.position() as usize;
+                    let end = begin + point_size as usize;
+                    let len = cursor.get_ref().len() as u64;
+                    match cursor.get_ref().get(begin..end) {
+                        Some(bytes) => {
+                            let record = Record::decode(bytes, field_defs);
+                            cursor.set_position(end as u64);
+                            record
+                        }
+                        None => Err(state
+                            .decompress_error
+                            .take()
+                            .unwrap_or_else(|| Error::new_truncated_data_error(end as u64, len))),
+                    }
+                    .map_err(|error| point_error(error, index, offset, None, field_defs))
+                }
+            };
+
+            match result {
+                Ok(record) => {
+                    if self.meta.data != DataKind::Ascii {
+                        offset += point_size;
+                    }
+                    records.push(record);
+                    report.last_good_index = Some(index);
+                    index += 1;
+                }
+                Err(error) => {
+                    report.cause = Some(error);
+                    break;
+                }
+            }
+        }
+
+        report.byte_offset = offset;
+        state.record_count = index;
+        state.finished = true;
+        (records, report)
+    }
 }
 
 impl<R, Record> Iterator for Reader<Record, R>
@@ -185,49 +834,354 @@ where
     type Item = Result<Record>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        self.state.next_record(&self.meta)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = self.meta.num_points as usize;
+        // The strict mode may end with a trailing data error.
+        (size, size.checked_add(self.state.strict as usize))
+    }
+}
+
+impl<R> DataState<R> {
+    /// Returns the byte offset of the next point in the data section.
+    fn byte_offset(&self, data_kind: DataKind) -> u64 {
+        match (data_kind, &self.decompressed_buffer) {
+            (DataKind::Ascii, _) => self.data_offset,
+            (_, Some(cursor)) => cursor.position(),
+            (_, None) => (self.record_count * self.chunk.len()) as u64,
+        }
+    }
+}
+
+/// Attaches the position of a point to an error. For a token that
+/// cannot be parsed, `line` is searched for the field of the token.
+pub(crate) fn point_error(
+    error: Error,
+    index: usize,
+    byte_offset: u64,
+    line: Option<&str>,
+    schema: &Schema,
+) -> Error {
+    let field_name = match line {
+        Some(line) if error.kind() == ErrorKind::Parse => invalid_field(line, schema),
+        _ => None,
+    };
+    Error::new_point_error(index, byte_offset, field_name, error)
+}
+
+/// Finds the field of the first token that cannot be parsed.
+fn invalid_field<'a>(line: &str, schema: &'a Schema) -> Option<&'a str> {
+    let mut tokens = line.split_ascii_whitespace();
+    for def in schema.iter() {
+        for _ in 0..def.count {
+            let token = tokens.next()?;
+            if match_kind!(def.kind, T => T::parse_token(token).is_err()) {
+                return Some(&def.name);
+            }
+        }
+    }
+    None
+}
+
+impl<R> DataState<R>
+where
+    R: BufRead,
+{
+    fn next_record<Record>(&mut self, meta: &PcdMeta) -> Option<Result<Record>>
+    where
+        Record: PcdDeserialize,
+    {
         if self.finished {
             return None;
         }
 
         // Check if we've already read all points or if there are no points
-        if self.record_count >= self.meta.num_points as usize {
+        if self.record_count >= meta.num_points as usize {
             self.finished = true;
-            return None;
+            return self.check_end(meta.data).err().map(Err);
         }
 
-        let record_result = match self.meta.data {
-            DataKind::Ascii => Record::read_line(&mut self.reader, &self.meta.field_defs),
-            DataKind::Binary => Record::read_chunk(&mut self.reader, &self.meta.field_defs),
+        let index = self.record_count;
+        let byte_offset = self.byte_offset(meta.data);
+
+        let record_result = match meta.data {
+            DataKind::Ascii => {
+                self.line.clear();
+                read_line_limited(&mut self.reader, &mut self.line, self.max_line_length).and_then(
+                    |read_size| {
+                        self.data_offset += read_size as u64;
+                        Record::decode_line(&self.line, &meta.field_defs)
+                    },
+                )
+            }
+            DataKind::Binary => match self.reader.read_exact(&mut self.chunk) {
+                Ok(()) => Record::decode(&self.chunk, &meta.field_defs),
+                Err(error) => Err(error.into()),
+            },
             DataKind::BinaryCompressed => {
                 // Read from decompressed buffer
                 if let Some(ref mut buffer) = self.decompressed_buffer {
-                    Record::read_chunk(buffer, &self.meta.field_defs)
+                    let begin = buffer.position() as usize;
+                    let end = begin + self.chunk.len();
+                    let len = buffer.get_ref().len();
+                    if end <= len {
+                        buffer.set_position(end as u64);
+                        Record::decode(&buffer.get_ref()[begin..end], &meta.field_defs)
+                    } else {
+                        Err(Error::new_truncated_data_error(end as u64, len as u64))
+                    }
                 } else {
-                    return Some(Err(Error::ParseError {
-                        line: 0,
-                        desc: "Compressed data buffer not initialized".into(),
-                    }));
+                    return Some(Err(Error::new_corrupt_data_error(
+                        "Compressed data buffer not initialized",
+                    )));
                 }
             }
         };
 
+        let record_result = record_result.map_err(|error| {
+            let line = (meta.data == DataKind::Ascii).then_some(self.line.as_str());
+            point_error(error, index, byte_offset, line, &meta.field_defs)
+        });
+
         match record_result {
-            Ok(_) => {
-                self.record_count += 1;
-                if self.record_count == self.meta.num_points as usize {
-                    self.finished = true;
-                }
+            Ok(_) => self.record_count += 1,
+            Err(_) => self.finished = true,
+        }
+
+        Some(record_result)
+    }
+
+    /// Checks the rest of the source after the last point in the
+    /// strict mode.
+    fn check_end(&mut self, data_kind: DataKind) -> Result<()> {
+        if !self.strict {
+            return Ok(());
+        }
+
+        // The data is counted without being kept.
+        let mut trailing = 0;
+        let mut is_blank = true;
+        loop {
+            let buffer = self.reader.fill_buf()?;
+            if buffer.is_empty() {
+                break;
+            }
+            is_blank &= buffer.iter().all(u8::is_ascii_whitespace);
+            trailing += buffer.len() as u64;
+            let len = buffer.len();
+            self.reader.consume(len);
+        }
+        check_trailing_data(data_kind, trailing, is_blank)
+    }
+}
+
+/// An iterator of points decoded on a worker thread, created by
+/// [Reader::prefetching()].
+pub struct Prefetching<Record> {
+    meta: PcdMeta,
+    receiver: Option<Receiver<Result<Vec<Record>>>>,
+    handle: Option<JoinHandle<()>>,
+    batch: std::vec::IntoIter<Record>,
+}
+
+impl<Record> Prefetching<Record> {
+    /// Get meta data.
+    pub fn meta(&self) -> &PcdMeta {
+        &self.meta
+    }
+
+    /// Waits for the worker thread and propagates its panic.
+    fn join(&mut self) {
+        self.receiver = None;
+        if let Some(handle) = self.handle.take() {
+            if let Err(payload) = handle.join() {
+                std::panic::resume_unwind(payload);
+            }
+        }
+    }
+}
+
+impl<Record> Iterator for Prefetching<Record> {
+    type Item = Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.batch.next() {
+                return Some(Ok(record));
             }
-            Err(_) => {
-                self.finished = true;
+
+            match self.receiver.as_ref()?.recv() {
+                Ok(Ok(batch)) => self.batch = batch.into_iter(),
+                Ok(Err(error)) => {
+                    self.join();
+                    return Some(Err(error));
+                }
+                Err(_) => {
+                    self.join();
+                    return None;
+                }
             }
         }
+    }
+}
 
-        Some(record_result)
+impl<Record> Drop for Prefetching<Record> {
+    fn drop(&mut self) {
+        // Dropping the receiver stops the worker blocked on sending.
+        self.receiver = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl<R> Reader<DynRecord, R>
+where
+    R: BufRead,
+{
+    /// Returns an iterator of [RecordView]s, which pair the loaded
+    /// records with the schema of the file.
+    pub fn views(&mut self) -> Views<'_, R> {
+        Views {
+            meta: &self.meta,
+            state: &mut self.state,
+        }
+    }
+}
+
+/// An iterator of [RecordView]s created by [DynReader::views()].
+pub struct Views<'a, R> {
+    meta: &'a PcdMeta,
+    state: &'a mut DataState<R>,
+}
+
+impl<'a, R> Iterator for Views<'a, R>
+where
+    R: BufRead,
+{
+    type Item = Result<RecordView<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.state.next_record::<DynRecord>(self.meta)?;
+        Some(result.map(|record| RecordView::new(record, &self.meta.field_defs)))
     }
 
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let size = self.meta.num_points as usize;
-        (size, Some(size))
+        let size = (self.meta.num_points as usize).saturating_sub(self.state.record_count);
+        (size, size.checked_add(self.state.strict as usize))
+    }
+}
+
+/// A lending iterator of [RawPoint]s created by [Reader::raw_points()].
+///
+/// Each point borrows the iterator, so it must be dropped before the
+/// next call to [next()](RawPoints::next).
+pub struct RawPoints<'a, R> {
+    meta: &'a PcdMeta,
+    state: &'a mut DataState<R>,
+    offsets: Vec<usize>,
+}
+
+impl<R> RawPoints<'_, R>
+where
+    R: BufRead,
+{
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<Result<RawPoint<'_>>> {
+        let state = &mut *self.state;
+        if state.finished {
+            return None;
+        }
+
+        if state.record_count >= self.meta.num_points as usize {
+            state.finished = true;
+            return state.check_end(self.meta.data).err().map(Err);
+        }
+
+        let point_size = state.chunk.len();
+        let index = state.record_count;
+        let byte_offset = state.byte_offset(self.meta.data);
+        let result = match (self.meta.data, &mut state.decompressed_buffer) {
+            (DataKind::BinaryCompressed, Some(cursor)) => {
+                let begin = cursor.position() as usize;
+                let end = begin + point_size;
+                let len = cursor.get_ref().len();
+                if end > len {
+                    Err(Error::new_truncated_data_error(end as u64, len as u64))
+                } else {
+                    cursor.set_position(end as u64);
+                    Ok(&cursor.get_ref()[begin..end])
+                }
+            }
+            (DataKind::BinaryCompressed, None) => Err(Error::new_corrupt_data_error(
+                "Compressed data buffer not initialized",
+            )),
+            (DataKind::Binary, _) => state
+                .reader
+                .read_exact(&mut state.chunk)
+                .map(|()| &*state.chunk)
+                .map_err(Error::from),
+            (DataKind::Ascii, _) => parse_line(
+                &mut state.reader,
+                self.meta,
+                &self.offsets,
+                &mut state.line,
+                state.max_line_length,
+                &mut state.chunk,
+            )
+            .map(|read_size| {
+                state.data_offset += read_size as u64;
+                &*state.chunk
+            }),
+        };
+
+        let result = result.map_err(|error| {
+            let line = (self.meta.data == DataKind::Ascii).then_some(state.line.as_str());
+            point_error(error, index, byte_offset, line, &self.meta.field_defs)
+        });
+
+        match result {
+            Ok(_) => state.record_count += 1,
+            Err(_) => state.finished = true,
+        }
+
+        Some(result.map(|bytes| RawPoint::new(bytes, &self.meta.field_defs, &self.offsets)))
     }
 }
+
+/// Parses an ASCII line into the binary layout of a point.
+fn parse_line<R: BufRead>(
+    reader: &mut R,
+    meta: &PcdMeta,
+    offsets: &[usize],
+    line: &mut String,
+    max_line_length: usize,
+    buffer: &mut [u8],
+) -> Result<usize> {
+    line.clear();
+    let read_size = read_line_limited(reader, line, max_line_length)?;
+
+    let schema = &meta.field_defs;
+    let expect: usize = schema.iter().map(|def| def.count as usize).sum();
+    let found = line.split_ascii_whitespace().count();
+    if found != expect {
+        return Err(Error::new_text_token_mismatch_error(expect, found));
+    }
+
+    let mut tokens = line.split_ascii_whitespace();
+    for (def, &offset) in schema.iter().zip(offsets) {
+        let size = def.kind.size();
+        let chunks = buffer[offset..offset + def.size()].chunks_exact_mut(size);
+
+        for (chunk, token) in chunks.zip(&mut tokens) {
+            match_kind!(def.kind, T => {
+                let value = T::parse_token(token)?;
+                chunk.copy_from_slice(&value.to_le_bytes());
+            });
+        }
+    }
+
+    Ok(read_size)
+}
@@ -60,14 +60,16 @@ pub struct TimestampedPoint {
 )]
 use crate::{
     error::Error,
-    metas::{FieldDef, Schema, ValueKind},
-    traits::Value,
+    metas::{FieldDef, FieldShape, Schema, SchemaDiff, StaticFieldDef, ValueKind},
+    traits::{match_field, match_kind, TokenFormat, Value},
     Result,
 };
-use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use itertools::Itertools;
-use num_traits::NumCast;
-use std::io::prelude::*;
+use num_traits::{Bounded, NumCast, ToPrimitive, Zero};
+use std::{
+    borrow::{Borrow, BorrowMut},
+    io::{self, prelude::*},
+};
 
 /// [PcdDeserialize](crate::record::PcdDeserialize) is analogous to a _point_ returned from a reader.
 ///
@@ -76,11 +78,89 @@ use std::io::prelude::*;
 ///
 /// When the PCD data is in Ascii mode, the record is represented by a line of literals.
 /// Otherwise if the data is in binary mode, the record is represented by a fixed size chunk.
+///
+/// Implementations must provide [read_chunk()](PcdDeserialize::read_chunk)
+/// and [read_line()](PcdDeserialize::read_line). The default
+/// [decode()](PcdDeserialize::decode) and
+/// [decode_tokens()](PcdDeserialize::decode_tokens) bridge to them, and
+/// can be overridden to decode without the reader-based calls.
 pub trait PcdDeserialize: Sized {
+    /// The field definitions of the record, known at compile time.
+    ///
+    /// It is empty for dynamic records, and for implementations that
+    /// describe the fields by [read_spec()](PcdDeserialize::read_spec)
+    /// instead.
+    const FIELDS: &'static [StaticFieldDef] = &[];
+
     fn is_dynamic() -> bool;
-    fn read_spec() -> Vec<(Option<String>, ValueKind, Option<usize>)>;
+
+    fn read_spec() -> Vec<(Option<String>, ValueKind, Option<usize>)> {
+        Self::FIELDS
+            .iter()
+            .map(|def| {
+                (
+                    def.name.map(|name| name.to_owned()),
+                    def.kind,
+                    def.count.map(|count| count as usize),
+                )
+            })
+            .collect()
+    }
+
+    /// Decodes a record from the binary bytes of a point.
+    fn decode(bytes: &[u8], field_defs: &Schema) -> Result<Self> {
+        let mut bytes = bytes;
+        Self::read_chunk(&mut bytes, field_defs)
+    }
+
+    /// Decodes a record from the tokens of an ASCII line.
+    ///
+    /// It takes exactly as many tokens as the record has values.
+    fn decode_tokens<'a, I>(tokens: &mut I, field_defs: &Schema) -> Result<Self>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let expect: usize = field_defs.iter().map(|def| def.count as usize).sum();
+        let mut line = String::new();
+        for (index, token) in tokens.by_ref().take(expect).enumerate() {
+            if index > 0 {
+                line.push(' ');
+            }
+            line.push_str(token);
+        }
+        line.push('\n');
+        Self::read_line(&mut line.as_bytes(), field_defs)
+    }
+
+    /// Decodes a record from an ASCII line, checking the number of
+    /// tokens.
+    fn decode_line(line: &str, field_defs: &Schema) -> Result<Self> {
+        let expect = field_defs.iter().map(|def| def.count as usize).sum();
+        let found = line.split_ascii_whitespace().count();
+        if found != expect {
+            return Err(Error::new_text_token_mismatch_error(expect, found));
+        }
+
+        Self::decode_tokens(&mut line.split_ascii_whitespace(), field_defs)
+    }
+
     fn read_chunk<R: BufRead>(reader: &mut R, field_defs: &Schema) -> Result<Self>;
+
     fn read_line<R: BufRead>(reader: &mut R, field_defs: &Schema) -> Result<Self>;
+
+    /// Returns true if the record is derived with `#[pcd(pod)]`.
+    fn is_pod() -> bool {
+        false
+    }
+
+    /// Appends binary records in `bytes` to `output` with a single copy.
+    ///
+    /// It returns false and leaves `output` untouched if the record
+    /// type does not support it.
+    fn read_pod_slice(bytes: &[u8], output: &mut Vec<Self>) -> bool {
+        let _ = (bytes, output);
+        false
+    }
 }
 
 /// [PcdSerialize](crate::record::PcdSerialize) is analogous to a _point_ written by a writer.
@@ -90,11 +170,312 @@ pub trait PcdDeserialize: Sized {
 ///
 /// When the PCD data is in Ascii mode, the record is represented by a line of literals.
 /// Otherwise if the data is in binary mode, the record is represented by a fixed size chunk.
+///
+/// Implementations must provide [write_chunk()](PcdSerialize::write_chunk)
+/// and [write_line()](PcdSerialize::write_line). The default
+/// [encode()](PcdSerialize::encode) and
+/// [encode_tokens()](PcdSerialize::encode_tokens) bridge to them, and
+/// the tokens are written as is, ignoring the
+/// [AsciiFormat](crate::writer::AsciiFormat) of the writer. Override
+/// them to encode without the writer-based calls.
 pub trait PcdSerialize: Sized {
+    /// The field definitions of the record, known at compile time.
+    ///
+    /// It is empty for dynamic records, and for implementations that
+    /// describe the fields by [write_spec()](PcdSerialize::write_spec)
+    /// instead.
+    const FIELDS: &'static [StaticFieldDef] = &[];
+
     fn is_dynamic() -> bool;
-    fn write_spec() -> Schema;
-    fn write_chunk<R: Write + Seek>(&self, writer: &mut R, spec: &Schema) -> Result<()>;
-    fn write_line<R: Write + Seek>(&self, writer: &mut R, spec: &Schema) -> Result<()>;
+
+    fn write_spec() -> Schema {
+        Schema::from(Self::FIELDS)
+    }
+
+    /// Appends the binary bytes of the record to `output`.
+    fn encode(&self, output: &mut Vec<u8>, spec: &Schema) -> Result<()> {
+        self.write_chunk(output, spec)
+    }
+
+    /// Writes the values of the record as ASCII tokens.
+    fn encode_tokens(&self, tokens: &mut TokenWriter<'_>, spec: &Schema) -> Result<()> {
+        let mut line = vec![];
+        self.write_line(&mut line, spec)?;
+        let line = String::from_utf8(line)
+            .map_err(|error| Error::new_invalid_argument_error(&error.to_string()))?;
+        for token in line.split_ascii_whitespace() {
+            tokens.push_token(token);
+        }
+        Ok(())
+    }
+
+    /// Appends the record as an ASCII line, including the line break,
+    /// to `output`.
+    fn encode_line(&self, output: &mut Vec<u8>, spec: &Schema) -> Result<()> {
+        let mut tokens = TokenWriter::new(output);
+        self.encode_tokens(&mut tokens, spec)?;
+        tokens.finish();
+        Ok(())
+    }
+
+    fn write_chunk<R: Write>(&self, writer: &mut R, spec: &Schema) -> Result<()>;
+
+    fn write_line<R: Write>(&self, writer: &mut R, spec: &Schema) -> Result<()>;
+
+    /// Returns true if the record is derived with `#[pcd(pod)]`.
+    fn is_pod() -> bool {
+        false
+    }
+
+    /// Views a slice of records as binary PCD data without copying.
+    ///
+    /// It returns `None` if the record type does not support it.
+    fn pod_bytes(records: &[Self]) -> Option<&[u8]> {
+        let _ = records;
+        None
+    }
+}
+
+/// Implements [read_chunk()](PcdDeserialize::read_chunk) by
+/// [decode()](PcdDeserialize::decode).
+#[doc(hidden)]
+pub fn read_chunk_by_decode<T, R>(reader: &mut R, field_defs: &Schema) -> Result<T>
+where
+    T: PcdDeserialize,
+    R: BufRead,
+{
+    let mut buffer = vec![0; field_defs.point_size()];
+    reader.read_exact(&mut buffer)?;
+    T::decode(&buffer, field_defs)
+}
+
+/// Implements [read_line()](PcdDeserialize::read_line) by
+/// [decode_tokens()](PcdDeserialize::decode_tokens).
+#[doc(hidden)]
+pub fn read_line_by_decode<T, R>(reader: &mut R, field_defs: &Schema) -> Result<T>
+where
+    T: PcdDeserialize,
+    R: BufRead,
+{
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    T::decode_line(&line, field_defs)
+}
+
+/// Implements [write_chunk()](PcdSerialize::write_chunk) by
+/// [encode()](PcdSerialize::encode).
+#[doc(hidden)]
+pub fn write_chunk_by_encode<T, W>(record: &T, writer: &mut W, spec: &Schema) -> Result<()>
+where
+    T: PcdSerialize,
+    W: Write,
+{
+    let mut buffer = Vec::with_capacity(spec.point_size());
+    record.encode(&mut buffer, spec)?;
+    writer.write_all(&buffer)?;
+    Ok(())
+}
+
+/// Implements [write_line()](PcdSerialize::write_line) by
+/// [encode_tokens()](PcdSerialize::encode_tokens).
+#[doc(hidden)]
+pub fn write_line_by_encode<T, W>(record: &T, writer: &mut W, spec: &Schema) -> Result<()>
+where
+    T: PcdSerialize,
+    W: Write,
+{
+    let mut buffer = vec![];
+    record.encode_line(&mut buffer, spec)?;
+    writer.write_all(&buffer)?;
+    Ok(())
+}
+
+/// Takes the tokens of an ASCII line, counting them for error reporting.
+#[doc(hidden)]
+pub struct TokenReader<'b, I> {
+    tokens: &'b mut I,
+    count: usize,
+}
+
+impl<'a, 'b, I> TokenReader<'b, I>
+where
+    I: Iterator<Item = &'a str>,
+{
+    pub fn new(tokens: &'b mut I) -> Self {
+        Self { tokens, count: 0 }
+    }
+
+    pub fn next_token(&mut self, field_defs: &Schema) -> Result<&'a str> {
+        let Some(token) = self.tokens.next() else {
+            let expect = field_defs.iter().map(|def| def.count as usize).sum();
+            return Err(Error::new_text_token_mismatch_error(expect, self.count));
+        };
+        self.count += 1;
+        Ok(token)
+    }
+}
+
+/// Writes space-separated tokens of an ASCII line.
+///
+/// Floats are written in the shortest form that round-trips unless an
+/// [AsciiFormat] is given.
+pub struct TokenWriter<'b> {
+    output: &'b mut Vec<u8>,
+    begin: usize,
+    format: TokenFormat,
+    precisions: &'b [Option<usize>],
+    index: usize,
+}
+
+impl<'b> TokenWriter<'b> {
+    pub fn new(output: &'b mut Vec<u8>) -> Self {
+        Self::with_format(output, TokenFormat::default(), &[])
+    }
+
+    /// Creates a writer with the precision of each token in the line.
+    pub(crate) fn with_format(
+        output: &'b mut Vec<u8>,
+        format: TokenFormat,
+        precisions: &'b [Option<usize>],
+    ) -> Self {
+        let begin = output.len();
+        Self {
+            output,
+            begin,
+            format,
+            precisions,
+            index: 0,
+        }
+    }
+
+    pub fn push(&mut self, value: impl Value) {
+        if self.output.len() > self.begin {
+            self.output.push(b' ');
+        }
+
+        let format = TokenFormat {
+            precision: self.precisions.get(self.index).copied().flatten(),
+            ..self.format
+        };
+        value.write_token(self.output, format);
+        self.index += 1;
+    }
+
+    /// Writes a token that is already formatted.
+    pub(crate) fn push_token(&mut self, token: &str) {
+        if self.output.len() > self.begin {
+            self.output.push(b' ');
+        }
+        self.output.extend_from_slice(token.as_bytes());
+        self.index += 1;
+    }
+
+    pub fn finish(self) {
+        self.output.push(b'\n');
+    }
+}
+
+/// Looks up the field of a record for conversion to a static type.
+///
+/// The field is located by name if both the schema and the name are
+/// given. Otherwise, the field at `index` is returned.
+#[doc(hidden)]
+pub fn dyn_record_field<'a>(
+    record: &'a DynRecord,
+    schema: Option<&Schema>,
+    index: usize,
+    name: Option<&str>,
+    field_name: &str,
+) -> Result<&'a Field> {
+    let index = match (schema, name) {
+        (Some(schema), Some(name)) => schema
+            .iter()
+            .position(|def| def.name == name)
+            .ok_or_else(|| Error::new_field_not_found_error(field_name))?,
+        _ => index,
+    };
+
+    record
+        .0
+        .get(index)
+        .ok_or_else(|| Error::new_field_not_found_error(field_name))
+}
+
+/// Borrows the values of a field for conversion to a static type,
+/// checking the value kind and the element count.
+#[doc(hidden)]
+pub fn dyn_field_values<'a, T: Value>(
+    field: &'a Field,
+    field_name: &str,
+    count: Option<usize>,
+) -> Result<&'a [T]> {
+    let values = T::field_values(field)
+        .ok_or_else(|| Error::new_field_kind_mismatch_error(field_name, T::KIND, field.kind()))?;
+
+    if let Some(count) = count {
+        if values.len() != count {
+            return Err(Error::new_field_size_mismatch_error(
+                field_name,
+                count,
+                values.len(),
+            ));
+        }
+    }
+
+    Ok(values)
+}
+
+/// Appends the bytes to the vector of records with a single copy.
+///
+/// # Safety
+///
+/// `T` must be `#[repr(C)]` without padding, and every field must be
+/// a primitive number or an array of primitive numbers.
+#[doc(hidden)]
+pub unsafe fn pod_extend_from_bytes<T>(bytes: &[u8], output: &mut Vec<T>) -> bool {
+    let size = std::mem::size_of::<T>();
+
+    // PCD binary data is little endian.
+    if cfg!(target_endian = "big") || size == 0 || !bytes.len().is_multiple_of(size) {
+        return false;
+    }
+
+    let count = bytes.len() / size;
+    output.reserve(count);
+    let len = output.len();
+
+    unsafe {
+        std::ptr::copy_nonoverlapping(
+            bytes.as_ptr(),
+            output.as_mut_ptr().add(len) as *mut u8,
+            bytes.len(),
+        );
+        output.set_len(len + count);
+    }
+
+    true
+}
+
+/// Views the records as bytes.
+///
+/// # Safety
+///
+/// `T` must be `#[repr(C)]` without padding, and every field must be
+/// a primitive number or an array of primitive numbers.
+#[doc(hidden)]
+pub unsafe fn pod_as_bytes<T>(records: &[T]) -> Option<&[u8]> {
+    // PCD binary data is little endian.
+    if cfg!(target_endian = "big") {
+        return None;
+    }
+
+    let bytes = unsafe {
+        std::slice::from_raw_parts(
+            records.as_ptr() as *const u8,
+            std::mem::size_of_val(records),
+        )
+    };
+    Some(bytes)
 }
 
 // Runtime record types
@@ -113,6 +494,11 @@ pub enum Field {
 }
 
 impl Field {
+    /// Creates a field of `count` zeros.
+    pub fn zeros(kind: ValueKind, count: usize) -> Self {
+        match_kind!(kind, T => T::into_field(vec![0 as T; count]))
+    }
+
     pub fn kind(&self) -> ValueKind {
         use Field as F;
         use ValueKind as K;
@@ -130,64 +516,102 @@ impl Field {
     }
 
     pub fn count(&self) -> usize {
-        use Field as F;
+        match_field!(self, values => values.len())
+    }
 
-        match self {
-            F::I8(values) => values.len(),
-            F::I16(values) => values.len(),
-            F::I32(values) => values.len(),
-            F::U8(values) => values.len(),
-            F::U16(values) => values.len(),
-            F::U32(values) => values.len(),
-            F::F32(values) => values.len(),
-            F::F64(values) => values.len(),
-        }
+    /// Borrows the values if `T` matches the field kind.
+    pub fn as_slice<T: Value>(&self) -> Option<&[T]> {
+        T::field_values(self)
     }
 
-    pub fn to_value<T>(&self) -> Option<T>
-    where
-        T: Value + NumCast,
-    {
-        use Field as F;
+    /// Mutably borrows the values if `T` matches the field kind.
+    pub fn as_mut_slice<T: Value>(&mut self) -> Option<&mut [T]> {
+        T::field_values_mut(self)
+    }
 
-        if T::KIND != self.kind() {
-            return None;
+    /// Gets the value of a single-element field if `T` matches the
+    /// field kind.
+    pub fn to_value<T: Value>(&self) -> Option<T> {
+        match self.as_slice::<T>()? {
+            &[value] => Some(value),
+            _ => None,
         }
+    }
 
-        Some(match self {
-            F::I8(v) => match &**v {
-                &[t] => T::from(t)?,
-                _ => return None,
-            },
-            F::I16(v) => match &**v {
-                &[t] => T::from(t)?,
-                _ => return None,
-            },
-            F::I32(v) => match &**v {
-                &[t] => T::from(t)?,
-                _ => return None,
-            },
-            F::U8(v) => match &**v {
-                &[t] => T::from(t)?,
-                _ => return None,
-            },
-            F::U16(v) => match &**v {
-                &[t] => T::from(t)?,
-                _ => return None,
-            },
-            F::U32(v) => match &**v {
-                &[t] => T::from(t)?,
-                _ => return None,
-            },
-            F::F32(v) => match &**v {
-                &[t] => T::from(t)?,
-                _ => return None,
-            },
-            F::F64(v) => match &**v {
-                &[t] => T::from(t)?,
-                _ => return None,
-            },
-        })
+    /// Converts the values to `f64`, which represents every value kind
+    /// exactly.
+    #[allow(clippy::unnecessary_cast)]
+    pub fn to_f64_vec(&self) -> Vec<f64> {
+        match_field!(self, values => values.iter().map(|&value| value as f64).collect())
+    }
+
+    /// Converts the values to another kind.
+    ///
+    /// Fractional parts are truncated towards zero when converting to
+    /// integers. It fails if any value is out of the range of the
+    /// target kind, or is NaN when converting to integers.
+    pub fn cast(&self, kind: ValueKind) -> Result<Field> {
+        let from = self.kind();
+        match_field!(self, values => match_kind!(kind, T => {
+            let values: Vec<T> = values
+                .iter()
+                .map(|&value| {
+                    checked_cast(value).ok_or_else(|| {
+                        Error::new_value_cast_error(&value.to_string(), from, kind)
+                    })
+                })
+                .try_collect()?;
+            Ok(T::into_field(values))
+        }))
+    }
+
+    /// Converts the values to another kind, clamping out-of-range values
+    /// to the bounds of the target kind.
+    ///
+    /// Fractional parts are truncated towards zero and NaN becomes zero
+    /// when converting to integers.
+    pub fn cast_saturating(&self, kind: ValueKind) -> Field {
+        match_field!(self, values => match_kind!(kind, T => {
+            let values: Vec<T> = values.iter().map(|&value| saturating_cast(value)).collect();
+            T::into_field(values)
+        }))
+    }
+}
+
+impl<T: Value> From<Vec<T>> for Field {
+    fn from(values: Vec<T>) -> Self {
+        T::into_field(values)
+    }
+}
+
+fn checked_cast<S, T>(value: S) -> Option<T>
+where
+    S: ToPrimitive + Copy,
+    T: NumCast + ToPrimitive,
+{
+    let output = T::from(value)?;
+
+    // Float narrowing yields infinity instead of failing.
+    if value.to_f64()?.is_finite() && !output.to_f64()?.is_finite() {
+        return None;
+    }
+
+    Some(output)
+}
+
+fn saturating_cast<S, T>(value: S) -> T
+where
+    S: ToPrimitive + Copy,
+    T: NumCast + ToPrimitive + Bounded + Zero,
+{
+    if let Some(output) = checked_cast(value) {
+        return output;
+    }
+
+    match value.to_f64() {
+        Some(value) if value < 0.0 => T::min_value(),
+        Some(value) if value > 0.0 => T::max_value(),
+        _ => T::zero(),
     }
 }
 
@@ -196,6 +620,14 @@ impl Field {
 pub struct DynRecord(pub Vec<Field>);
 
 impl DynRecord {
+    /// Compares the fields of the record with the schema. The fields
+    /// of the record have no names, so they are compared by position.
+    pub fn schema_diff(&self, schema: &Schema) -> SchemaDiff {
+        let expected: Vec<FieldShape> = schema.iter().map(FieldShape::from).collect();
+        let found: Vec<FieldShape> = self.0.iter().map(FieldShape::from).collect();
+        SchemaDiff::new(&expected, &found)
+    }
+
     pub fn is_schema_consistent(&self, schema: &Schema) -> bool {
         if self.0.len() != schema.len() {
             return false;
@@ -226,6 +658,21 @@ impl DynRecord {
             })
     }
 
+    /// Pairs the record with the schema for name-based access.
+    pub fn view<'a>(&'a self, schema: &'a Schema) -> RecordView<'a, &'a DynRecord> {
+        RecordView::new(self, schema)
+    }
+
+    /// Pairs the record with the schema for name-based access and
+    /// mutation.
+    pub fn view_mut<'a>(&'a mut self, schema: &'a Schema) -> RecordView<'a, &'a mut DynRecord> {
+        RecordView::new(self, schema)
+    }
+
+    /// Gets the first three fields as an array.
+    ///
+    /// It assumes the first three fields are x, y and z in order. Use
+    /// [RecordView::xyz()] to look up the fields by name.
     pub fn to_xyz<T>(&self) -> Option<[T; 3]>
     where
         T: Value + NumCast,
@@ -274,379 +721,347 @@ impl DynRecord {
     }
 }
 
+/// A [DynRecord] paired with its [Schema], giving access to fields by name.
+///
+/// The record is either owned, borrowed or mutably borrowed. The
+/// [set()](RecordView::set) method is available if the record is
+/// owned or mutably borrowed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordView<'a, R = DynRecord>
+where
+    R: Borrow<DynRecord>,
+{
+    record: R,
+    schema: &'a Schema,
+}
+
+impl<'a, R> RecordView<'a, R>
+where
+    R: Borrow<DynRecord>,
+{
+    pub fn new(record: R, schema: &'a Schema) -> Self {
+        Self { record, schema }
+    }
+
+    pub fn record(&self) -> &DynRecord {
+        self.record.borrow()
+    }
+
+    pub fn schema(&self) -> &'a Schema {
+        self.schema
+    }
+
+    pub fn into_record(self) -> R {
+        self.record
+    }
+
+    /// Gets the field with the given name.
+    pub fn field(&self, name: &str) -> Result<&Field> {
+        dyn_record_field(self.record(), Some(self.schema), 0, Some(name), name)
+    }
+
+    /// Gets the value of a single-element field.
+    pub fn get<T: Value>(&self, name: &str) -> Result<T> {
+        let field = self.field(name)?;
+        let values = dyn_field_values::<T>(field, name, Some(1))?;
+        Ok(values[0])
+    }
+
+    /// Gets the values of a field.
+    pub fn get_slice<T: Value>(&self, name: &str) -> Result<&[T]> {
+        let field = self.field(name)?;
+        dyn_field_values::<T>(field, name, None)
+    }
+
+    /// Gets the values of `x`, `y` and `z` fields.
+    pub fn xyz<T: Value>(&self) -> Result<[T; 3]> {
+        Ok([self.get("x")?, self.get("y")?, self.get("z")?])
+    }
+}
+
+impl<R> RecordView<'_, R>
+where
+    R: BorrowMut<DynRecord>,
+{
+    /// Gets the mutable field with the given name.
+    pub fn field_mut(&mut self, name: &str) -> Result<&mut Field> {
+        let index = self
+            .schema
+            .iter()
+            .position(|def| def.name == name)
+            .ok_or_else(|| Error::new_field_not_found_error(name))?;
+        self.record
+            .borrow_mut()
+            .0
+            .get_mut(index)
+            .ok_or_else(|| Error::new_field_not_found_error(name))
+    }
+
+    /// Sets the value of a single-element field.
+    ///
+    /// The value type must match the field kind.
+    pub fn set<T: Value>(&mut self, name: &str, value: T) -> Result<()> {
+        self.set_slice(name, &[value])
+    }
+
+    /// Sets the values of a field.
+    ///
+    /// The value type must match the field kind, and the number of
+    /// values must match the field count.
+    pub fn set_slice<T: Value>(&mut self, name: &str, values: &[T]) -> Result<()> {
+        let field = self.field_mut(name)?;
+        dyn_field_values::<T>(field, name, Some(values.len()))?;
+        *field = T::into_field(values.to_vec());
+        Ok(())
+    }
+}
+
+/// The undecoded binary bytes of a _point_, yielded by
+/// [RawPoints](crate::reader::RawPoints).
+///
+/// The bytes are laid out as in binary PCD data regardless of the data
+/// kind of the source.
+#[derive(Debug, Clone, Copy)]
+pub struct RawPoint<'a> {
+    bytes: &'a [u8],
+    schema: &'a Schema,
+    offsets: &'a [usize],
+}
+
+impl<'a> RawPoint<'a> {
+    pub(crate) fn new(bytes: &'a [u8], schema: &'a Schema, offsets: &'a [usize]) -> Self {
+        Self {
+            bytes,
+            schema,
+            offsets,
+        }
+    }
+
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    pub fn schema(&self) -> &'a Schema {
+        self.schema
+    }
+
+    /// Gets the bytes of the field at `index`.
+    pub fn field_bytes(&self, index: usize) -> Option<&'a [u8]> {
+        let def = self.schema.fields.get(index)?;
+        let begin = self.offsets[index];
+        Some(&self.bytes[begin..begin + def.size()])
+    }
+
+    /// Gets the value of the single-element field at `index`.
+    pub fn get<T: Value>(&self, index: usize) -> Result<T> {
+        let def = self.field_def(index)?;
+        if def.count != 1 {
+            return Err(Error::new_field_size_mismatch_error(
+                &def.name,
+                1,
+                def.count as usize,
+            ));
+        }
+        self.get_element(index, 0)
+    }
+
+    /// Gets the `element`-th value of the field at `index`.
+    pub fn get_element<T: Value>(&self, index: usize, element: usize) -> Result<T> {
+        let def = self.field_def(index)?;
+        if def.kind != T::KIND {
+            return Err(Error::new_field_kind_mismatch_error(
+                &def.name,
+                T::KIND,
+                def.kind,
+            ));
+        }
+        if element >= def.count as usize {
+            let desc = format!(
+                "element {element} is out of range of field \"{}\" with {} elements",
+                def.name, def.count
+            );
+            return Err(Error::new_invalid_argument_error(&desc));
+        }
+
+        let size = def.kind.size();
+        let begin = self.offsets[index] + element * size;
+        Ok(T::from_le_slice(&self.bytes[begin..begin + size]))
+    }
+
+    /// Gets the value of the single-element field with the given name.
+    pub fn get_by_name<T: Value>(&self, name: &str) -> Result<T> {
+        let index = self
+            .schema
+            .index_of(name)
+            .ok_or_else(|| Error::new_field_not_found_error(name))?;
+        self.get(index)
+    }
+
+    /// Decodes the point into a [DynRecord].
+    pub fn to_record(&self) -> Result<DynRecord> {
+        DynRecord::decode(self.bytes, self.schema)
+    }
+
+    fn field_def(&self, index: usize) -> Result<&'a FieldDef> {
+        self.schema.fields.get(index).ok_or_else(|| {
+            let desc = format!(
+                "field index {index} is out of range of {} fields",
+                self.schema.len()
+            );
+            Error::new_invalid_argument_error(&desc)
+        })
+    }
+}
+
 impl PcdSerialize for DynRecord {
+    const FIELDS: &'static [StaticFieldDef] = &[];
+
     fn is_dynamic() -> bool {
         true
     }
 
-    fn write_spec() -> Schema {
-        unreachable!();
-    }
-
-    fn write_chunk<Writer>(&self, writer: &mut Writer, spec: &Schema) -> Result<()>
-    where
-        Writer: Write + Seek,
-    {
+    fn encode(&self, output: &mut Vec<u8>, spec: &Schema) -> Result<()> {
         if !self.is_schema_consistent(spec) {
             return Err(Error::new_writer_schema_mismatch_error(
-                Self::write_spec().fields,
-                spec.fields.to_vec(),
+                self.schema_diff(spec),
             ));
         }
 
         for field in self.0.iter() {
-            use Field as F;
-
-            match field {
-                F::I8(values) => {
-                    values
-                        .iter()
-                        .map(|val| Ok(writer.write_i8(*val)?))
-                        .collect::<Result<Vec<_>>>()?;
-                }
-                F::I16(values) => {
-                    values
-                        .iter()
-                        .map(|val| Ok(writer.write_i16::<LittleEndian>(*val)?))
-                        .collect::<Result<Vec<_>>>()?;
-                }
-                F::I32(values) => {
-                    values
-                        .iter()
-                        .map(|val| Ok(writer.write_i32::<LittleEndian>(*val)?))
-                        .collect::<Result<Vec<_>>>()?;
-                }
-                F::U8(values) => {
-                    values
-                        .iter()
-                        .map(|val| Ok(writer.write_u8(*val)?))
-                        .collect::<Result<Vec<_>>>()?;
-                }
-                F::U16(values) => {
-                    values
-                        .iter()
-                        .map(|val| Ok(writer.write_u16::<LittleEndian>(*val)?))
-                        .collect::<Result<Vec<_>>>()?;
-                }
-                F::U32(values) => {
-                    values
-                        .iter()
-                        .map(|val| Ok(writer.write_u32::<LittleEndian>(*val)?))
-                        .collect::<Result<Vec<_>>>()?;
-                }
-                F::F32(values) => {
-                    values
-                        .iter()
-                        .map(|val| Ok(writer.write_f32::<LittleEndian>(*val)?))
-                        .collect::<Result<Vec<_>>>()?;
-                }
-                F::F64(values) => {
-                    values
-                        .iter()
-                        .map(|val| Ok(writer.write_f64::<LittleEndian>(*val)?))
-                        .collect::<Result<Vec<_>>>()?;
+            match_field!(field, values => {
+                for value in values {
+                    output.extend_from_slice(&value.to_le_bytes());
                 }
-            }
+            });
         }
-
         Ok(())
     }
 
-    fn write_line<Writer>(&self, writer: &mut Writer, spec: &Schema) -> Result<()>
-    where
-        Writer: Write + Seek,
-    {
+    fn encode_tokens(&self, tokens: &mut TokenWriter<'_>, spec: &Schema) -> Result<()> {
         if !self.is_schema_consistent(spec) {
             return Err(Error::new_writer_schema_mismatch_error(
-                Self::write_spec().fields,
-                spec.fields.to_vec(),
+                self.schema_diff(spec),
             ));
         }
 
-        let mut tokens = vec![];
-
         for field in self.0.iter() {
-            use Field as F;
-
-            match field {
-                F::I8(values) => {
-                    let iter = values.iter().map(|val| val.to_string());
-                    tokens.extend(iter);
-                }
-                F::I16(values) => {
-                    let iter = values.iter().map(|val| val.to_string());
-                    tokens.extend(iter);
-                }
-                F::I32(values) => {
-                    let iter = values.iter().map(|val| val.to_string());
-                    tokens.extend(iter);
-                }
-                F::U8(values) => {
-                    let iter = values.iter().map(|val| val.to_string());
-                    tokens.extend(iter);
+            match_field!(field, values => {
+                for &value in values {
+                    tokens.push(value);
                 }
-                F::U16(values) => {
-                    let iter = values.iter().map(|val| val.to_string());
-                    tokens.extend(iter);
-                }
-                F::U32(values) => {
-                    let iter = values.iter().map(|val| val.to_string());
-                    tokens.extend(iter);
-                }
-                F::F32(values) => {
-                    let iter = values.iter().map(|val| val.to_string());
-                    tokens.extend(iter);
-                }
-                F::F64(values) => {
-                    let iter = values.iter().map(|val| val.to_string());
-                    tokens.extend(iter);
-                }
-            }
+            });
         }
+        Ok(())
+    }
 
-        writeln!(writer, "{}", tokens.join(" "))?;
+    fn write_chunk<R: Write>(&self, writer: &mut R, spec: &Schema) -> Result<()> {
+        write_chunk_by_encode(self, writer, spec)
+    }
 
-        Ok(())
+    fn write_line<R: Write>(&self, writer: &mut R, spec: &Schema) -> Result<()> {
+        write_line_by_encode(self, writer, spec)
     }
 }
 
 impl PcdDeserialize for DynRecord {
+    const FIELDS: &'static [StaticFieldDef] = &[];
+
     fn is_dynamic() -> bool {
         true
     }
 
-    fn read_spec() -> Vec<(Option<String>, ValueKind, Option<usize>)> {
-        unreachable!();
-    }
-
-    fn read_chunk<R: BufRead>(reader: &mut R, field_defs: &Schema) -> Result<Self> {
-        use Field as F;
-        use ValueKind as K;
+    fn decode(bytes: &[u8], field_defs: &Schema) -> Result<Self> {
+        if bytes.len() < field_defs.point_size() {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
 
+        let mut offset = 0;
         let fields = field_defs
             .iter()
             .map(|def| {
-                let FieldDef { kind, count, .. } = *def;
-
-                let counter = 0..count;
-
-                let field = match kind {
-                    K::I8 => {
-                        let values = counter
-                            .map(|_| Ok(reader.read_i8()?))
-                            .collect::<Result<Vec<_>>>()?;
-                        F::I8(values)
-                    }
-                    K::I16 => {
-                        let values = counter
-                            .map(|_| Ok(reader.read_i16::<LittleEndian>()?))
-                            .collect::<Result<Vec<_>>>()?;
-                        F::I16(values)
-                    }
-                    K::I32 => {
-                        let values = counter
-                            .map(|_| Ok(reader.read_i32::<LittleEndian>()?))
-                            .collect::<Result<Vec<_>>>()?;
-                        F::I32(values)
-                    }
-                    K::U8 => {
-                        let values = counter
-                            .map(|_| Ok(reader.read_u8()?))
-                            .collect::<Result<Vec<_>>>()?;
-                        F::U8(values)
-                    }
-                    K::U16 => {
-                        let values = counter
-                            .map(|_| Ok(reader.read_u16::<LittleEndian>()?))
-                            .collect::<Result<Vec<_>>>()?;
-                        F::U16(values)
-                    }
-                    K::U32 => {
-                        let values = counter
-                            .map(|_| Ok(reader.read_u32::<LittleEndian>()?))
-                            .collect::<Result<Vec<_>>>()?;
-                        F::U32(values)
-                    }
-                    K::F32 => {
-                        let values = counter
-                            .map(|_| Ok(reader.read_f32::<LittleEndian>()?))
-                            .collect::<Result<Vec<_>>>()?;
-                        F::F32(values)
-                    }
-                    K::F64 => {
-                        let values = counter
-                            .map(|_| Ok(reader.read_f64::<LittleEndian>()?))
-                            .collect::<Result<Vec<_>>>()?;
-                        F::F64(values)
-                    }
-                };
-
-                Ok(field)
+                let size = def.kind.size();
+                let chunks = bytes[offset..offset + def.size()].chunks_exact(size);
+                offset += def.size();
+
+                match_kind!(def.kind, T => T::into_field(chunks.map(T::from_le_slice).collect()))
             })
-            .collect::<Result<Vec<_>>>()?;
+            .collect();
         Ok(Self(fields))
     }
 
-    fn read_line<R: BufRead>(reader: &mut R, field_defs: &Schema) -> Result<Self> {
-        let mut line = String::new();
-        reader.read_line(&mut line)?;
-        let tokens = line.split_ascii_whitespace().collect::<Vec<_>>();
-
-        {
-            let expect = field_defs.iter().map(|def| def.count as usize).sum();
-            if tokens.len() != expect {
-                return Err(Error::new_text_token_mismatch_error(expect, tokens.len()));
-            }
-        }
-
-        let mut tokens_iter = tokens.into_iter();
+    fn decode_tokens<'a, I>(tokens: &mut I, field_defs: &Schema) -> Result<Self>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let mut tokens = TokenReader::new(tokens);
 
         let fields: Vec<Field> = field_defs
             .iter()
-            .map(|def| -> Result<_, Error> {
-                let FieldDef { kind, count, .. } = *def;
-
-                let count = count as usize;
-
-                let field = match kind {
-                    ValueKind::I8 => {
-                        let values: Vec<i8> = (&mut tokens_iter)
-                            .map(|token| token.parse())
-                            .take(count)
-                            .try_collect()?;
-                        Field::I8(values)
-                    }
-                    ValueKind::I16 => {
-                        let values: Vec<i16> = (&mut tokens_iter)
-                            .map(|token| token.parse())
-                            .take(count)
-                            .try_collect()?;
-                        Field::I16(values)
-                    }
-                    ValueKind::I32 => {
-                        let values: Vec<i32> = (&mut tokens_iter)
-                            .map(|token| token.parse())
-                            .take(count)
-                            .try_collect()?;
-                        Field::I32(values)
-                    }
-                    ValueKind::U8 => {
-                        let values: Vec<u8> = (&mut tokens_iter)
-                            .map(|token| token.parse())
-                            .take(count)
-                            .try_collect()?;
-                        Field::U8(values)
-                    }
-                    ValueKind::U16 => {
-                        let values: Vec<u16> = (&mut tokens_iter)
-                            .map(|token| token.parse())
-                            .take(count)
-                            .try_collect()?;
-                        Field::U16(values)
-                    }
-                    ValueKind::U32 => {
-                        let values: Vec<u32> = (&mut tokens_iter)
-                            .map(|token| token.parse())
-                            .take(count)
-                            .try_collect()?;
-                        Field::U32(values)
-                    }
-                    ValueKind::F32 => {
-                        let values: Vec<f32> = (&mut tokens_iter)
-                            .map(|token| token.parse())
-                            .take(count)
-                            .try_collect()?;
-                        Field::F32(values)
-                    }
-                    ValueKind::F64 => {
-                        let values: Vec<f64> = (&mut tokens_iter)
-                            .map(|token| token.parse())
-                            .take(count)
-                            .try_collect()?;
-                        Field::F64(values)
-                    }
-                };
-
-                Ok(field)
+            .map(|def| -> Result<_> {
+                match_kind!(def.kind, T => {
+                    let values: Vec<T> = (0..def.count)
+                        .map(|_| T::parse_token(tokens.next_token(field_defs)?))
+                        .collect::<Result<_>>()?;
+                    Ok(T::into_field(values))
+                })
             })
             .try_collect()?;
 
         Ok(Self(fields))
     }
-}
 
-// impl for primitive types
-
-impl PcdDeserialize for u8 {
-    fn is_dynamic() -> bool {
-        false
-    }
-
-    fn read_spec() -> Vec<(Option<String>, ValueKind, Option<usize>)> {
-        vec![(None, ValueKind::U8, Some(1))]
-    }
-
-    fn read_chunk<R: BufRead>(reader: &mut R, _field_defs: &Schema) -> Result<Self> {
-        let value = reader.read_u8()?;
-        Ok(value)
+    fn read_chunk<R: BufRead>(reader: &mut R, field_defs: &Schema) -> Result<Self> {
+        read_chunk_by_decode(reader, field_defs)
     }
 
-    fn read_line<R: BufRead>(reader: &mut R, _field_defs: &Schema) -> Result<Self> {
-        let mut line = String::new();
-        reader.read_line(&mut line)?;
-        Ok(line.parse()?)
+    fn read_line<R: BufRead>(reader: &mut R, field_defs: &Schema) -> Result<Self> {
+        read_line_by_decode(reader, field_defs)
     }
 }
 
-impl PcdDeserialize for i8 {
-    fn is_dynamic() -> bool {
-        false
-    }
-
-    fn read_spec() -> Vec<(Option<String>, ValueKind, Option<usize>)> {
-        vec![(None, ValueKind::I8, Some(1))]
-    }
-
-    fn read_chunk<R: BufRead>(reader: &mut R, _field_defs: &Schema) -> Result<Self> {
-        let value = reader.read_i8()?;
-        Ok(value)
-    }
-
-    fn read_line<R: BufRead>(reader: &mut R, _field_defs: &Schema) -> Result<Self> {
-        let mut line = String::new();
-        reader.read_line(&mut line)?;
-        Ok(line.parse()?)
-    }
-}
+// impl for primitive types
 
 macro_rules! impl_primitive {
-    ($ty:ty, $kind:ident, $read:ident) => {
+    ($ty:ty, $kind:ident) => {
         impl PcdDeserialize for $ty {
+            const FIELDS: &'static [StaticFieldDef] =
+                &[StaticFieldDef::new(None, ValueKind::$kind, Some(1))];
+
             fn is_dynamic() -> bool {
                 false
             }
 
-            fn read_spec() -> Vec<(Option<String>, ValueKind, Option<usize>)> {
-                vec![(None, ValueKind::$kind, Some(1))]
+            fn decode(bytes: &[u8], _field_defs: &Schema) -> Result<Self> {
+                match bytes.get(..std::mem::size_of::<Self>()) {
+                    Some(bytes) => Ok(Self::from_le_slice(bytes)),
+                    None => Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+                }
+            }
+
+            fn decode_tokens<'a, I>(tokens: &mut I, field_defs: &Schema) -> Result<Self>
+            where
+                I: Iterator<Item = &'a str>,
+            {
+                let token = TokenReader::new(tokens).next_token(field_defs)?;
+                Self::parse_token(token)
             }
 
-            fn read_chunk<R: BufRead>(reader: &mut R, _field_defs: &Schema) -> Result<Self> {
-                let value = reader.$read::<LittleEndian>()?;
-                Ok(value)
+            fn read_chunk<R: BufRead>(reader: &mut R, field_defs: &Schema) -> Result<Self> {
+                read_chunk_by_decode(reader, field_defs)
             }
 
-            fn read_line<R: BufRead>(reader: &mut R, _field_defs: &Schema) -> Result<Self> {
-                let mut line = String::new();
-                reader.read_line(&mut line)?;
-                Ok(line.parse()?)
+            fn read_line<R: BufRead>(reader: &mut R, field_defs: &Schema) -> Result<Self> {
+                read_line_by_decode(reader, field_defs)
             }
         }
     };
 }
 
-impl_primitive!(u16, U16, read_u16);
-impl_primitive!(u32, U32, read_u32);
-impl_primitive!(i16, I16, read_i16);
-impl_primitive!(i32, I32, read_i32);
-impl_primitive!(f32, F32, read_f32);
-impl_primitive!(f64, F64, read_f64);
+impl_primitive!(u8, U8);
+impl_primitive!(u16, U16);
+impl_primitive!(u32, U32);
+impl_primitive!(i8, I8);
+impl_primitive!(i16, I16);
+impl_primitive!(i32, I32);
+impl_primitive!(f32, F32);
+impl_primitive!(f64, F64);
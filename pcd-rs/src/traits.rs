@@ -1,6 +1,7 @@
 //! Traits definitions.
 
-use crate::ValueKind;
+use crate::{record::Field, writer::NanSpelling, Result, ValueKind};
+use std::io::Write as _;
 
 /// This trait assocaites Rust primitive types to PCD primitive types.
 pub trait Value
@@ -8,36 +9,212 @@ where
     Self: Copy,
 {
     const KIND: ValueKind;
+
+    /// Borrows the values of the field if the field has the same kind.
+    fn field_values(field: &Field) -> Option<&[Self]>;
+
+    /// Mutably borrows the values of the field if the field has the same kind.
+    fn field_values_mut(field: &mut Field) -> Option<&mut [Self]>;
+
+    /// Wraps the values into a field.
+    fn into_field(values: Vec<Self>) -> Field;
+
+    /// Decodes a value from little endian bytes.
+    ///
+    /// It panics if the length of `bytes` differs from the value size.
+    fn from_le_slice(bytes: &[u8]) -> Self;
+
+    /// Parses a value from an ASCII token.
+    ///
+    /// Floats accept `nan`, `-nan`, `NaN`, `inf` and `-inf` as written
+    /// by PCL and numpy.
+    #[doc(hidden)]
+    fn parse_token(token: &str) -> Result<Self>;
+
+    /// Appends the value as an ASCII token.
+    #[doc(hidden)]
+    fn write_token(self, output: &mut Vec<u8>, format: TokenFormat);
 }
 
-impl Value for u8 {
-    const KIND: ValueKind = ValueKind::U8;
+/// The formatting of a single ASCII token.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenFormat {
+    pub precision: Option<usize>,
+    pub scientific: bool,
+    pub nan: NanSpelling,
 }
 
-impl Value for u16 {
-    const KIND: ValueKind = ValueKind::U16;
+macro_rules! impl_value {
+    ($ty:ty, $kind:ident, $parse:expr, $write:expr) => {
+        impl Value for $ty {
+            const KIND: ValueKind = ValueKind::$kind;
+
+            fn field_values(field: &Field) -> Option<&[Self]> {
+                match field {
+                    Field::$kind(values) => Some(values),
+                    _ => None,
+                }
+            }
+
+            fn field_values_mut(field: &mut Field) -> Option<&mut [Self]> {
+                match field {
+                    Field::$kind(values) => Some(values),
+                    _ => None,
+                }
+            }
+
+            fn into_field(values: Vec<Self>) -> Field {
+                Field::$kind(values)
+            }
+
+            fn from_le_slice(bytes: &[u8]) -> Self {
+                Self::from_le_bytes(bytes.try_into().unwrap())
+            }
+
+            fn parse_token(token: &str) -> Result<Self> {
+                $parse(token)
+            }
+
+            fn write_token(self, output: &mut Vec<u8>, format: TokenFormat) {
+                $write(self, output, format)
+            }
+        }
+    };
+    ($ty:ty, $kind:ident, int) => {
+        impl_value!($ty, $kind, parse_int, write_int);
+    };
+    ($ty:ty, $kind:ident, float) => {
+        impl_value!($ty, $kind, parse_float, write_float);
+    };
 }
 
-impl Value for u32 {
-    const KIND: ValueKind = ValueKind::U32;
+fn parse_int<T: std::str::FromStr>(token: &str) -> Result<T>
+where
+    crate::Error: From<T::Err>,
+{
+    Ok(token.parse()?)
 }
 
-impl Value for i8 {
-    const KIND: ValueKind = ValueKind::I8;
+fn parse_float<T>(token: &str) -> Result<T>
+where
+    T: fast_float2::FastFloat + std::str::FromStr<Err = std::num::ParseFloatError>,
+{
+    // Fall back to the standard parser to report the error.
+    match fast_float2::parse(token) {
+        Ok(value) => Ok(value),
+        Err(_) => Ok(token.parse()?),
+    }
 }
 
-impl Value for i16 {
-    const KIND: ValueKind = ValueKind::I16;
+fn write_int<T: itoa::Integer>(value: T, output: &mut Vec<u8>, _format: TokenFormat) {
+    output.extend_from_slice(itoa::Buffer::new().format(value).as_bytes());
 }
 
-impl Value for i32 {
-    const KIND: ValueKind = ValueKind::I32;
+fn write_float<T>(value: T, output: &mut Vec<u8>, format: TokenFormat)
+where
+    T: ryu::Float + num_traits::Float + std::fmt::Display + std::fmt::LowerExp,
+{
+    // Writing to a vector never fails.
+    let _ = match format {
+        _ if value.is_nan() => match format.nan {
+            NanSpelling::Mixed => write!(output, "NaN"),
+            NanSpelling::Lower => write!(output, "nan"),
+        },
+        TokenFormat {
+            precision: Some(precision),
+            scientific: true,
+            ..
+        } => write!(output, "{value:.precision$e}"),
+        TokenFormat {
+            precision: None,
+            scientific: true,
+            ..
+        } => write!(output, "{value:e}"),
+        TokenFormat {
+            precision: Some(precision),
+            scientific: false,
+            ..
+        } => write!(output, "{value:.precision$}"),
+        TokenFormat {
+            precision: None,
+            scientific: false,
+            ..
+        } => {
+            let mut buffer = ryu::Buffer::new();
+            let token = buffer.format(value);
+
+            // Keep integral values short, e.g. "1" instead of "1.0".
+            let token = token.strip_suffix(".0").unwrap_or(token);
+            output.write_all(token.as_bytes())
+        }
+    };
 }
 
-impl Value for f32 {
-    const KIND: ValueKind = ValueKind::F32;
+impl_value!(u8, U8, int);
+impl_value!(u16, U16, int);
+impl_value!(u32, U32, int);
+impl_value!(i8, I8, int);
+impl_value!(i16, I16, int);
+impl_value!(i32, I32, int);
+impl_value!(f32, F32, float);
+impl_value!(f64, F64, float);
+
+/// Matches on the value kind, binding the primitive type to `$ty`.
+macro_rules! match_kind {
+    ($kind:expr, $ty:ident => $body:expr) => {
+        match $kind {
+            $crate::metas::ValueKind::I8 => {
+                type $ty = i8;
+                $body
+            }
+            $crate::metas::ValueKind::I16 => {
+                type $ty = i16;
+                $body
+            }
+            $crate::metas::ValueKind::I32 => {
+                type $ty = i32;
+                $body
+            }
+            $crate::metas::ValueKind::U8 => {
+                type $ty = u8;
+                $body
+            }
+            $crate::metas::ValueKind::U16 => {
+                type $ty = u16;
+                $body
+            }
+            $crate::metas::ValueKind::U32 => {
+                type $ty = u32;
+                $body
+            }
+            $crate::metas::ValueKind::F32 => {
+                type $ty = f32;
+                $body
+            }
+            $crate::metas::ValueKind::F64 => {
+                type $ty = f64;
+                $body
+            }
+        }
+    };
 }
 
-impl Value for f64 {
-    const KIND: ValueKind = ValueKind::F64;
+/// Matches on the field variants, binding the values to `$values`.
+macro_rules! match_field {
+    ($field:expr, $values:ident => $body:expr) => {
+        match $field {
+            $crate::record::Field::I8($values) => $body,
+            $crate::record::Field::I16($values) => $body,
+            $crate::record::Field::I32($values) => $body,
+            $crate::record::Field::U8($values) => $body,
+            $crate::record::Field::U16($values) => $body,
+            $crate::record::Field::U32($values) => $body,
+            $crate::record::Field::F32($values) => $body,
+            $crate::record::Field::F64($values) => $body,
+        }
+    };
 }
+
+pub(crate) use match_field;
+pub(crate) use match_kind;
@@ -0,0 +1,118 @@
+//! Converting PCD data between data kinds.
+//!
+//! [transcode()] streams the points of a [DynReader] to a new writer
+//! with another [DataKind], keeping the rest of the header.
+//!
+//! ```rust
+//! # fn main() -> pcd_rs::Result<()> {
+//! use pcd_rs::{transcode::TranscodeInit, DataKind, DynReader};
+//! use std::{fs::File, io::BufWriter};
+//!
+//! let reader = DynReader::open("test_files/ascii.pcd")?;
+//! let output = BufWriter::new(File::create("test_files/transcode_doc.pcd")?);
+//!
+//! let num_points = pcd_rs::transcode(
+//!     reader,
+//!     TranscodeInit {
+//!         writer: output,
+//!         data_kind: DataKind::BinaryCompressed,
+//!         ascii_format: Default::default(),
+//!         upgrade_version: false,
+//!     },
+//! )?;
+//! # std::fs::remove_file("test_files/transcode_doc.pcd")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    metas::DataKind,
+    reader::DynReader,
+    record::DynRecord,
+    writer::{AsciiFormat, DynWriter, WriterInit},
+    Result,
+};
+use std::io::{prelude::*, BufRead};
+
+/// The output configuration of [transcode()].
+pub struct TranscodeInit<W> {
+    /// The output, which must implement both [Write] and [Seek].
+    pub writer: W,
+    /// The data kind of the output.
+    pub data_kind: DataKind,
+    /// The formatting of floats in ASCII output.
+    pub ascii_format: AsciiFormat,
+    /// Writes the header of version 0.5 or 0.6 input as version 0.7.
+    /// Otherwise, the version is kept, and binary_compressed output
+    /// is rejected for such input, since it requires version 0.7.
+    pub upgrade_version: bool,
+}
+
+/// Streams the points of the reader to the writer configured by
+/// `writer_init`, returning the number of points written.
+///
+/// The WIDTH, HEIGHT, VIEWPOINT and the fields of the header are kept.
+/// Binary points are copied as bytes without decoding, and ASCII
+/// points are parsed into bytes once. ASCII output is written in the
+/// shortest form that round-trips by default, so the conversion is
+/// lossless unless the precision is limited by `ascii_format`.
+///
+/// If the input is broken, the points copied before the error are kept
+/// in the output as an unorganized point cloud, whose WIDTH is POINTS
+/// and HEIGHT is 1, and the error is returned. For this purpose, the
+/// WIDTH and HEIGHT arguments may be padded with spaces.
+pub fn transcode<R, W>(mut reader: DynReader<R>, writer_init: TranscodeInit<W>) -> Result<u64>
+where
+    R: BufRead,
+    W: Write + Seek,
+{
+    let TranscodeInit {
+        writer,
+        data_kind,
+        ascii_format,
+        upgrade_version,
+    } = writer_init;
+    let meta = reader.meta();
+
+    let version = if upgrade_version {
+        "0.7"
+    } else {
+        meta.version.as_str()
+    };
+
+    let mut writer: DynWriter<W> = WriterInit {
+        width: meta.width,
+        height: meta.height,
+        viewpoint: meta.viewpoint.clone(),
+        data_kind,
+        schema: Some(meta.field_defs.clone()),
+    }
+    .with_ascii_format(ascii_format)
+    .build_with_version::<DynRecord, _>(writer, version)?;
+
+    let mut points = reader.raw_points();
+    let mut num_points = 0;
+    let result = loop {
+        let Some(point) = points.next() else {
+            break Ok(());
+        };
+        if let Err(error) = point.and_then(|point| writer.push_raw(&point)) {
+            break Err(error);
+        }
+        num_points += 1;
+    };
+
+    match result {
+        Ok(()) => {
+            writer.finish()?;
+            Ok(num_points)
+        }
+        Err(error) => {
+            // The points copied before the error are kept as an
+            // unorganized point cloud. The input error is returned
+            // even if finishing fails.
+            let _ = writer.finish_unorganized();
+            Err(error)
+        }
+    }
+}
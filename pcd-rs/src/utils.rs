@@ -1,19 +1,100 @@
 use crate::{
-    error::Error,
+    error::{Error, LimitKind},
     metas::{DataKind, FieldDef, PcdMeta, Schema, TypeKind, ValueKind, ViewPoint},
+    reader::ReaderLimits,
     Result,
 };
-use std::{collections::HashSet, io::prelude::*};
+use std::{collections::HashSet, fmt::Display, io::prelude::*, str::FromStr};
+
+/// Reads a line, failing if it is longer than `max_length` bytes
+/// without the line break.
+pub fn read_line_limited<R: BufRead>(
+    reader: &mut R,
+    line: &mut String,
+    max_length: usize,
+) -> Result<usize> {
+    let limit = (max_length as u64).saturating_add(1);
+    let read_size = reader.by_ref().take(limit).read_line(line)?;
+    if read_size as u64 == limit && !line.ends_with('\n') {
+        return Err(Error::new_limit_exceeded_error(
+            LimitKind::LineLength,
+            max_length as u64,
+            limit,
+        ));
+    }
+    Ok(read_size)
+}
+
+/// Counts the bytes consumed from the inner reader.
+pub struct CountingReader<R> {
+    inner: R,
+    count: u64,
+}
+
+impl<R> CountingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner, count: 0 }
+    }
+
+    /// Returns the number of bytes consumed so far.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let size = self.inner.read(buf)?;
+        self.count += size as u64;
+        Ok(size)
+    }
+}
+
+impl<R: BufRead> BufRead for CountingReader<R> {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.count += amt as u64;
+        self.inner.consume(amt);
+    }
+}
+
+/// Parses a header value, reporting the header line on failure.
+fn parse_header_value<T>(token: &str, line: usize) -> Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    token.parse().map_err(|error| {
+        Error::new_parse_error(line, &format!("invalid value \"{token}\": {error}"))
+    })
+}
 
-pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<PcdMeta> {
+pub fn load_meta<R: BufRead>(
+    reader: &mut R,
+    line_count: &mut usize,
+    limits: &ReaderLimits,
+) -> Result<PcdMeta> {
     let mut get_meta_line = |expect_entry: &str| -> Result<_> {
         loop {
             let mut line = String::new();
-            let read_size = reader.read_line(&mut line)?;
             *line_count += 1;
+            if *line_count > limits.max_header_lines {
+                return Err(Error::new_limit_exceeded_error(
+                    LimitKind::HeaderLines,
+                    limits.max_header_lines as u64,
+                    *line_count as u64,
+                ));
+            }
+            let read_size = read_line_limited(reader, &mut line, limits.max_line_length)?;
 
             if read_size == 0 {
-                return Err(Error::new_parse_error(*line_count, "Unexpected end of file").into());
+                return Err(Error::new_parse_error(
+                    *line_count,
+                    "Unexpected end of file",
+                ));
             }
 
             let line_stripped = match line.split('#').next() {
@@ -29,7 +110,7 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
 
             if tokens.is_empty() {
                 let desc = format!("Cannot parse empty line at line {}", *line_count + 1);
-                return Err(Error::new_parse_error(*line_count, &desc).into());
+                return Err(Error::new_parse_error(*line_count, &desc));
             }
 
             if tokens[0] != expect_entry {
@@ -39,15 +120,15 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
                     tokens[0],
                     *line_count + 1
                 );
-                return Err(Error::new_parse_error(*line_count, &desc).into());
+                return Err(Error::new_parse_error(*line_count, &desc));
             }
 
-            return Ok(tokens);
+            return Ok((tokens, *line_count));
         }
     };
 
     let meta_version = {
-        let tokens = get_meta_line("VERSION")?;
+        let (tokens, _) = get_meta_line("VERSION")?;
         if tokens.len() == 2 {
             match tokens[1].as_str() {
                 "0.7" | ".7" => String::from("0.7"),
@@ -58,22 +139,24 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
                         "Unsupported version {:?}. Supported versions are: 0.5, 0.6, 0.7",
                         tokens[1]
                     );
-                    return Err(Error::new_parse_error(*line_count, &desc).into());
+                    return Err(Error::new_parse_error(*line_count, &desc));
                 }
             }
         } else {
-            return Err(
-                Error::new_parse_error(*line_count, "VERSION line is not understood").into(),
-            );
+            return Err(Error::new_parse_error(
+                *line_count,
+                "VERSION line is not understood",
+            ));
         }
     };
 
     let meta_fields = {
-        let tokens = get_meta_line("FIELDS")?;
+        let (tokens, _) = get_meta_line("FIELDS")?;
         if tokens.len() == 1 {
-            return Err(
-                Error::new_parse_error(*line_count, "FIELDS line is not understood").into(),
-            );
+            return Err(Error::new_parse_error(
+                *line_count,
+                "FIELDS line is not understood",
+            ));
         }
 
         let mut name_set = HashSet::new();
@@ -83,31 +166,42 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
             let mut field = tk.clone();
             // If this field is just an underscore, it was meant to be skipped. Label it as
             // unknown_field_{idx}
-            if field == String::from("_") {
+            if field == "_" {
                 field = format!("unknown_field_{idx}");
             }
 
             if name_set.contains(&field.clone()) {
                 let desc = format!("field name {:?} is specified more than once", field);
-                return Err(Error::new_parse_error(*line_count, &desc).into());
+                return Err(Error::new_parse_error(*line_count, &desc));
             }
 
             name_set.insert(field.clone());
             field_names.push(field.to_owned());
         }
 
+        if field_names.len() > limits.max_fields {
+            return Err(Error::new_limit_exceeded_error(
+                LimitKind::Fields,
+                limits.max_fields as u64,
+                field_names.len() as u64,
+            ));
+        }
+
         field_names
     };
 
     let meta_size = {
-        let tokens = get_meta_line("SIZE")?;
+        let (tokens, line) = get_meta_line("SIZE")?;
         if tokens.len() == 1 {
-            return Err(Error::new_parse_error(*line_count, "SIZE line is not understood").into());
+            return Err(Error::new_parse_error(
+                *line_count,
+                "SIZE line is not understood",
+            ));
         }
 
         let mut sizes = vec![];
         for tk in tokens[1..].iter() {
-            let size: u64 = tk.parse()?;
+            let size: u64 = parse_header_value(tk, line)?;
             sizes.push(size);
         }
 
@@ -115,10 +209,13 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
     };
 
     let meta_type = {
-        let tokens = get_meta_line("TYPE")?;
+        let (tokens, _) = get_meta_line("TYPE")?;
 
         if tokens.len() == 1 {
-            return Err(Error::new_parse_error(*line_count, "TYPE line is not understood").into());
+            return Err(Error::new_parse_error(
+                *line_count,
+                "TYPE line is not understood",
+            ));
         }
 
         let mut types = vec![];
@@ -129,7 +226,7 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
                 "F" => TypeKind::F,
                 _ => {
                     let desc = format!("Invalid type character {:?} in TYPE line", type_char);
-                    return Err(Error::new_parse_error(*line_count, &desc).into());
+                    return Err(Error::new_parse_error(*line_count, &desc));
                 }
             };
             types.push(type_);
@@ -139,15 +236,18 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
     };
 
     let meta_count = {
-        let tokens = get_meta_line("COUNT")?;
+        let (tokens, line) = get_meta_line("COUNT")?;
 
         if tokens.len() == 1 {
-            return Err(Error::new_parse_error(*line_count, "COUNT line is not understood").into());
+            return Err(Error::new_parse_error(
+                *line_count,
+                "COUNT line is not understood",
+            ));
         }
 
         let mut counts = vec![];
         for tk in tokens[1..].iter() {
-            let count: u64 = tk.parse()?;
+            let count: u64 = parse_header_value(tk, line)?;
             counts.push(count);
         }
 
@@ -155,25 +255,29 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
     };
 
     let meta_width = {
-        let tokens = get_meta_line("WIDTH")?;
+        let (tokens, line) = get_meta_line("WIDTH")?;
 
         if tokens.len() != 2 {
-            return Err(Error::new_parse_error(*line_count, "WIDTH line is not understood").into());
+            return Err(Error::new_parse_error(
+                *line_count,
+                "WIDTH line is not understood",
+            ));
         }
 
-        let width: u64 = tokens[1].parse()?;
+        let width: u64 = parse_header_value(&tokens[1], line)?;
         width
     };
 
     let meta_height = {
-        let tokens = get_meta_line("HEIGHT")?;
+        let (tokens, line) = get_meta_line("HEIGHT")?;
         if tokens.len() != 2 {
-            return Err(
-                Error::new_parse_error(*line_count, "HEIGHT line is not understood").into(),
-            );
+            return Err(Error::new_parse_error(
+                *line_count,
+                "HEIGHT line is not understood",
+            ));
         }
 
-        let height: u64 = tokens[1].parse()?;
+        let height: u64 = parse_header_value(&tokens[1], line)?;
         height
     };
 
@@ -183,23 +287,22 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
         if meta_version == "0.5" || meta_version == "0.6" {
             ViewPoint::default()
         } else {
-            let tokens = get_meta_line("VIEWPOINT")?;
+            let (tokens, line) = get_meta_line("VIEWPOINT")?;
 
             if tokens.len() != 8 {
                 return Err(Error::new_parse_error(
                     *line_count,
                     "VIEWPOINT line is not understood",
-                )
-                .into());
+                ));
             }
 
-            let tx = tokens[1].parse()?;
-            let ty = tokens[2].parse()?;
-            let tz = tokens[3].parse()?;
-            let qw = tokens[4].parse()?;
-            let qx = tokens[5].parse()?;
-            let qy = tokens[6].parse()?;
-            let qz = tokens[7].parse()?;
+            let tx = parse_header_value(&tokens[1], line)?;
+            let ty = parse_header_value(&tokens[2], line)?;
+            let tz = parse_header_value(&tokens[3], line)?;
+            let qw = parse_header_value(&tokens[4], line)?;
+            let qx = parse_header_value(&tokens[5], line)?;
+            let qy = parse_header_value(&tokens[6], line)?;
+            let qz = parse_header_value(&tokens[7], line)?;
             ViewPoint {
                 tx,
                 ty,
@@ -213,23 +316,34 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
     };
 
     let meta_points = {
-        let tokens = get_meta_line("POINTS")?;
+        let (tokens, line) = get_meta_line("POINTS")?;
 
         if tokens.len() != 2 {
-            return Err(
-                Error::new_parse_error(*line_count, "POINTS line is not understood").into(),
-            );
+            return Err(Error::new_parse_error(
+                *line_count,
+                "POINTS line is not understood",
+            ));
         }
 
-        let count: u64 = tokens[1].parse()?;
+        let count: u64 = parse_header_value(&tokens[1], line)?;
+        if count > limits.max_points {
+            return Err(Error::new_limit_exceeded_error(
+                LimitKind::Points,
+                limits.max_points,
+                count,
+            ));
+        }
         count
     };
 
     let meta_data = {
-        let tokens = get_meta_line("DATA")?;
+        let (tokens, _) = get_meta_line("DATA")?;
 
         if tokens.len() != 2 {
-            return Err(Error::new_parse_error(*line_count, "DATA line is not understood").into());
+            return Err(Error::new_parse_error(
+                *line_count,
+                "DATA line is not understood",
+            ));
         }
 
         match tokens[1].as_str() {
@@ -242,35 +356,39 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
                         "binary_compressed format is only supported in PCD version 0.7, found version {}",
                         meta_version
                     );
-                    return Err(Error::new_parse_error(*line_count, &desc).into());
+                    return Err(Error::new_parse_error(*line_count, &desc));
                 }
                 DataKind::BinaryCompressed
             }
             _ => {
-                return Err(
-                    Error::new_parse_error(*line_count, "DATA line is not understood").into(),
-                );
+                return Err(Error::new_parse_error(
+                    *line_count,
+                    "DATA line is not understood",
+                ));
             }
         }
     };
 
     // Check integrity
     if meta_size.len() != meta_fields.len() {
-        return Err(
-            Error::new_parse_error(*line_count, "SIZE entry conflicts with FIELD entry").into(),
-        );
+        return Err(Error::new_parse_error(
+            *line_count,
+            "SIZE entry conflicts with FIELD entry",
+        ));
     }
 
     if meta_type.len() != meta_fields.len() {
-        return Err(
-            Error::new_parse_error(*line_count, "TYPE entry conflicts with FIELD entry").into(),
-        );
+        return Err(Error::new_parse_error(
+            *line_count,
+            "TYPE entry conflicts with FIELD entry",
+        ));
     }
 
     if meta_count.len() != meta_fields.len() {
-        return Err(
-            Error::new_parse_error(*line_count, "COUNT entry conflicts with FIELD entry").into(),
-        );
+        return Err(Error::new_parse_error(
+            *line_count,
+            "COUNT entry conflicts with FIELD entry",
+        ));
     }
 
     // Organize field type
@@ -293,7 +411,7 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
                     _ => {
                         let desc =
                             format!("Field type {:?} with size {} is not supported", type_, size);
-                        return Err(Error::new_parse_error(*line_count, &desc).into());
+                        return Err(Error::new_parse_error(*line_count, &desc));
                     }
                 };
 
@@ -308,9 +426,28 @@ pub fn load_meta<R: BufRead>(reader: &mut R, line_count: &mut usize) -> Result<P
             .collect()
     };
 
+    let field_defs = field_defs?;
+
+    // A point is decoded in a single buffer, whose size must not
+    // overflow whatever the limits are.
+    let Some(point_size) = field_defs.checked_point_size() else {
+        return Err(Error::new_parse_error(
+            *line_count,
+            "the size of a point overflows",
+        ));
+    };
+    let point_size = point_size as u64;
+    if point_size > limits.max_decompressed_bytes {
+        return Err(Error::new_limit_exceeded_error(
+            LimitKind::DecompressedBytes,
+            limits.max_decompressed_bytes,
+            point_size,
+        ));
+    }
+
     let meta = PcdMeta {
         version: meta_version,
-        field_defs: field_defs?,
+        field_defs,
         width: meta_width,
         height: meta_height,
         viewpoint: meta_viewpoint,
@@ -0,0 +1,1024 @@
+//! Conformance checks of PCD files.
+//!
+//! [validate()] and [validate_reader()] check the header and the data
+//! section against the PCD format without decoding the points into
+//! records. Unlike the [Reader](crate::reader::Reader), they do not stop
+//! at the first problem but collect all findings into a
+//! [ValidationReport]. With the `serde` feature, the report can be
+//! serialized, for example to JSON.
+//!
+//! ```rust
+//! let report = pcd_rs::validate("test_files/ascii.pcd")?;
+//! assert!(report.is_valid());
+//! for finding in &report.findings {
+//!     println!("{finding}");
+//! }
+//! # Ok::<(), pcd_rs::Error>(())
+//! ```
+
+use crate::{
+    lzf,
+    metas::{DataKind, FieldDef, Schema, ValueKind},
+    traits::{match_kind, Value},
+    Result,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, prelude::*, BufReader},
+    path::Path,
+};
+
+/// The header entries in the required order.
+const ENTRIES: [&str; 10] = [
+    "VERSION",
+    "FIELDS",
+    "SIZE",
+    "TYPE",
+    "COUNT",
+    "WIDTH",
+    "HEIGHT",
+    "VIEWPOINT",
+    "POINTS",
+    "DATA",
+];
+
+/// The header is given up if DATA is not found within the lines.
+const MAX_HEADER_LINES: usize = 1024;
+
+/// The maximum ratio of decompressed to compressed size of LZF data.
+/// A 3-byte back reference expands to at most 264 bytes.
+const MAX_LZF_RATIO: u64 = 88;
+
+/// The severity of a [Finding].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Severity {
+    /// A notable property of a valid file, such as NaN values.
+    Info,
+    /// A deviation that most readers accept, such as a missing entry
+    /// with a default value.
+    Warning,
+    /// A violation that makes the file unreadable or inconsistent.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        f.write_str(text)
+    }
+}
+
+/// The check that produced a [Finding].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Check {
+    /// Header entries are missing, unknown, repeated or out of order.
+    HeaderOrder,
+    /// A header entry is missing and its default value is assumed.
+    HeaderDefault,
+    /// The VERSION entry.
+    Version,
+    /// The names in the FIELDS entry.
+    FieldName,
+    /// The SIZE, TYPE and COUNT entries and their combinations.
+    FieldType,
+    /// The consistency of WIDTH, HEIGHT and POINTS.
+    Dimensions,
+    /// The VIEWPOINT entry.
+    Viewpoint,
+    /// The DATA entry.
+    DataKind,
+    /// The length of the data section.
+    DataLength,
+    /// The integrity of binary_compressed data.
+    Lzf,
+    /// The number of tokens on ASCII lines.
+    TokenCount,
+    /// The ASCII tokens that cannot be parsed.
+    TokenParse,
+    /// NaN and infinite values.
+    NanInf,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Check::HeaderOrder => "header_order",
+            Check::HeaderDefault => "header_default",
+            Check::Version => "version",
+            Check::FieldName => "field_name",
+            Check::FieldType => "field_type",
+            Check::Dimensions => "dimensions",
+            Check::Viewpoint => "viewpoint",
+            Check::DataKind => "data_kind",
+            Check::DataLength => "data_length",
+            Check::Lzf => "lzf",
+            Check::TokenCount => "token_count",
+            Check::TokenParse => "token_parse",
+            Check::NanInf => "nan_inf",
+        };
+        f.write_str(text)
+    }
+}
+
+/// A problem or a notable property found by [validate()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Finding {
+    pub severity: Severity,
+    pub check: Check,
+    pub message: String,
+    /// The header line, counting from 1.
+    pub line: Option<usize>,
+    /// The index of the first point concerned.
+    pub point_index: Option<u64>,
+    /// The name of the field concerned.
+    pub field: Option<String>,
+}
+
+impl Finding {
+    fn new(severity: Severity, check: Check, message: String) -> Self {
+        Self {
+            severity,
+            check,
+            message,
+            line: None,
+            point_index: None,
+            field: None,
+        }
+    }
+
+    fn at_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    fn at_point(mut self, index: u64) -> Self {
+        self.point_index = Some(index);
+        self
+    }
+
+    fn in_field(mut self, name: &str) -> Self {
+        self.field = Some(name.to_owned());
+        self
+    }
+}
+
+/// Formats the finding like `error[dimensions] line 9: ...`.
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]", self.severity, self.check)?;
+        if let Some(line) = self.line {
+            write!(f, " line {line}")?;
+        }
+        if let Some(index) = self.point_index {
+            write!(f, " point {index}")?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, " field {field:?}")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// The findings of [validate()] in the order they are found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ValidationReport {
+    pub findings: Vec<Finding>,
+}
+
+impl ValidationReport {
+    /// Returns true if there are no errors.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Returns the highest severity of the findings.
+    pub fn max_severity(&self) -> Option<Severity> {
+        self.findings.iter().map(|finding| finding.severity).max()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Finding> {
+        self.with_severity(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Finding> {
+        self.with_severity(Severity::Warning)
+    }
+
+    pub fn infos(&self) -> impl Iterator<Item = &Finding> {
+        self.with_severity(Severity::Info)
+    }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &Finding> {
+        self.findings
+            .iter()
+            .filter(move |finding| finding.severity == severity)
+    }
+
+    fn push(&mut self, finding: Finding) {
+        self.findings.push(finding);
+    }
+
+    fn error(&mut self, check: Check, message: String) -> &mut Finding {
+        self.push(Finding::new(Severity::Error, check, message));
+        self.findings.last_mut().unwrap()
+    }
+}
+
+/// Checks a PCD file. See the [module doc](mod@crate::validate).
+///
+/// It fails only if the file cannot be read. Problems of the content
+/// are reported as findings.
+pub fn validate(path: impl AsRef<Path>) -> Result<ValidationReport> {
+    let file = BufReader::new(File::open(path.as_ref())?);
+    validate_reader(file)
+}
+
+/// Checks PCD data from a reader. See the [module doc](mod@crate::validate).
+pub fn validate_reader<R: BufRead>(mut reader: R) -> Result<ValidationReport> {
+    let mut report = ValidationReport::default();
+
+    let Some(entries) = read_entries(&mut reader, &mut report)? else {
+        return Ok(report);
+    };
+    let Some(header) = check_header(&entries, &mut report) else {
+        report.push(Finding::new(
+            Severity::Info,
+            Check::DataLength,
+            "the data section is not checked because of header errors".into(),
+        ));
+        return Ok(report);
+    };
+
+    let mut scan = DataScan::new(&header.schema);
+    match header.data_kind {
+        DataKind::Ascii => scan.ascii(&mut reader, &header, &mut report)?,
+        DataKind::Binary => scan.binary(&mut reader, &header, &mut report)?,
+        DataKind::BinaryCompressed => scan.compressed(&mut reader, &header, &mut report)?,
+    }
+    scan.report_floats(&header.schema, &mut report);
+
+    Ok(report)
+}
+
+/// A header entry with its line number and values.
+struct Entry {
+    line: usize,
+    values: Vec<String>,
+}
+
+/// Reads the header lines up to DATA, checking the entry order.
+fn read_entries<R: BufRead>(
+    reader: &mut R,
+    report: &mut ValidationReport,
+) -> Result<Option<[Option<Entry>; 10]>> {
+    let mut entries: [Option<Entry>; 10] = Default::default();
+    let mut last: Option<usize> = None;
+    let mut buffer = vec![];
+
+    for line in 1..=MAX_HEADER_LINES {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            report
+                .error(Check::HeaderOrder, "the header has no DATA entry".into())
+                .line = Some(line);
+            return Ok(None);
+        }
+
+        let text = String::from_utf8_lossy(&buffer);
+        let content = text.split('#').next().unwrap_or("");
+        let mut tokens = content.split_ascii_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+
+        let Some(position) = ENTRIES.iter().position(|&entry| entry == keyword) else {
+            let message = format!("unknown header entry {keyword:?}");
+            report.push(Finding::new(Severity::Error, Check::HeaderOrder, message).at_line(line));
+            continue;
+        };
+
+        if let Some(entry) = &entries[position] {
+            let message = format!("{keyword} is repeated, first on line {}", entry.line);
+            report.push(Finding::new(Severity::Error, Check::HeaderOrder, message).at_line(line));
+            continue;
+        }
+
+        if let Some(last) = last.filter(|&last| last > position) {
+            let message = format!("{keyword} must come before {}", ENTRIES[last]);
+            report.push(Finding::new(Severity::Error, Check::HeaderOrder, message).at_line(line));
+        }
+        last = Some(last.map_or(position, |last| last.max(position)));
+
+        entries[position] = Some(Entry {
+            line,
+            values: tokens.map(str::to_owned).collect(),
+        });
+
+        if keyword == "DATA" {
+            return Ok(Some(entries));
+        }
+    }
+
+    let message = format!("no DATA entry within {MAX_HEADER_LINES} header lines");
+    report.error(Check::HeaderOrder, message);
+    Ok(None)
+}
+
+/// The header values needed to check the data section.
+struct Header {
+    schema: Schema,
+    num_points: u64,
+    data_kind: DataKind,
+}
+
+/// Checks the header entries. It returns `None` if the data section
+/// cannot be checked.
+fn check_header(entries: &[Option<Entry>; 10], report: &mut ValidationReport) -> Option<Header> {
+    let [version, fields, size, type_, count, width, height, viewpoint, points, data] = entries;
+    let mut usable = true;
+
+    let mut required = |entry: &Option<Entry>, name: &str, report: &mut ValidationReport| {
+        if entry.is_none() {
+            report.error(
+                Check::HeaderOrder,
+                format!("the header has no {name} entry"),
+            );
+            usable = false;
+        }
+    };
+    required(fields, "FIELDS", report);
+    required(size, "SIZE", report);
+    required(type_, "TYPE", report);
+    required(width, "WIDTH", report);
+    required(height, "HEIGHT", report);
+    required(data, "DATA", report);
+
+    let version = match version {
+        Some(entry) => match entry.values.as_slice() {
+            [value] if matches!(value.as_str(), "0.7" | ".7") => "0.7",
+            [value] if matches!(value.as_str(), "0.6" | ".6") => "0.6",
+            [value] if matches!(value.as_str(), "0.5" | ".5") => "0.5",
+            values => {
+                let message = format!(
+                    "unsupported version {:?}, expect 0.5, 0.6 or 0.7",
+                    values.join(" ")
+                );
+                report.error(Check::Version, message).line = Some(entry.line);
+                "0.7"
+            }
+        },
+        None => {
+            report.push(Finding::new(
+                Severity::Warning,
+                Check::HeaderDefault,
+                "the header has no VERSION entry, 0.7 is assumed".into(),
+            ));
+            "0.7"
+        }
+    };
+
+    let names = fields.as_ref().map(check_names(report));
+    let num_fields = names.as_ref().map_or(0, Vec::len);
+
+    // The lengths of the lists are checked against FIELDS.
+    let fields = names.as_ref().map(|_| ());
+    let sizes: Option<Vec<u64>> = fields
+        .and(size.as_ref())
+        .and_then(|entry| parse_list(entry, "SIZE", num_fields, report));
+    let types: Option<Vec<&str>> = fields.and(type_.as_ref()).and_then(|entry| {
+        check_len(entry, "TYPE", num_fields, report)?;
+        let mut types = vec![];
+        for value in &entry.values {
+            if !matches!(value.as_str(), "I" | "U" | "F") {
+                let message = format!("invalid TYPE {value:?}, expect I, U or F");
+                report.error(Check::FieldType, message).line = Some(entry.line);
+                return None;
+            }
+            types.push(value.as_str());
+        }
+        Some(types)
+    });
+    let counts: Option<Vec<u64>> = match count {
+        Some(entry) => fields.and_then(|()| parse_list(entry, "COUNT", num_fields, report)),
+        None => {
+            report.push(Finding::new(
+                Severity::Warning,
+                Check::HeaderDefault,
+                "the header has no COUNT entry, 1 is assumed for all fields".into(),
+            ));
+            Some(vec![1; num_fields])
+        }
+    };
+
+    let schema = match (names, sizes, types, counts) {
+        (Some(names), Some(sizes), Some(types), Some(counts)) => {
+            let line = size.as_ref().map_or(0, |entry| entry.line);
+            check_schema(names, sizes, types, counts, line, report)
+        }
+        _ => None,
+    };
+
+    let width = width
+        .as_ref()
+        .and_then(|entry| parse_single(entry, "WIDTH", report));
+    let height = height
+        .as_ref()
+        .and_then(|entry| parse_single(entry, "HEIGHT", report));
+    let num_points = match points {
+        Some(entry) => parse_single(entry, "POINTS", report),
+        None => {
+            report.push(Finding::new(
+                Severity::Warning,
+                Check::HeaderDefault,
+                "the header has no POINTS entry, WIDTH * HEIGHT is assumed".into(),
+            ));
+            width.zip(height).and_then(|(w, h)| w.checked_mul(h))
+        }
+    };
+
+    if let (Some(width), Some(height), Some(num_points)) = (width, height, num_points) {
+        let line = points.as_ref().map(|entry| entry.line);
+        check_dimensions(width, height, num_points, line, report);
+    }
+
+    match viewpoint {
+        Some(entry) => check_viewpoint(entry, report),
+        None if version == "0.7" => report.push(Finding::new(
+            Severity::Warning,
+            Check::HeaderDefault,
+            "the header has no VIEWPOINT entry, the identity pose is assumed".into(),
+        )),
+        None => {}
+    }
+
+    let data_kind = data.as_ref().and_then(|entry| {
+        let kind = match entry.values.as_slice() {
+            [kind] if kind == "ascii" => DataKind::Ascii,
+            [kind] if kind == "binary" => DataKind::Binary,
+            [kind] if kind == "binary_compressed" => DataKind::BinaryCompressed,
+            values => {
+                let message = format!(
+                    "invalid DATA {:?}, expect ascii, binary or binary_compressed",
+                    values.join(" ")
+                );
+                report.error(Check::DataKind, message).line = Some(entry.line);
+                return None;
+            }
+        };
+        if kind == DataKind::BinaryCompressed && version != "0.7" {
+            let message = format!("binary_compressed data requires version 0.7, found {version}");
+            report.error(Check::DataKind, message).line = Some(entry.line);
+        }
+        Some(kind)
+    });
+
+    Some(Header {
+        schema: schema?,
+        num_points: num_points.filter(|_| usable)?,
+        data_kind: data_kind?,
+    })
+}
+
+fn check_names(report: &mut ValidationReport) -> impl FnMut(&Entry) -> Vec<String> + '_ {
+    |entry| {
+        if entry.values.is_empty() {
+            report
+                .error(Check::FieldName, "FIELDS has no names".into())
+                .line = Some(entry.line);
+        }
+
+        for (position, name) in entry.values.iter().enumerate() {
+            if entry.values[..position].contains(name) && name != "_" {
+                let message = format!("field name {name:?} is repeated");
+                report.push(
+                    Finding::new(Severity::Error, Check::FieldName, message)
+                        .at_line(entry.line)
+                        .in_field(name),
+                );
+            } else if name == "_" {
+                let message = format!("field {position} is a padding field");
+                report.push(
+                    Finding::new(Severity::Info, Check::FieldName, message).at_line(entry.line),
+                );
+            } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                let message = format!(
+                    "field name {name:?} has characters other than letters, digits and underscores"
+                );
+                report.push(
+                    Finding::new(Severity::Warning, Check::FieldName, message)
+                        .at_line(entry.line)
+                        .in_field(name),
+                );
+            }
+        }
+        entry.values.clone()
+    }
+}
+
+fn check_len(
+    entry: &Entry,
+    name: &str,
+    num_fields: usize,
+    report: &mut ValidationReport,
+) -> Option<()> {
+    if entry.values.len() != num_fields {
+        let message = format!(
+            "{name} has {} values, but FIELDS has {num_fields} names",
+            entry.values.len()
+        );
+        report.error(Check::FieldType, message).line = Some(entry.line);
+        return None;
+    }
+    Some(())
+}
+
+fn parse_list(
+    entry: &Entry,
+    name: &str,
+    num_fields: usize,
+    report: &mut ValidationReport,
+) -> Option<Vec<u64>> {
+    check_len(entry, name, num_fields, report)?;
+    let mut values = vec![];
+    for value in &entry.values {
+        match value.parse() {
+            Ok(value) => values.push(value),
+            Err(_) => {
+                let message = format!("invalid {name} value {value:?}");
+                report.error(Check::FieldType, message).line = Some(entry.line);
+                return None;
+            }
+        }
+    }
+    Some(values)
+}
+
+fn parse_single(entry: &Entry, name: &str, report: &mut ValidationReport) -> Option<u64> {
+    match entry.values.as_slice() {
+        [value] => match value.parse() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                let message = format!("invalid {name} value {value:?}");
+                report.error(Check::Dimensions, message).line = Some(entry.line);
+                None
+            }
+        },
+        values => {
+            let message = format!("{name} expects 1 value, but found {}", values.len());
+            report.error(Check::Dimensions, message).line = Some(entry.line);
+            None
+        }
+    }
+}
+
+fn check_schema(
+    names: Vec<String>,
+    sizes: Vec<u64>,
+    types: Vec<&str>,
+    counts: Vec<u64>,
+    line: usize,
+    report: &mut ValidationReport,
+) -> Option<Schema> {
+    let mut fields = vec![];
+    let mut valid = true;
+
+    for (((name, size), type_), count) in names.into_iter().zip(sizes).zip(types).zip(counts) {
+        let kind = match (type_, size) {
+            ("U", 1) => ValueKind::U8,
+            ("U", 2) => ValueKind::U16,
+            ("U", 4) => ValueKind::U32,
+            ("I", 1) => ValueKind::I8,
+            ("I", 2) => ValueKind::I16,
+            ("I", 4) => ValueKind::I32,
+            ("F", 4) => ValueKind::F32,
+            ("F", 8) => ValueKind::F64,
+            _ => {
+                let message = format!("TYPE {type_} with SIZE {size} is not supported");
+                report.push(
+                    Finding::new(Severity::Error, Check::FieldType, message)
+                        .at_line(line)
+                        .in_field(&name),
+                );
+                valid = false;
+                continue;
+            }
+        };
+
+        if count == 0 {
+            report.push(
+                Finding::new(Severity::Error, Check::FieldType, "COUNT is 0".into())
+                    .at_line(line)
+                    .in_field(&name),
+            );
+            valid = false;
+        }
+
+        let field = FieldDef { name, kind, count };
+        if field.checked_size().is_none() {
+            let message = format!("the field size of {count} values overflows");
+            report.push(
+                Finding::new(Severity::Error, Check::FieldType, message)
+                    .at_line(line)
+                    .in_field(&field.name),
+            );
+            valid = false;
+        }
+        fields.push(field);
+    }
+
+    let schema = Schema { fields };
+    if valid && schema.checked_point_size().is_none() {
+        let message = "the size of a point overflows".into();
+        report.push(Finding::new(Severity::Error, Check::FieldType, message).at_line(line));
+        valid = false;
+    }
+
+    (valid && !schema.is_empty()).then_some(schema)
+}
+
+fn check_dimensions(
+    width: u64,
+    height: u64,
+    num_points: u64,
+    line: Option<usize>,
+    report: &mut ValidationReport,
+) {
+    if width.checked_mul(height) != Some(num_points) {
+        let message = format!("WIDTH * HEIGHT is {width} * {height}, but POINTS is {num_points}");
+        report.error(Check::Dimensions, message).line = line;
+    } else if num_points == 0 {
+        report.push(Finding::new(
+            Severity::Info,
+            Check::Dimensions,
+            "the cloud has no points".into(),
+        ));
+    } else if height > 1 {
+        let message = format!("the cloud is organized in {height} rows of {width} points");
+        report.push(Finding::new(Severity::Info, Check::Dimensions, message));
+    }
+}
+
+fn check_viewpoint(entry: &Entry, report: &mut ValidationReport) {
+    let values: Option<Vec<f64>> = entry
+        .values
+        .iter()
+        .map(|value| value.parse().ok())
+        .collect();
+    let values = match values {
+        Some(values) if values.len() == 7 => values,
+        _ => {
+            let message = "VIEWPOINT expects 7 numbers: tx ty tz qw qx qy qz".into();
+            report.error(Check::Viewpoint, message).line = Some(entry.line);
+            return;
+        }
+    };
+
+    let norm = values[3..]
+        .iter()
+        .map(|value| value * value)
+        .sum::<f64>()
+        .sqrt();
+    if (norm - 1.0).abs() > 1e-3 {
+        let message = format!("the orientation quaternion has norm {norm}, expect 1");
+        report.push(Finding::new(Severity::Warning, Check::Viewpoint, message).at_line(entry.line));
+    }
+}
+
+/// The NaN and infinite values of a float field.
+#[derive(Default)]
+struct FloatStats {
+    nan: u64,
+    first_nan: Option<u64>,
+    inf: u64,
+    first_inf: Option<u64>,
+}
+
+impl FloatStats {
+    fn add(&mut self, is_nan: bool, is_inf: bool, index: u64) {
+        if is_nan {
+            self.nan += 1;
+            self.first_nan.get_or_insert(index);
+        } else if is_inf {
+            self.inf += 1;
+            self.first_inf.get_or_insert(index);
+        }
+    }
+
+    fn add_bytes(&mut self, kind: ValueKind, bytes: &[u8], index: u64) {
+        match kind {
+            ValueKind::F32 => {
+                let value = f32::from_le_slice(bytes);
+                self.add(value.is_nan(), value.is_infinite(), index);
+            }
+            ValueKind::F64 => {
+                let value = f64::from_le_slice(bytes);
+                self.add(value.is_nan(), value.is_infinite(), index);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Repeated findings on data lines, reported once.
+#[derive(Default)]
+struct LineErrors {
+    count: u64,
+    first: Option<(u64, String, Option<String>)>,
+}
+
+impl LineErrors {
+    fn add(&mut self, index: u64, message: impl FnOnce() -> String, field: Option<&str>) {
+        self.count += 1;
+        if self.first.is_none() {
+            self.first = Some((index, message(), field.map(str::to_owned)));
+        }
+    }
+
+    fn report(self, check: Check, what: &str, report: &mut ValidationReport) {
+        let Some((index, message, field)) = self.first else {
+            return;
+        };
+        let message = format!("{} {what}, the first: {message}", self.count);
+        let mut finding = Finding::new(Severity::Error, check, message).at_point(index);
+        finding.field = field;
+        report.push(finding);
+    }
+}
+
+/// The state of scanning the data section.
+struct DataScan {
+    floats: Vec<FloatStats>,
+}
+
+impl DataScan {
+    fn new(schema: &Schema) -> Self {
+        Self {
+            floats: schema.iter().map(|_| FloatStats::default()).collect(),
+        }
+    }
+
+    fn binary<R: BufRead>(
+        &mut self,
+        reader: &mut R,
+        header: &Header,
+        report: &mut ValidationReport,
+    ) -> Result<()> {
+        let schema = &header.schema;
+        let point_size = schema.point_size();
+        let mut buffer = vec![0; point_size];
+
+        for index in 0..header.num_points {
+            let read_size = read_full(reader, &mut buffer)?;
+            if read_size < point_size {
+                let actual = index
+                    .saturating_mul(point_size as u64)
+                    .saturating_add(read_size as u64);
+                report_truncated(header, point_size, actual, index, report);
+                return Ok(());
+            }
+
+            let mut offset = 0;
+            for (stats, def) in self.floats.iter_mut().zip(schema.iter()) {
+                let bytes = &buffer[offset..offset + def.size()];
+                for value in bytes.chunks_exact(def.kind.size()) {
+                    stats.add_bytes(def.kind, value, index);
+                }
+                offset += def.size();
+            }
+        }
+
+        report_trailing(reader, report)
+    }
+
+    fn compressed<R: BufRead>(
+        &mut self,
+        reader: &mut R,
+        header: &Header,
+        report: &mut ValidationReport,
+    ) -> Result<()> {
+        let mut sizes = [0; 8];
+        if read_full(reader, &mut sizes)? < sizes.len() {
+            let message = "the data section has no compressed sizes".into();
+            report.error(Check::DataLength, message);
+            return Ok(());
+        }
+        let compressed_size = u32::from_le_bytes(sizes[..4].try_into().unwrap()) as u64;
+        let uncompressed_size = u32::from_le_bytes(sizes[4..].try_into().unwrap()) as u64;
+
+        let schema = &header.schema;
+        let point_size = schema.point_size() as u64;
+        let expected = header.num_points.saturating_mul(point_size);
+        if uncompressed_size != expected {
+            let message = format!(
+                "the decompressed size is {uncompressed_size} bytes, but {} points of {point_size} bytes take {expected} bytes",
+                header.num_points
+            );
+            report.error(Check::DataLength, message);
+        }
+
+        let mut compressed = vec![];
+        reader
+            .by_ref()
+            .take(compressed_size)
+            .read_to_end(&mut compressed)?;
+        if (compressed.len() as u64) < compressed_size {
+            let message = format!(
+                "the compressed data has {} bytes, but {compressed_size} bytes are declared",
+                compressed.len()
+            );
+            report.error(Check::DataLength, message);
+            return Ok(());
+        }
+
+        if uncompressed_size > compressed_size * MAX_LZF_RATIO {
+            let message = format!(
+                "{compressed_size} bytes of LZF data cannot expand to {uncompressed_size} bytes"
+            );
+            report.error(Check::Lzf, message);
+            return Ok(());
+        }
+
+        let data = if uncompressed_size == 0 {
+            vec![]
+        } else {
+            match lzf::decompress(&compressed, uncompressed_size as usize) {
+                Ok(data) => data,
+                Err(error) => {
+                    report.error(Check::Lzf, error.to_string());
+                    return Ok(());
+                }
+            }
+        };
+
+        // The fields are stored one after another.
+        if uncompressed_size == expected {
+            let mut offset = 0;
+            for (stats, def) in self.floats.iter_mut().zip(schema.iter()) {
+                let len = def.size() * header.num_points as usize;
+                let values = data[offset..offset + len].chunks_exact(def.kind.size());
+                for (nth, value) in values.enumerate() {
+                    stats.add_bytes(def.kind, value, nth as u64 / def.count);
+                }
+                offset += len;
+            }
+        }
+
+        report_trailing(reader, report)
+    }
+
+    fn ascii<R: BufRead>(
+        &mut self,
+        reader: &mut R,
+        header: &Header,
+        report: &mut ValidationReport,
+    ) -> Result<()> {
+        let schema = &header.schema;
+        let expect: u64 = schema.iter().map(|def| def.count).sum();
+        let mut token_counts = LineErrors::default();
+        let mut tokens_invalid = LineErrors::default();
+        let mut buffer = vec![];
+
+        let mut index = 0;
+        while index < header.num_points {
+            buffer.clear();
+            if reader.read_until(b'\n', &mut buffer)? == 0 {
+                break;
+            }
+
+            let line = String::from_utf8_lossy(&buffer);
+            let found = line.split_ascii_whitespace().count() as u64;
+            if found != expect {
+                let message = || format!("{found} tokens, but {expect} are expected");
+                token_counts.add(index, message, None);
+                index += 1;
+                continue;
+            }
+
+            let mut tokens = line.split_ascii_whitespace();
+            for (stats, def) in self.floats.iter_mut().zip(schema.iter()) {
+                for token in tokens.by_ref().take(def.count as usize) {
+                    let valid = match def.kind {
+                        ValueKind::F32 => f32::parse_token(token)
+                            .map(|value| stats.add(value.is_nan(), value.is_infinite(), index))
+                            .is_ok(),
+                        ValueKind::F64 => f64::parse_token(token)
+                            .map(|value| stats.add(value.is_nan(), value.is_infinite(), index))
+                            .is_ok(),
+                        kind => match_kind!(kind, T => T::parse_token(token).is_ok()),
+                    };
+                    if !valid {
+                        let message = || format!("{token:?} is not a valid {:?}", def.kind);
+                        tokens_invalid.add(index, message, Some(&def.name));
+                    }
+                }
+            }
+            index += 1;
+        }
+
+        token_counts.report(Check::TokenCount, "lines have wrong token counts", report);
+        tokens_invalid.report(Check::TokenParse, "tokens cannot be parsed", report);
+
+        if index < header.num_points {
+            let message = format!(
+                "the data section has {index} lines, but POINTS is {}",
+                header.num_points
+            );
+            report.push(Finding::new(Severity::Error, Check::DataLength, message).at_point(index));
+            return Ok(());
+        }
+
+        let mut extra = 0;
+        loop {
+            buffer.clear();
+            if reader.read_until(b'\n', &mut buffer)? == 0 {
+                break;
+            }
+            if !buffer.trim_ascii().is_empty() {
+                extra += 1;
+            }
+        }
+        if extra > 0 {
+            let message = format!("{extra} lines after the last point");
+            report.push(Finding::new(Severity::Warning, Check::DataLength, message));
+        }
+        Ok(())
+    }
+
+    fn report_floats(self, schema: &Schema, report: &mut ValidationReport) {
+        for (stats, def) in self.floats.into_iter().zip(schema.iter()) {
+            if let Some(index) = stats.first_nan {
+                let message = format!("{} NaN values", stats.nan);
+                report.push(
+                    Finding::new(Severity::Info, Check::NanInf, message)
+                        .at_point(index)
+                        .in_field(&def.name),
+                );
+            }
+            if let Some(index) = stats.first_inf {
+                let message = format!("{} infinite values", stats.inf);
+                report.push(
+                    Finding::new(Severity::Warning, Check::NanInf, message)
+                        .at_point(index)
+                        .in_field(&def.name),
+                );
+            }
+        }
+    }
+}
+
+/// Reads until the buffer is full or the end of input.
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(size) => filled += size,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(filled)
+}
+
+fn report_truncated(
+    header: &Header,
+    point_size: usize,
+    actual: u64,
+    index: u64,
+    report: &mut ValidationReport,
+) {
+    let expected = header.num_points.saturating_mul(point_size as u64);
+    let message = format!(
+        "the data section has {actual} bytes, but {} points of {point_size} bytes take {expected} bytes",
+        header.num_points
+    );
+    report.push(Finding::new(Severity::Error, Check::DataLength, message).at_point(index));
+}
+
+fn report_trailing<R: Read>(reader: &mut R, report: &mut ValidationReport) -> Result<()> {
+    let trailing = io::copy(reader, &mut io::sink())?;
+    if trailing > 0 {
+        let message = format!("{trailing} bytes after the data section");
+        report.push(Finding::new(Severity::Warning, Check::DataLength, message));
+    }
+    Ok(())
+}
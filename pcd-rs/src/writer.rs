@@ -47,20 +47,30 @@ fn main() -> Result<()> {
 )]
 
 use crate::{
+    error::LimitKind,
     lzf,
-    metas::{DataKind, FieldDef, Schema, ValueKind, ViewPoint},
-    record::{DynRecord, PcdSerialize},
+    metas::{DataKind, FieldDef, FieldShape, PcdMeta, Schema, SchemaDiff, ViewPoint},
+    reader::{check_compressed_sizes, ReaderLimits},
+    record::{DynRecord, PcdSerialize, RawPoint, TokenWriter},
+    traits::TokenFormat,
     Error, Result,
 };
-use byteorder::{LittleEndian, WriteBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::{
-    collections::HashSet,
-    fs::File,
-    io::{prelude::*, BufWriter, Cursor, SeekFrom},
+    collections::{HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::{self, prelude::*, BufReader, BufWriter, SeekFrom},
     marker::PhantomData,
     path::Path,
+    sync::mpsc::{self, SyncSender},
+    thread::{self, JoinHandle},
 };
 
+/// The number of points sent at once to the thread of [ThreadedWriter].
+const THREADED_BATCH_SIZE: usize = 1024;
+/// The number of batches queued for the thread of [ThreadedWriter].
+const THREADED_QUEUE_DEPTH: usize = 4;
+
 /// The `DynReader` struct writes points with schema determined in runtime.
 pub type DynWriter<W> = Writer<DynRecord, W>;
 
@@ -73,6 +83,36 @@ pub struct WriterInit {
     pub schema: Option<Schema>,
 }
 
+/// Formatting options of floats in ASCII data, set by
+/// [WriterInit::with_ascii_format()].
+///
+/// By default, floats are written in the shortest form that
+/// round-trips, and NaN values as `nan`. All options produce tokens
+/// readable by PCL, except that PCL expects [NanSpelling::Lower] for
+/// NaN values.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AsciiFormat {
+    /// The number of decimal places of all float fields.
+    pub precision: Option<usize>,
+    /// The number of decimal places of float fields by name, overriding
+    /// `precision`.
+    pub field_precision: HashMap<String, usize>,
+    /// Writes floats in scientific notation, such as `1.5e-3`.
+    pub scientific: bool,
+    /// The spelling of NaN values.
+    pub nan: NanSpelling,
+}
+
+/// The spelling of NaN values in ASCII data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NanSpelling {
+    /// `nan`, as written by PCL and numpy.
+    #[default]
+    Lower,
+    /// `NaN`, as Rust formats it.
+    Mixed,
+}
+
 impl WriterInit {
     /// Builds new [Writer](crate::writer::Writer) object from a writer.
     /// The writer must implement both [Write](std::io::Write) and [Write](std::io::Seek)
@@ -81,33 +121,67 @@ impl WriterInit {
         self,
         writer: W,
     ) -> Result<Writer<Record, W>, Error> {
-        let record_spec = if Record::is_dynamic() {
-            // Check if the schema is set.
-            let Some(schema) = self.schema else {
-                return Err(Error::new_invalid_writer_configuration_error(
-                    "The schema is not set on the writer. It is required for the dynamic record type."
-                ));
-            };
+        self.with_ascii_format(AsciiFormat::default())
+            .build_from_writer(writer)
+    }
 
-            schema
-        } else {
-            if self.schema.is_some() {
-                return Err(Error::new_invalid_writer_configuration_error(
-                    "schema should not be set for static record type",
-                ));
-            }
-            Record::write_spec()
-        };
+    /// Builds new [Writer](crate::writer::Writer) by creating a new file.
+    pub fn create<Record, P>(self, path: P) -> Result<Writer<Record, BufWriter<File>>>
+    where
+        Record: PcdSerialize,
+        P: AsRef<Path>,
+    {
+        self.with_ascii_format(AsciiFormat::default()).create(path)
+    }
 
-        let seq_writer = Writer::new(
-            self.width,
-            self.height,
-            self.data_kind,
-            self.viewpoint,
-            record_spec,
-            writer,
-        )?;
-        Ok(seq_writer)
+    /// Sets the formatting of floats in ASCII data, which is ignored
+    /// for binary data.
+    ///
+    /// ```rust
+    /// # fn main() -> pcd_rs::Result<()> {
+    /// use pcd_rs::{AsciiFormat, DataKind, DynWriter, Schema, ValueKind, WriterInit};
+    ///
+    /// let writer: DynWriter<_> = WriterInit {
+    ///     width: 0,
+    ///     height: 1,
+    ///     viewpoint: Default::default(),
+    ///     data_kind: DataKind::Ascii,
+    ///     schema: Some(Schema::from_iter([("x", ValueKind::F32, 1)])),
+    /// }
+    /// .with_ascii_format(AsciiFormat {
+    ///     precision: Some(3),
+    ///     ..Default::default()
+    /// })
+    /// .build_from_writer(std::io::Cursor::new(vec![]))?;
+    /// writer.finish()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_ascii_format(self, ascii_format: AsciiFormat) -> FormattedWriterInit {
+        FormattedWriterInit {
+            init: self,
+            ascii_format,
+        }
+    }
+}
+
+/// A [WriterInit] with the formatting of floats in ASCII data, created
+/// by [WriterInit::with_ascii_format()].
+///
+/// The format is checked before anything is written, and building
+/// fails if a field precision is set for a field not in the schema.
+pub struct FormattedWriterInit {
+    init: WriterInit,
+    ascii_format: AsciiFormat,
+}
+
+impl FormattedWriterInit {
+    /// Builds new [Writer](crate::writer::Writer) object from a writer.
+    pub fn build_from_writer<Record: PcdSerialize, W: Write + Seek>(
+        self,
+        writer: W,
+    ) -> Result<Writer<Record, W>> {
+        self.build_with_version(writer, "0.7")
     }
 
     /// Builds new [Writer](crate::writer::Writer) by creating a new file.
@@ -116,12 +190,42 @@ impl WriterInit {
         Record: PcdSerialize,
         P: AsRef<Path>,
     {
+        // The file is not created if the configuration is invalid.
+        let (data_kind, record_spec, header) = self.prepare::<Record>("0.7")?;
         let writer = BufWriter::new(File::create(path.as_ref())?);
-        let seq_writer = self.build_from_writer(writer)?;
-        Ok(seq_writer)
+        Writer::new(data_kind, record_spec, header, writer)
+    }
+
+    /// Builds a writer that writes a header of the given version, which
+    /// is one of "0.5", "0.6" and "0.7".
+    pub(crate) fn build_with_version<Record: PcdSerialize, W: Write + Seek>(
+        self,
+        writer: W,
+        version: &str,
+    ) -> Result<Writer<Record, W>> {
+        let (data_kind, record_spec, header) = self.prepare::<Record>(version)?;
+        Writer::new(data_kind, record_spec, header, writer)
+    }
+
+    /// Resolves the schema and encodes the header, checking the
+    /// configuration before anything is written.
+    pub(crate) fn prepare<Record: PcdSerialize>(
+        self,
+        version: &str,
+    ) -> Result<(DataKind, Schema, Header)> {
+        let Self {
+            mut init,
+            ascii_format,
+        } = self;
+        let record_spec = resolve_schema::<Record>(init.schema.take())?;
+        let header = Header::new(&init, &record_spec, &ascii_format, version)?;
+        Ok((init.data_kind, record_spec, header))
     }
 }
 
+/// Truncates the underlying file of a writer to a length.
+type Truncate<W> = fn(&mut W, u64) -> io::Result<()>;
+
 /// The `Writer` struct writes points in type `T` to writer `W`.
 pub struct Writer<T, W>
 where
@@ -131,10 +235,32 @@ where
     record_spec: Schema,
     writer: W,
     num_records: usize,
+    /// The maximum number of records the POINTS and WIDTH arguments
+    /// can hold.
+    max_records: usize,
     points_arg_begin: u64,
     points_arg_width: usize,
+    /// The position and width of the WIDTH argument, which is patched
+    /// by `finish()` if the writer appends to a file.
+    width_arg: Option<(u64, usize)>,
+    /// The positions and widths of the WIDTH and HEIGHT arguments of a
+    /// new header, which are patched by `finish_unorganized()`.
+    dims_args: Option<[(u64, usize); 2]>,
+    /// The WIDTH and HEIGHT of the header.
+    dimensions: (u64, u64),
+    /// Requires exactly WIDTH times HEIGHT points on finish.
+    organized: bool,
     finished: bool,
     compressed_buffer: Option<Vec<u8>>,
+    /// The position of the binary_compressed data section and the
+    /// function truncating the file after it, if the writer appends to
+    /// a file. The data section is rewritten by `finish()`.
+    compressed_rewrite: Option<(u64, Truncate<W>)>,
+    /// Reused for encoding each point.
+    chunk: Vec<u8>,
+    token_format: TokenFormat,
+    /// The decimal places of each token in an ASCII line.
+    token_precisions: Vec<Option<usize>>,
     _phantom: PhantomData<T>,
 }
 
@@ -144,200 +270,954 @@ where
     W: Write + Seek,
 {
     fn new(
-        width: u64,
-        height: u64,
         data_kind: DataKind,
-        viewpoint: ViewPoint,
         record_spec: Schema,
+        header: Header,
         mut writer: W,
-    ) -> Result<Self, Error> {
-        macro_rules! ensure {
-            ($cond:expr, $desc:expr) => {
-                if !$cond {
-                    return Err(Error::new_invalid_writer_configuration_error($desc));
-                }
-            };
+    ) -> Result<Self> {
+        let Header {
+            bytes,
+            points_arg_offset,
+            points_arg_width,
+            dims_args,
+            width,
+            height,
+            token_format,
+            token_precisions,
+        } = header;
+
+        let header_begin = writer.stream_position()?;
+        let points_arg_begin = header_begin + points_arg_offset;
+        let dims_args = dims_args.map(|(offset, width)| (header_begin + offset, width));
+        writer.write_all(&bytes)?;
+
+        let compressed_buffer = if data_kind == DataKind::BinaryCompressed {
+            Some(Vec::new())
+        } else {
+            None
+        };
+
+        let seq_writer = Self {
+            data_kind,
+            record_spec,
+            writer,
+            num_records: 0,
+            max_records: usize::MAX,
+            points_arg_begin,
+            points_arg_width,
+            width_arg: None,
+            dims_args: Some(dims_args),
+            dimensions: (width, height),
+            organized: false,
+            finished: false,
+            compressed_buffer,
+            compressed_rewrite: None,
+            chunk: vec![],
+            token_format,
+            token_precisions,
+            _phantom: PhantomData,
+        };
+        Ok(seq_writer)
+    }
+
+    /// Requires the points to fill the WIDTH and HEIGHT of the header.
+    ///
+    /// Pushing more than WIDTH times HEIGHT points fails, and
+    /// [finish()](Writer::finish) fails if fewer points are pushed.
+    /// Invalid points of an organized point cloud are kept in place
+    /// and marked by NaN coordinates, as PCL does. The mode cannot be
+    /// used on a writer created by [append()](Writer::append), whose
+    /// WIDTH grows with the points.
+    pub fn set_organized(&mut self) -> Result<()> {
+        if self.width_arg.is_some() {
+            return Err(Error::new_invalid_argument_error(
+                "an appended point cloud cannot be organized",
+            ));
         }
 
-        // Run sanity check on the schema.
-        {
-            for FieldDef { name, count, .. } in &record_spec {
-                if name.is_empty() {}
-                ensure!(!name.is_empty(), "field name must not be empty");
-                ensure!(*count > 0, "The field count must be nonzero");
+        let (width, height) = self.dimensions;
+        let num_points = width
+            .checked_mul(height)
+            .and_then(|num_points| usize::try_from(num_points).ok())
+            .unwrap_or(usize::MAX);
+        self.max_records = self.max_records.min(num_points);
+        self.organized = true;
+        Ok(())
+    }
+
+    /// Finish the writer.
+    ///
+    /// The method consumes the writer must be called once when finished.
+    /// Otherwise it will panic when it drops.
+    ///
+    /// In the [organized](Writer::set_organized) mode, it fails if the
+    /// number of points is not WIDTH times HEIGHT. The data is written
+    /// out anyway, with WIDTH set to POINTS and HEIGHT to 1, so that the
+    /// output is a valid unorganized point cloud.
+    pub fn finish(mut self) -> Result<()> {
+        let (width, height) = self.dimensions;
+        let is_mismatched =
+            self.organized && Some(self.num_records as u64) != width.checked_mul(height);
+        if is_mismatched {
+            self.set_unorganized()?;
+        }
+
+        if let Some((data_offset, _)) = self.compressed_rewrite {
+            self.writer.seek(SeekFrom::Start(data_offset))?;
+        }
+        if let Some(ref uncompressed_data) = self.compressed_buffer {
+            self.writer.write_all(&compress_data(uncompressed_data)?)?;
+        }
+        let data_end = self.writer.stream_position()?;
+
+        // Update the points count in the header
+        self.writer.seek(SeekFrom::Start(self.points_arg_begin))?;
+        self.writer
+            .write_all(points_arg(self.num_records, self.points_arg_width).as_bytes())?;
+        if let Some((width_arg_begin, width_arg_width)) = self.width_arg {
+            self.writer.seek(SeekFrom::Start(width_arg_begin))?;
+            self.writer
+                .write_all(points_arg(self.num_records, width_arg_width).as_bytes())?;
+        }
+        self.writer.flush()?;
+        if let Some((_, truncate)) = self.compressed_rewrite {
+            truncate(&mut self.writer, data_end)?;
+        }
+        self.finished = true;
+
+        if is_mismatched {
+            return Err(Error::new_point_count_mismatch_error(
+                width,
+                height,
+                self.num_records as u64,
+            ));
+        }
+        Ok(())
+    }
+
+    /// The number of points written so far.
+    pub(crate) fn num_records(&self) -> usize {
+        self.num_records
+    }
+
+    /// Finishes the writer as an unorganized point cloud, whose WIDTH
+    /// is POINTS and HEIGHT is 1, such as the partial output of a
+    /// broken input.
+    pub(crate) fn finish_unorganized(mut self) -> Result<()> {
+        self.set_unorganized()?;
+        self.organized = false;
+        self.finish()
+    }
+
+    /// Patches HEIGHT to 1 and lets `finish()` patch WIDTH to POINTS.
+    /// WIDTH and HEIGHT are left as is if the number of points does not
+    /// fit in the WIDTH argument.
+    fn set_unorganized(&mut self) -> Result<()> {
+        if let Some([width_arg, (height_arg_begin, height_arg_width)]) = self.dims_args {
+            if self.num_records <= arg_capacity(width_arg.1) {
+                let position = self.writer.stream_position()?;
+                self.writer.seek(SeekFrom::Start(height_arg_begin))?;
+                self.writer
+                    .write_all(points_arg(1, height_arg_width).as_bytes())?;
+                self.writer.seek(SeekFrom::Start(position))?;
+                self.width_arg = Some(width_arg);
             }
+        }
+        Ok(())
+    }
 
-            let names: HashSet<_> = record_spec.iter().map(|field| &field.name).collect();
-            ensure!(
-                names.len() == record_spec.len(),
-                "schema names must be unique"
+    /// Writes a new point to PCD data.
+    pub fn push(&mut self, record: &Record) -> Result<()> {
+        self.reserve(1)?;
+
+        match (self.data_kind, &mut self.compressed_buffer) {
+            (DataKind::Ascii, _) => {
+                self.write_line_with(|tokens, spec| record.encode_tokens(tokens, spec))?
+            }
+            // Buffer the binary data for compression
+            (_, Some(buffer)) => record.encode(buffer, &self.record_spec)?,
+            (_, None) => {
+                self.chunk.clear();
+                record.encode(&mut self.chunk, &self.record_spec)?;
+                self.writer.write_all(&self.chunk)?;
+            }
+        }
+
+        self.num_records += 1;
+        Ok(())
+    }
+
+    /// Writes the bytes of binary points laid out as the schema.
+    ///
+    /// The data kind must be binary or binary_compressed, and the
+    /// length must be a multiple of the point size.
+    pub(crate) fn push_binary(&mut self, bytes: &[u8]) -> Result<()> {
+        debug_assert_ne!(self.data_kind, DataKind::Ascii);
+        let point_size = self.record_spec.point_size();
+        let count = bytes.len().checked_div(point_size).unwrap_or(0);
+        debug_assert_eq!(count * point_size, bytes.len());
+        self.reserve(count)?;
+
+        match self.compressed_buffer {
+            Some(ref mut buffer) => buffer.extend_from_slice(bytes),
+            None => self.writer.write_all(bytes)?,
+        }
+
+        self.num_records += count;
+        Ok(())
+    }
+
+    /// Checks whether the header can hold the count of `additional`
+    /// more records.
+    fn reserve(&self, additional: usize) -> Result<()> {
+        let count = self.num_records.saturating_add(additional);
+        if count > self.max_records {
+            return Err(Error::new_limit_exceeded_error(
+                LimitKind::Points,
+                self.max_records as u64,
+                count as u64,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Encodes an ASCII line with the formatting options and writes it.
+    fn write_line_with<F>(&mut self, encode: F) -> Result<()>
+    where
+        F: FnOnce(&mut TokenWriter<'_>, &Schema) -> Result<()>,
+    {
+        self.chunk.clear();
+        let mut tokens =
+            TokenWriter::with_format(&mut self.chunk, self.token_format, &self.token_precisions);
+        encode(&mut tokens, &self.record_spec)?;
+        tokens.finish();
+        self.writer.write_all(&self.chunk)?;
+        Ok(())
+    }
+}
+
+impl<W, Record> Writer<Record, W>
+where
+    Record: PcdSerialize,
+    W: Write + Seek,
+{
+    /// Writes a slice of points to PCD data.
+    ///
+    /// For binary data, records derived with `#[pcd(pod)]` are copied
+    /// to the output with a single memcpy. Otherwise, the points are
+    /// encoded one by one.
+    pub fn push_batch(&mut self, records: &[Record]) -> Result<()> {
+        self.reserve(records.len())?;
+
+        let bytes = match self.data_kind {
+            DataKind::Ascii => None,
+            DataKind::Binary | DataKind::BinaryCompressed => Record::pod_bytes(records),
+        };
+
+        let Some(bytes) = bytes else {
+            for record in records {
+                self.push(record)?;
+            }
+            return Ok(());
+        };
+
+        match self.compressed_buffer {
+            Some(ref mut buffer) => buffer.extend_from_slice(bytes),
+            None => self.writer.write_all(bytes)?,
+        }
+
+        self.num_records += records.len();
+        Ok(())
+    }
+
+    /// Writes a row of an organized point cloud.
+    ///
+    /// The row must have WIDTH points and start at the beginning of a
+    /// row, that is, the points pushed so far must fill whole rows.
+    pub fn push_row(&mut self, row: &[Record]) -> Result<()> {
+        if self.width_arg.is_some() {
+            return Err(Error::new_invalid_argument_error(
+                "rows cannot be pushed to an appended point cloud",
+            ));
+        }
+
+        let (width, _) = self.dimensions;
+        if row.len() as u64 != width {
+            let desc = format!("the row has {} points, but WIDTH is {width}", row.len());
+            return Err(Error::new_invalid_argument_error(&desc));
+        }
+        if !(self.num_records as u64).is_multiple_of(width) {
+            let desc = format!(
+                "{} points are pushed, which do not fill whole rows of WIDTH {width}",
+                self.num_records
             );
+            return Err(Error::new_invalid_argument_error(&desc));
         }
 
-        let (points_arg_begin, points_arg_width) = {
-            let fields_args: Vec<_> = record_spec
-                .iter()
-                .map(|field| field.name.to_owned())
-                .collect();
+        self.push_batch(row)
+    }
 
-            let size_args: Vec<_> = record_spec
+    /// Writes a point read by [RawPoints](crate::reader::RawPoints).
+    ///
+    /// The fields of the point must match the schema of the writer in
+    /// kinds and counts. For binary data, the bytes are copied without
+    /// decoding.
+    pub fn push_raw(&mut self, point: &RawPoint<'_>) -> Result<()> {
+        if !point.schema().is_compatible_with(&self.record_spec) {
+            // The fields are compared by kinds and counts only.
+            let expected: Vec<FieldShape> = self.record_spec.iter().map(FieldShape::from).collect();
+            let found: Vec<FieldShape> = point
+                .schema()
                 .iter()
-                .map(|field| {
-                    use ValueKind::*;
-                    let size = match field.kind {
-                        U8 | I8 => 1,
-                        U16 | I16 => 2,
-                        U32 | I32 | F32 => 4,
-                        F64 => 8,
-                    };
-                    size.to_string()
+                .map(|def| FieldShape {
+                    name: None,
+                    ..def.into()
                 })
                 .collect();
+            return Err(Error::new_writer_schema_mismatch_error(SchemaDiff::new(
+                &expected, &found,
+            )));
+        }
+        self.reserve(1)?;
 
-            let type_args: Vec<_> = record_spec
-                .iter()
-                .map(|field| {
-                    use ValueKind::*;
-                    match field.kind {
-                        U8 | U16 | U32 => "U",
-                        I8 | I16 | I32 => "I",
-                        F32 | F64 => "F",
-                    }
-                })
-                .collect();
+        match (self.data_kind, &mut self.compressed_buffer) {
+            (DataKind::Ascii, _) => {
+                let record = point.to_record()?;
+                self.write_line_with(|tokens, spec| record.encode_tokens(tokens, spec))?
+            }
+            (_, Some(buffer)) => buffer.extend_from_slice(point.bytes()),
+            (_, None) => self.writer.write_all(point.bytes())?,
+        }
 
-            let count_args: Vec<_> = record_spec
-                .iter()
-                .map(|field| field.count.to_string())
-                .collect();
+        self.num_records += 1;
+        Ok(())
+    }
+}
 
-            let viewpoint_args: Vec<_> = {
-                [
-                    viewpoint.tx,
-                    viewpoint.ty,
-                    viewpoint.tz,
-                    viewpoint.qw,
-                    viewpoint.qx,
-                    viewpoint.qy,
-                    viewpoint.qz,
-                ]
-                .iter()
-                .map(|value| value.to_string())
-                .collect()
-            };
+impl<Record> Writer<Record, BufWriter<File>>
+where
+    Record: PcdSerialize,
+{
+    /// Opens an existing PCD file to append points to it.
+    ///
+    /// The points are written after the existing points, and the POINTS
+    /// and WIDTH in the header are updated by [finish()](Writer::finish).
+    /// Only unorganized point clouds, whose HEIGHT is 1, can be appended.
+    ///
+    /// The POINTS and WIDTH arguments are updated in place, so the
+    /// number of points is limited by the characters available to them,
+    /// including the padding spaces. Headers written by [Writer] reserve
+    /// 20 characters for POINTS, while WIDTH keeps the digits it was
+    /// written with. A [LimitExceeded](crate::ErrorKind::LimitExceeded)
+    /// error is returned when the count no longer fits.
+    ///
+    /// The file is refused with a
+    /// [TrailingData](crate::ErrorKind::TrailingData) error if there is
+    /// data after the last point, except blank lines after ASCII data,
+    /// which are removed. For binary_compressed data, the existing
+    /// points are decompressed and compressed again with the new points
+    /// on finish, and the file is left untouched until then.
+    pub fn append<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let target = AppendTarget::open(path)?;
+        check_append_schema::<Record>(&target.meta.field_defs)?;
 
-            let points_arg_width = (usize::max_value() as f64).log10().floor() as usize + 1;
+        if target.meta.height != 1 {
+            let desc = format!(
+                "cannot append to an organized point cloud with HEIGHT {}",
+                target.meta.height
+            );
+            return Err(Error::new_invalid_argument_error(&desc));
+        }
 
-            writeln!(writer, "# .PCD v.7 - Point Cloud Data file format")?;
-            writeln!(writer, "VERSION .7")?;
-            writeln!(writer, "FIELDS {}", fields_args.join(" "))?;
-            writeln!(writer, "SIZE {}", size_args.join(" "))?;
-            writeln!(writer, "TYPE {}", type_args.join(" "))?;
-            writeln!(writer, "COUNT {}", count_args.join(" "))?;
-            writeln!(writer, "WIDTH {}", width)?;
-            writeln!(writer, "HEIGHT {}", height)?;
-            writeln!(writer, "VIEWPOINT {}", viewpoint_args.join(" "))?;
+        let AppendTarget {
+            mut file,
+            meta,
+            data_offset,
+            points_arg: (points_arg_offset, points_arg_width),
+            width_arg,
+        } = target;
+        file.seek(SeekFrom::Start(data_offset))?;
+        let mut reader = BufReader::new(&file);
 
-            write!(writer, "POINTS ")?;
-            let points_arg_begin = writer.seek(SeekFrom::Current(0))?;
-            writeln!(writer, "{:width$}", " ", width = points_arg_width)?;
+        let data_len = meta.num_points.saturating_mul(meta.point_size() as u64);
+        let compressed_buffer = if meta.data == DataKind::BinaryCompressed {
+            let compressed_size = reader.read_u32::<LittleEndian>()?;
+            let uncompressed_size = reader.read_u32::<LittleEndian>()?;
+            check_compressed_sizes(compressed_size, uncompressed_size, &ReaderLimits::default())?;
 
-            match data_kind {
-                DataKind::Binary => writeln!(writer, "DATA binary")?,
-                DataKind::Ascii => writeln!(writer, "DATA ascii")?,
-                DataKind::BinaryCompressed => writeln!(writer, "DATA binary_compressed")?,
-            }
+            let mut compressed_data = vec![0u8; compressed_size as usize];
+            reader.read_exact(&mut compressed_data)?;
+            let data = if compressed_size == 0 && uncompressed_size == 0 {
+                vec![]
+            } else {
+                lzf::decompress(&compressed_data, uncompressed_size as usize)?
+            };
 
-            (points_arg_begin, points_arg_width)
-        };
+            if (data.len() as u64) < data_len {
+                return Err(Error::new_truncated_data_error(data_len, data.len() as u64));
+            }
+            if (data.len() as u64) > data_len {
+                return Err(Error::new_trailing_data_error(data.len() as u64 - data_len));
+            }
 
-        let compressed_buffer = if data_kind == DataKind::BinaryCompressed {
-            Some(Vec::new())
+            // The data section is rewritten on finish, which would drop
+            // the bytes after it.
+            let end = reader.stream_position()?;
+            let file_len = file.metadata()?.len();
+            if file_len > end {
+                return Err(Error::new_trailing_data_error(file_len - end));
+            }
+            Some(data)
         } else {
             None
         };
 
-        let seq_writer = Self {
-            data_kind,
-            record_spec,
-            writer,
-            num_records: 0,
-            points_arg_begin,
+        drop(reader);
+        let max_records = arg_capacity(points_arg_width).min(arg_capacity(width_arg.1));
+        if meta.num_points >= max_records as u64 {
+            return Err(Error::new_limit_exceeded_error(
+                LimitKind::Points,
+                max_records as u64,
+                meta.num_points + 1,
+            ));
+        }
+
+        match meta.data {
+            DataKind::Ascii => {
+                // Keep the lines of the existing points only.
+                file.seek(SeekFrom::Start(data_offset))?;
+                let mut reader = BufReader::new(&file);
+                let mut end = data_offset;
+                let mut line = vec![];
+                let mut num_lines = 0;
+                while num_lines < meta.num_points {
+                    line.clear();
+                    let len = reader.read_until(b'\n', &mut line)?;
+                    if len == 0 {
+                        break;
+                    }
+                    end += len as u64;
+                    num_lines += 1;
+                }
+
+                if num_lines < meta.num_points {
+                    let desc = format!(
+                        "expect {} lines of points, but found {}",
+                        meta.num_points, num_lines
+                    );
+                    return Err(Error::new_corrupt_data_error(&desc));
+                }
+
+                // Only blank lines may follow the points.
+                let mut trailing = 0;
+                for byte in reader.bytes() {
+                    if !byte?.is_ascii_whitespace() {
+                        let file_len = file.metadata()?.len();
+                        return Err(Error::new_trailing_data_error(file_len - end));
+                    }
+                    trailing += 1;
+                }
+                if trailing > 0 {
+                    file.set_len(end)?;
+                }
+                file.seek(SeekFrom::Start(end))?;
+
+                // Complete the last line if the line break is missing.
+                if line.last().is_some_and(|&byte| byte != b'\n') {
+                    file.write_all(b"\n")?;
+                }
+            }
+            DataKind::Binary => {
+                let end = data_offset + data_len;
+                let file_len = file.metadata()?.len();
+                if file_len < end {
+                    return Err(Error::new_truncated_data_error(
+                        data_len,
+                        file_len - data_offset,
+                    ));
+                }
+                if file_len > end {
+                    return Err(Error::new_trailing_data_error(file_len - end));
+                }
+                file.seek(SeekFrom::Start(end))?;
+            }
+            // The data section is written again on finish.
+            DataKind::BinaryCompressed => {}
+        }
+
+        let (token_format, token_precisions) =
+            token_format(&meta.field_defs, &AsciiFormat::default())?;
+
+        Ok(Self {
+            data_kind: meta.data,
+            record_spec: meta.field_defs,
+            writer: BufWriter::new(file),
+            num_records: meta.num_points as usize,
+            max_records,
+            points_arg_begin: points_arg_offset,
             points_arg_width,
+            width_arg: Some(width_arg),
+            dims_args: None,
+            dimensions: (meta.width, meta.height),
+            organized: false,
             finished: false,
             compressed_buffer,
+            compressed_rewrite: (meta.data == DataKind::BinaryCompressed)
+                .then_some((data_offset, truncate_file)),
+            chunk: vec![],
+            token_format,
+            token_precisions,
             _phantom: PhantomData,
+        })
+    }
+}
+
+/// Truncates the file appended to after the rewritten data section.
+fn truncate_file(writer: &mut BufWriter<File>, len: u64) -> io::Result<()> {
+    writer.get_ref().set_len(len)
+}
+
+/// A file opened by [Writer::append()] with the positions of the
+/// arguments to be patched.
+struct AppendTarget {
+    file: File,
+    meta: PcdMeta,
+    data_offset: u64,
+    /// The position and width of the POINTS argument.
+    points_arg: (u64, usize),
+    /// The position and width of the WIDTH argument.
+    width_arg: (u64, usize),
+}
+
+impl AppendTarget {
+    fn open(path: &Path) -> Result<Self> {
+        let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+        let (meta, data_offset) = PcdMeta::read_from(BufReader::new(&file))?;
+
+        let mut header = vec![0; data_offset as usize];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut header)?;
+        let points_arg = find_header_arg(&header, "POINTS")?;
+        let width_arg = find_header_arg(&header, "WIDTH")?;
+
+        Ok(Self {
+            file,
+            meta,
+            data_offset,
+            points_arg,
+            width_arg,
+        })
+    }
+}
+
+/// Checks whether the static record type matches the schema of an
+/// appended file.
+fn check_append_schema<Record: PcdSerialize>(schema: &Schema) -> Result<()> {
+    if Record::is_dynamic() {
+        return Ok(());
+    }
+
+    let is_matched = if Record::FIELDS.is_empty() {
+        Record::write_spec() == *schema
+    } else {
+        Record::FIELDS.len() == schema.len()
+            && Record::FIELDS
+                .iter()
+                .zip(schema.iter())
+                .all(|(record_field, field)| record_field.matches(field))
+    };
+
+    if !is_matched {
+        let expected: Vec<FieldShape> = schema.iter().map(FieldShape::from).collect();
+        let found: Vec<FieldShape> = if Record::FIELDS.is_empty() {
+            Record::write_spec().iter().map(FieldShape::from).collect()
+        } else {
+            Record::FIELDS.iter().map(FieldShape::from).collect()
         };
-        Ok(seq_writer)
+        return Err(Error::new_writer_schema_mismatch_error(SchemaDiff::new(
+            &expected, &found,
+        )));
     }
+    Ok(())
+}
 
-    /// Finish the writer.
+/// Finds the argument of a header entry, returning its position and
+/// the width available to it, including the padding spaces.
+fn find_header_arg(header: &[u8], entry: &str) -> Result<(u64, usize)> {
+    let mut line_begin = 0;
+
+    for line in header.split_inclusive(|&byte| byte == b'\n') {
+        let content_len = line
+            .iter()
+            .position(|&byte| matches!(byte, b'#' | b'\r' | b'\n'))
+            .unwrap_or(line.len());
+        let mut tokens = line[..content_len]
+            .split(|byte| byte.is_ascii_whitespace())
+            .filter(|token| !token.is_empty());
+
+        if tokens.next() == Some(entry.as_bytes()) {
+            if let Some(arg) = tokens.next() {
+                // The token is a subslice of the line.
+                let arg_begin = arg.as_ptr() as usize - line.as_ptr() as usize;
+                let offset = (line_begin + arg_begin) as u64;
+                return Ok((offset, content_len - arg_begin));
+            }
+        }
+
+        line_begin += line.len();
+    }
+
+    let desc = format!("{entry} entry is not found in the header");
+    Err(Error::new_corrupt_data_error(&desc))
+}
+
+/// Returns the largest count that fits in an argument of the width.
+fn arg_capacity(width: usize) -> usize {
+    u32::try_from(width)
+        .ok()
+        .and_then(|width| 10usize.checked_pow(width))
+        .map_or(usize::MAX, |limit| limit - 1)
+}
+
+impl<W, Record> Writer<Record, W>
+where
+    Record: PcdSerialize + Send + 'static,
+    W: Write + Seek + Send + 'static,
+{
+    /// Moves the encoding and writing to a background thread.
     ///
-    /// The method consumes the writer must be called once when finished.
-    /// Otherwise it will panic when it drops.
-    pub fn finish(mut self) -> Result<()> {
-        // Write compressed data if using compression
-        if self.data_kind == DataKind::BinaryCompressed {
-            if let Some(ref uncompressed_data) = self.compressed_buffer {
-                if uncompressed_data.is_empty() {
-                    // For empty data, write zeros for sizes
-                    self.writer.write_u32::<LittleEndian>(0)?;
-                    self.writer.write_u32::<LittleEndian>(0)?;
-                } else {
-                    // Compress the data
-                    let compressed_data = lzf::compress(uncompressed_data)?;
-
-                    // Write compressed size and uncompressed size
-                    self.writer
-                        .write_u32::<LittleEndian>(compressed_data.len() as u32)?;
-                    self.writer
-                        .write_u32::<LittleEndian>(uncompressed_data.len() as u32)?;
-
-                    // Write compressed data
-                    self.writer.write_all(&compressed_data)?;
+    /// The points are sent to the thread in batches through a bounded
+    /// queue, so [ThreadedWriter::push()] blocks when the thread falls
+    /// behind.
+    pub fn threaded(self) -> ThreadedWriter<Record, W> {
+        let (sender, receiver) = mpsc::sync_channel::<Message<Record>>(THREADED_QUEUE_DEPTH);
+
+        let handle = thread::spawn(move || {
+            let mut writer = self;
+            for message in receiver {
+                let batch = match message {
+                    Message::Batch(batch) => batch,
+                    Message::Finish => return writer.finish(),
+                };
+                if let Err(error) = writer.push_batch(&batch) {
+                    // The output is incomplete, so it is not finished.
+                    writer.finished = true;
+                    return Err(error);
                 }
             }
+            // The ThreadedWriter is dropped without finish().
+            writer.finished = true;
+            Err(ThreadedWriter::<Record, W>::stopped_error())
+        });
+
+        ThreadedWriter {
+            sender: Some(sender),
+            handle: Some(handle),
+            batch: Vec::with_capacity(THREADED_BATCH_SIZE),
+            _phantom: PhantomData,
         }
+    }
+}
 
-        // Update the points count in the header
-        self.writer.seek(SeekFrom::Start(self.points_arg_begin))?;
-        write!(
-            self.writer,
-            "{:<width$}",
-            self.num_records,
-            width = self.points_arg_width
-        )?;
-        self.finished = true;
+/// A message to the thread of [ThreadedWriter].
+enum Message<T> {
+    Batch(Vec<T>),
+    /// Finishes the writer and stops the thread.
+    Finish,
+}
+
+/// A writer encoding and writing points on a background thread,
+/// created by [Writer::threaded()].
+pub struct ThreadedWriter<T, W>
+where
+    W: Write + Seek,
+{
+    sender: Option<SyncSender<Message<T>>>,
+    handle: Option<JoinHandle<Result<()>>>,
+    batch: Vec<T>,
+    _phantom: PhantomData<W>,
+}
+
+impl<W, Record> ThreadedWriter<Record, W>
+where
+    Record: PcdSerialize + Send + 'static,
+    W: Write + Seek + Send + 'static,
+{
+    /// Queues a copy of the point for writing, like [Writer::push()].
+    ///
+    /// If the background thread failed, its error is returned.
+    pub fn push(&mut self, record: &Record) -> Result<()>
+    where
+        Record: Clone,
+    {
+        self.push_owned(record.clone())
+    }
+
+    /// Queues a point for writing, without copying it.
+    ///
+    /// If the background thread failed, its error is returned.
+    pub fn push_owned(&mut self, record: Record) -> Result<()> {
+        self.batch.push(record);
+        if self.batch.len() >= THREADED_BATCH_SIZE {
+            self.send_batch()?;
+        }
         Ok(())
     }
 
-    /// Writes a new point to PCD data.
-    pub fn push(&mut self, record: &Record) -> Result<()> {
-        match self.data_kind {
-            DataKind::Binary => record.write_chunk(&mut self.writer, &self.record_spec)?,
-            DataKind::Ascii => record.write_line(&mut self.writer, &self.record_spec)?,
-            DataKind::BinaryCompressed => {
-                // Buffer the binary data for compression
-                if let Some(ref mut buffer) = self.compressed_buffer {
-                    // Create a temporary buffer with cursor to write the record
-                    let mut temp_buffer = Vec::new();
-                    let mut cursor = Cursor::new(&mut temp_buffer);
-                    record.write_chunk(&mut cursor, &self.record_spec)?;
-                    buffer.extend_from_slice(&temp_buffer);
-                } else {
-                    return Err(Error::ParseError {
-                        line: 0,
-                        desc: "Compressed buffer not initialized".into(),
-                    });
+    /// Writes the queued points, and finishes the writer on the
+    /// background thread.
+    pub fn finish(mut self) -> Result<()> {
+        if !self.batch.is_empty() {
+            self.send_batch()?;
+        }
+        self.send(Message::Finish)?;
+        self.join()
+    }
+
+    fn send_batch(&mut self) -> Result<()> {
+        let batch = std::mem::replace(&mut self.batch, Vec::with_capacity(THREADED_BATCH_SIZE));
+        self.send(Message::Batch(batch))
+    }
+
+    fn send(&mut self, message: Message<Record>) -> Result<()> {
+        let Some(sender) = &self.sender else {
+            return Err(Self::stopped_error());
+        };
+        if sender.send(message).is_err() {
+            // The thread stops only on errors.
+            self.join()?;
+            return Err(Self::stopped_error());
+        }
+        Ok(())
+    }
+
+    fn join(&mut self) -> Result<()> {
+        self.sender = None;
+        let Some(handle) = self.handle.take() else {
+            return Err(Self::stopped_error());
+        };
+        match handle.join() {
+            Ok(result) => result,
+            Err(payload) => std::panic::resume_unwind(payload),
+        }
+    }
+
+    fn stopped_error() -> Error {
+        std::io::Error::new(
+            std::io::ErrorKind::BrokenPipe,
+            "the background writer thread has stopped",
+        )
+        .into()
+    }
+}
+
+impl<W, Record> Drop for ThreadedWriter<Record, W>
+where
+    W: Write + Seek,
+{
+    fn drop(&mut self) {
+        if self.handle.is_some() && !thread::panicking() {
+            panic!("call finish() before ThreadedWriter drops");
+        }
+    }
+}
+
+/// Resolves the schema of the written records.
+pub(crate) fn resolve_schema<Record: PcdSerialize>(schema: Option<Schema>) -> Result<Schema> {
+    if Record::is_dynamic() {
+        // Check if the schema is set.
+        let Some(schema) = schema else {
+            return Err(Error::new_invalid_writer_configuration_error(
+                "The schema is not set on the writer. It is required for the dynamic record type.",
+            ));
+        };
+
+        Ok(schema)
+    } else {
+        if schema.is_some() {
+            return Err(Error::new_invalid_writer_configuration_error(
+                "schema should not be set for static record type",
+            ));
+        }
+        Ok(Record::write_spec())
+    }
+}
+
+/// The encoded header and the ASCII formatting of a new writer.
+pub(crate) struct Header {
+    pub bytes: Vec<u8>,
+    /// The position of the POINTS argument in the header.
+    pub points_arg_offset: u64,
+    pub points_arg_width: usize,
+    /// The positions and widths of the WIDTH and HEIGHT arguments.
+    pub dims_args: [(u64, usize); 2],
+    pub width: u64,
+    pub height: u64,
+    pub token_format: TokenFormat,
+    /// The decimal places of each token in an ASCII line.
+    pub token_precisions: Vec<Option<usize>>,
+}
+
+impl Header {
+    pub fn new(
+        init: &WriterInit,
+        record_spec: &Schema,
+        ascii_format: &AsciiFormat,
+        version: &str,
+    ) -> Result<Self> {
+        let WriterInit {
+            width,
+            height,
+            data_kind,
+            ref viewpoint,
+            ..
+        } = *init;
+
+        macro_rules! ensure {
+            ($cond:expr, $desc:expr) => {
+                if !$cond {
+                    return Err(Error::new_invalid_writer_configuration_error($desc));
                 }
+            };
+        }
+
+        // Run sanity check on the schema.
+        {
+            for FieldDef { name, count, .. } in record_spec {
+                ensure!(!name.is_empty(), "field name must not be empty");
+                ensure!(*count > 0, "The field count must be nonzero");
             }
+
+            let names: HashSet<_> = record_spec.iter().map(|field| &field.name).collect();
+            ensure!(
+                names.len() == record_spec.len(),
+                "schema names must be unique"
+            );
         }
 
-        self.num_records += 1;
-        Ok(())
+        ensure!(
+            matches!(version, "0.5" | "0.6" | "0.7"),
+            "the version must be one of 0.5, 0.6 and 0.7"
+        );
+        ensure!(
+            version == "0.7" || data_kind != DataKind::BinaryCompressed,
+            "binary_compressed data requires version 0.7"
+        );
+
+        let (token_format, token_precisions) = token_format(record_spec, ascii_format)?;
+
+        let (bytes, points_arg_offset, points_arg_width, dims_args) = {
+            let points_arg_width = (usize::MAX as f64).log10().floor() as usize + 1;
+            // The WIDTH and HEIGHT arguments are padded to hold any
+            // number of points up to WIDTH times HEIGHT, so that an
+            // organized point cloud can be made unorganized on finish.
+            // A single row never needs the room.
+            let dims_width = if height > 1 {
+                width.saturating_mul(height).to_string().len()
+            } else {
+                0
+            };
+            let dims_arg = |writer: &mut Vec<u8>, name: &str, value: u64| {
+                write!(writer, "{name} ")?;
+                let begin = writer.len() as u64;
+                writeln!(writer, "{value:<dims_width$}")?;
+                io::Result::Ok((begin, writer.len() - 1 - begin as usize))
+            };
+
+            let mut writer = vec![];
+            // The version is written in the short form, such as ".7".
+            let version = version.trim_start_matches('0');
+            writeln!(writer, "# .PCD v{version} - Point Cloud Data file format")?;
+            writeln!(writer, "VERSION {version}")?;
+            write!(writer, "{}", record_spec)?;
+            let width_arg = dims_arg(&mut writer, "WIDTH", width)?;
+            let height_arg = dims_arg(&mut writer, "HEIGHT", height)?;
+            // VIEWPOINT is introduced in version 0.7.
+            if version == ".7" {
+                writeln!(writer, "VIEWPOINT {}", viewpoint)?;
+            }
+
+            write!(writer, "POINTS ")?;
+            let points_arg_begin = writer.len() as u64;
+            writeln!(writer, "{:width$}", " ", width = points_arg_width)?;
+
+            writeln!(writer, "DATA {}", data_kind)?;
+
+            (
+                writer,
+                points_arg_begin,
+                points_arg_width,
+                [width_arg, height_arg],
+            )
+        };
+
+        Ok(Self {
+            bytes,
+            points_arg_offset,
+            points_arg_width,
+            dims_args,
+            width,
+            height,
+            token_format,
+            token_precisions,
+        })
     }
 }
 
+/// Resolves the formatting of each token in an ASCII line.
+pub(crate) fn token_format(
+    record_spec: &Schema,
+    ascii_format: &AsciiFormat,
+) -> Result<(TokenFormat, Vec<Option<usize>>)> {
+    for name in ascii_format.field_precision.keys() {
+        if record_spec.index_of(name).is_none() {
+            return Err(Error::new_invalid_writer_configuration_error(
+                "ascii precision is set for a field not in the schema",
+            ));
+        }
+    }
+
+    let token_format = TokenFormat {
+        precision: None,
+        scientific: ascii_format.scientific,
+        nan: ascii_format.nan,
+    };
+    let token_precisions = record_spec
+        .iter()
+        .flat_map(|field| {
+            let precision = ascii_format
+                .field_precision
+                .get(&field.name)
+                .copied()
+                .or(ascii_format.precision);
+            std::iter::repeat_n(precision, field.count as usize)
+        })
+        .collect();
+    Ok((token_format, token_precisions))
+}
+
+/// Encodes the compressed data section with its size prefixes.
+pub(crate) fn compress_data(uncompressed_data: &[u8]) -> Result<Vec<u8>> {
+    let mut output = vec![];
+    if uncompressed_data.is_empty() {
+        // For empty data, write zeros for sizes
+        output.write_u32::<LittleEndian>(0)?;
+        output.write_u32::<LittleEndian>(0)?;
+    } else {
+        let compressed_data = lzf::compress(uncompressed_data)?;
+        output.write_u32::<LittleEndian>(compressed_data.len() as u32)?;
+        output.write_u32::<LittleEndian>(uncompressed_data.len() as u32)?;
+        output.extend_from_slice(&compressed_data);
+    }
+    Ok(output)
+}
+
+/// Formats the POINTS argument padded to the reserved width.
+pub(crate) fn points_arg(num_records: usize, width: usize) -> String {
+    format!("{:<width$}", num_records, width = width)
+}
+
 impl<W, Record> Drop for Writer<Record, W>
 where
     W: Write + Seek,
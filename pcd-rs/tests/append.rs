@@ -0,0 +1,219 @@
+use eyre::Result;
+use pcd_rs::{
+    DataKind, DynReader, DynRecord, DynWriter, ErrorKind, Field, PcdMeta, Schema, ValueKind,
+    WriterInit,
+};
+use std::fs;
+
+fn schema() -> Schema {
+    Schema::from_iter([("x", ValueKind::F32, 1), ("label", ValueKind::U16, 2)])
+}
+
+fn record(value: u16) -> DynRecord {
+    DynRecord(vec![
+        Field::F32(vec![value as f32 * 0.5]),
+        Field::U16(vec![value, value + 1]),
+    ])
+}
+
+#[test]
+fn append_to_written_file() -> Result<()> {
+    for (data_kind, path) in [
+        (DataKind::Ascii, "test_files/append_ascii.pcd"),
+        (DataKind::Binary, "test_files/append_binary.pcd"),
+        (
+            DataKind::BinaryCompressed,
+            "test_files/append_compressed.pcd",
+        ),
+    ] {
+        let records: Vec<_> = (0..7).map(record).collect();
+
+        let mut writer: DynWriter<_> = WriterInit {
+            width: 3,
+            height: 1,
+            viewpoint: Default::default(),
+            data_kind,
+            schema: Some(schema()),
+        }
+        .create(path)?;
+        for record in &records[..3] {
+            writer.push(record)?;
+        }
+        writer.finish()?;
+
+        for chunk in records[3..].chunks(2) {
+            let mut writer = DynWriter::append(path)?;
+            for record in chunk {
+                writer.push(record)?;
+            }
+            writer.finish()?;
+        }
+
+        let (meta, _) = PcdMeta::open(path)?;
+        let loaded: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+        fs::remove_file(path)?;
+
+        assert_eq!(meta.data, data_kind);
+        assert_eq!((meta.width, meta.height, meta.num_points), (7, 1, 7));
+        assert_eq!(loaded, records);
+    }
+    Ok(())
+}
+
+#[test]
+fn append_argument_width() -> Result<()> {
+    let path = "test_files/append_narrow.pcd";
+    // The last line has no line break.
+    let text = "\
+VERSION .7
+FIELDS x label
+SIZE 4 2
+TYPE F U
+COUNT 1 2
+WIDTH 2
+HEIGHT 1
+VIEWPOINT 0 0 0 1 0 0 0
+POINTS 2
+DATA ascii
+0 0 1
+0.5 1 2";
+    fs::write(path, text)?;
+
+    // POINTS and WIDTH can hold up to 9 points.
+    let mut writer = DynWriter::append(path)?;
+    for value in 2..9 {
+        writer.push(&record(value))?;
+    }
+    assert_eq!(
+        writer.push(&record(9)).unwrap_err().kind(),
+        ErrorKind::LimitExceeded
+    );
+    writer.finish()?;
+
+    let (meta, _) = PcdMeta::open(path)?;
+    let loaded: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+    assert_eq!((meta.width, meta.num_points), (9, 9));
+    assert_eq!(loaded, (0..9).map(record).collect::<Vec<_>>());
+
+    // The file is refused once it is full.
+    let original = fs::read(path)?;
+    let error = DynWriter::append(path).err().unwrap();
+    let unchanged = fs::read(path)? == original;
+    fs::remove_file(path)?;
+
+    assert_eq!(error.kind(), ErrorKind::LimitExceeded);
+    assert!(unchanged);
+    Ok(())
+}
+
+#[test]
+fn append_organized() -> Result<()> {
+    let path = "test_files/append_organized.pcd";
+    fs::copy("test_files/binary.pcd", path)?;
+
+    let error = DynWriter::append(path).err().unwrap();
+    let unchanged = fs::read(path)? == fs::read("test_files/binary.pcd")?;
+    fs::remove_file(path)?;
+
+    assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+    assert!(unchanged);
+    Ok(())
+}
+
+#[test]
+fn append_refuses_trailing_bytes() -> Result<()> {
+    for (data_kind, path) in [
+        (DataKind::Binary, "test_files/append_trailing.pcd"),
+        (
+            DataKind::BinaryCompressed,
+            "test_files/append_trailing_compressed.pcd",
+        ),
+    ] {
+        let mut writer: DynWriter<_> = WriterInit {
+            width: 2,
+            height: 1,
+            viewpoint: Default::default(),
+            data_kind,
+            schema: Some(schema()),
+        }
+        .create(path)?;
+        writer.push(&record(0))?;
+        writer.push(&record(1))?;
+        writer.finish()?;
+
+        let mut bytes = fs::read(path)?;
+        bytes.extend([0xaa; 5]);
+        fs::write(path, &bytes)?;
+
+        let error = DynWriter::append(path).err().unwrap();
+        let unchanged = fs::read(path)? == bytes;
+        fs::remove_file(path)?;
+
+        assert_eq!(error.kind(), ErrorKind::TrailingData);
+        assert!(unchanged);
+    }
+    Ok(())
+}
+
+#[test]
+fn append_trailing_lines() -> Result<()> {
+    let path = "test_files/append_trailing_lines.pcd";
+    let meta = PcdMeta {
+        width: 2,
+        num_points: 2,
+        field_defs: schema(),
+        ..PcdMeta::open("test_files/ascii.pcd")?.0
+    };
+    let mut header = vec![];
+    meta.write_header(&mut header)?;
+    let header = String::from_utf8(header)?;
+
+    // Lines after the points are refused.
+    let text = format!("{header}0 0 1\n0.5 1 2\n\n1 2\n");
+    fs::write(path, &text)?;
+    let error = DynWriter::append(path).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::TrailingData);
+    assert_eq!(fs::read_to_string(path)?, text);
+
+    // Blank lines are removed.
+    fs::write(path, format!("{header}0 0 1\n0.5 1 2\n\n \n"))?;
+    let mut writer = DynWriter::append(path)?;
+    writer.push(&record(2))?;
+    writer.finish()?;
+
+    let loaded: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+    fs::remove_file(path)?;
+
+    assert_eq!(loaded, (0..3).map(record).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn append_compressed_on_finish() -> Result<()> {
+    let path = "test_files/append_compressed_finish.pcd";
+    let mut writer: DynWriter<_> = WriterInit {
+        width: 1,
+        height: 1,
+        viewpoint: Default::default(),
+        data_kind: DataKind::BinaryCompressed,
+        schema: Some(schema()),
+    }
+    .create(path)?;
+    writer.push(&record(0))?;
+    writer.finish()?;
+
+    // The file is untouched until the writer is finished.
+    let original = fs::read(path)?;
+    let mut writer = DynWriter::append(path)?;
+    writer.push(&record(1))?;
+    writer.push(&record(2))?;
+    let unchanged = fs::read(path)? == original;
+    writer.finish()?;
+
+    let loaded: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+    fs::remove_file(path)?;
+
+    assert!(unchanged);
+    assert_eq!(loaded, (0..3).map(record).collect::<Vec<_>>());
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use eyre::Result;
+use pcd_rs::{
+    AsciiFormat, DataKind, DynReader, DynRecord, DynWriter, Error, Field, NanSpelling, Schema,
+    ValueKind, WriterInit,
+};
+use std::{collections::HashMap, io::Cursor};
+
+fn write_ascii(records: &[DynRecord], ascii_format: AsciiFormat) -> Result<Vec<String>> {
+    let schema = Schema::from_iter([
+        ("x", ValueKind::F32, 2),
+        ("intensity", ValueKind::F64, 1),
+        ("label", ValueKind::U32, 1),
+    ]);
+
+    let mut buffer = vec![];
+    let mut writer: DynWriter<_> = WriterInit {
+        width: records.len() as u64,
+        height: 1,
+        viewpoint: Default::default(),
+        data_kind: DataKind::Ascii,
+        schema: Some(schema),
+    }
+    .with_ascii_format(ascii_format)
+    .build_from_writer(Cursor::new(&mut buffer))?;
+    for record in records {
+        writer.push(record)?;
+    }
+    writer.finish()?;
+
+    let loaded: Vec<DynRecord> = DynReader::from_bytes(&buffer)?.collect::<Result<_, _>>()?;
+    assert_eq!(loaded.len(), records.len());
+
+    let text = String::from_utf8(buffer)?;
+    let data = text.split_once("DATA ascii\n").unwrap().1;
+    Ok(data.lines().map(|line| line.to_owned()).collect())
+}
+
+fn records() -> Vec<DynRecord> {
+    vec![
+        DynRecord(vec![
+            Field::F32(vec![1.23456, -0.5]),
+            Field::F64(vec![1234.5678]),
+            Field::U32(vec![7]),
+        ]),
+        DynRecord(vec![
+            Field::F32(vec![f32::NAN, 0.001]),
+            Field::F64(vec![f64::NAN]),
+            Field::U32(vec![0]),
+        ]),
+    ]
+}
+
+#[test]
+fn ascii_default_format() -> Result<()> {
+    let lines = write_ascii(&records(), AsciiFormat::default())?;
+    assert_eq!(lines, ["1.23456 -0.5 1234.5678 7", "nan 0.001 nan 0"]);
+    Ok(())
+}
+
+#[test]
+fn ascii_fixed_precision() -> Result<()> {
+    let format = AsciiFormat {
+        precision: Some(2),
+        field_precision: HashMap::from([("intensity".to_owned(), 0)]),
+        nan: NanSpelling::Lower,
+        ..Default::default()
+    };
+    let lines = write_ascii(&records(), format)?;
+    assert_eq!(lines, ["1.23 -0.50 1235 7", "nan 0.00 nan 0"]);
+    Ok(())
+}
+
+#[test]
+fn ascii_scientific() -> Result<()> {
+    let format = AsciiFormat {
+        scientific: true,
+        field_precision: HashMap::from([("x".to_owned(), 1)]),
+        nan: NanSpelling::Mixed,
+        ..Default::default()
+    };
+    let lines = write_ascii(&records(), format)?;
+    assert_eq!(lines, ["1.2e0 -5.0e-1 1.2345678e3 7", "NaN 1.0e-3 NaN 0"]);
+    Ok(())
+}
+
+#[test]
+fn ascii_format_unknown_field() -> Result<()> {
+    let format = AsciiFormat {
+        field_precision: HashMap::from([("y".to_owned(), 1)]),
+        ..Default::default()
+    };
+    let init = || WriterInit {
+        width: 1,
+        height: 1,
+        viewpoint: Default::default(),
+        data_kind: DataKind::Ascii,
+        schema: Some(Schema::from_iter([("x", ValueKind::F32, 1)])),
+    };
+
+    // Nothing is written if the format is invalid.
+    let mut buffer = vec![];
+    let result: pcd_rs::Result<DynWriter<_>> = init()
+        .with_ascii_format(format.clone())
+        .build_from_writer(Cursor::new(&mut buffer));
+    assert!(matches!(
+        result.err(),
+        Some(Error::InvalidWriterConfiguration { .. })
+    ));
+    assert!(buffer.is_empty());
+
+    let path = "test_files/ascii_format_unknown_field.pcd";
+    let result: pcd_rs::Result<DynWriter<_>> = init().with_ascii_format(format).create(path);
+    assert!(matches!(
+        result,
+        Err(Error::InvalidWriterConfiguration { .. })
+    ));
+    assert!(!std::path::Path::new(path).exists());
+    Ok(())
+}
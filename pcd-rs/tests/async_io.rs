@@ -0,0 +1,153 @@
+#![cfg(feature = "async")]
+
+use eyre::Result;
+use futures::{StreamExt, TryStreamExt};
+use pcd_rs::{
+    async_io::{DynAsyncReader, DynAsyncWriter},
+    DataKind, DynReader, DynRecord, Error, Field, LimitKind, ReaderLimits, Schema, ValueKind,
+    WriterInit,
+};
+use std::io::Cursor;
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+fn async_read_matches_sync() -> Result<()> {
+    block_on(async {
+        for path in ["test_files/ascii.pcd", "test_files/binary.pcd"] {
+            let expect: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+
+            let reader = DynAsyncReader::open(path).await?;
+            assert_eq!(reader.meta(), DynReader::open(path)?.meta());
+            let records: Vec<DynRecord> = reader.try_collect().await?;
+            assert_eq!(records, expect);
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn async_write_round_trip() -> Result<()> {
+    let schema = Schema::from_iter([("x", ValueKind::F32, 1), ("rgb", ValueKind::U8, 3)]);
+    let records: Vec<DynRecord> = (0..300u16)
+        .map(|index| {
+            DynRecord(vec![
+                Field::F32(vec![index as f32 / 4.0]),
+                Field::U8(vec![index as u8, 1, 2]),
+            ])
+        })
+        .collect();
+
+    block_on(async {
+        for data_kind in [
+            DataKind::Ascii,
+            DataKind::Binary,
+            DataKind::BinaryCompressed,
+        ] {
+            let mut buffer = Cursor::new(vec![]);
+            let mut writer: DynAsyncWriter<_> = WriterInit {
+                width: records.len() as u64,
+                height: 1,
+                viewpoint: Default::default(),
+                data_kind,
+                schema: Some(schema.clone()),
+            }
+            .build_from_async_writer(&mut buffer)
+            .await?;
+
+            for record in &records {
+                writer.push(record).await?;
+            }
+            writer.finish().await?;
+
+            let bytes = buffer.into_inner();
+            let loaded: Vec<DynRecord> =
+                DynReader::from_bytes(&bytes)?.collect::<Result<_, _>>()?;
+            assert_eq!(loaded, records);
+
+            let loaded: Vec<DynRecord> = DynAsyncReader::from_reader(bytes.as_slice())
+                .await?
+                .try_collect()
+                .await?;
+            assert_eq!(loaded, records);
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn async_read_truncated() -> Result<()> {
+    let bytes = std::fs::read("test_files/binary.pcd")?;
+    let truncated = &bytes[..bytes.len() - 5000];
+
+    block_on(async {
+        let results: Vec<_> = DynAsyncReader::from_reader(truncated)
+            .await?
+            .collect()
+            .await;
+        assert!(results.last().unwrap().is_err());
+        Ok(())
+    })
+}
+
+#[test]
+fn async_read_limits() -> Result<()> {
+    block_on(async {
+        let limits = ReaderLimits {
+            max_points: 1000,
+            ..Default::default()
+        };
+        let result = DynAsyncReader::open_with_limits("test_files/binary.pcd", limits).await;
+        assert!(matches!(
+            result,
+            Err(Error::LimitExceededError {
+                kind: LimitKind::Points,
+                ..
+            })
+        ));
+
+        let limits = ReaderLimits {
+            max_line_length: 16,
+            ..Default::default()
+        };
+        let result = DynAsyncReader::open_with_limits("test_files/binary.pcd", limits).await;
+        assert!(matches!(
+            result,
+            Err(Error::LimitExceededError {
+                kind: LimitKind::LineLength,
+                ..
+            })
+        ));
+        Ok(())
+    })
+}
+
+#[test]
+fn async_write_cancelled() -> Result<()> {
+    let schema = Schema::from_iter([("x", ValueKind::F32, 1)]);
+
+    block_on(async {
+        let mut buffer = Cursor::new(vec![]);
+        let mut writer: DynAsyncWriter<_> = WriterInit {
+            width: 1,
+            height: 1,
+            viewpoint: Default::default(),
+            data_kind: DataKind::Binary,
+            schema: Some(schema),
+        }
+        .build_from_async_writer(&mut buffer)
+        .await?;
+        writer.push(&DynRecord(vec![Field::F32(vec![1.0])])).await?;
+
+        // Dropping an unfinished writer, as a cancelled task does, does
+        // not panic.
+        drop(writer);
+        Ok(())
+    })
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "derive")]
+
+use eyre::Result;
+use pcd_rs::{DynRecord, Error, Field, PcdDeserialize, PcdSerialize, Schema, ValueKind};
+
+#[derive(Debug, PcdDeserialize, PcdSerialize, PartialEq)]
+pub struct Point {
+    x: f32,
+    label: [u16; 2],
+    id: i32,
+}
+
+#[test]
+fn encode_decode_typed() -> Result<()> {
+    let schema = Point::write_spec();
+    let point = Point {
+        x: 0.25,
+        label: [3, 65535],
+        id: -9,
+    };
+
+    let mut bytes = vec![];
+    point.encode(&mut bytes, &schema)?;
+    assert_eq!(bytes.len(), schema.point_size());
+    assert_eq!(Point::decode(&bytes, &schema)?, point);
+
+    let mut line = vec![];
+    point.encode_line(&mut line, &schema)?;
+    assert_eq!(line, b"0.25 3 65535 -9\n");
+    assert_eq!(
+        Point::decode_line(std::str::from_utf8(&line)?, &schema)?,
+        point
+    );
+
+    let mut tokens = "1.5 1 2 3 rest".split(' ');
+    let point = Point::decode_tokens(&mut tokens, &schema)?;
+    assert_eq!(point.id, 3);
+    assert_eq!(tokens.next(), Some("rest"));
+
+    Ok(())
+}
+
+#[test]
+fn decode_errors() -> Result<()> {
+    let schema = Point::write_spec();
+
+    match Point::decode_tokens(&mut "1.5 1".split(' '), &schema) {
+        Err(Error::TextTokenMismatchError { expect, found }) => {
+            assert_eq!(expect, 4);
+            assert_eq!(found, 2);
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+    match Point::decode_line("1.5 1 2 3 4\n", &schema) {
+        Err(Error::TextTokenMismatchError { expect, found }) => {
+            assert_eq!(expect, 4);
+            assert_eq!(found, 5);
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+    assert!(Point::decode(&[0; 5], &schema).is_err());
+    assert!(DynRecord::decode(&[0; 5], &schema).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn encode_decode_dynamic() -> Result<()> {
+    let schema = Point::write_spec();
+    let record = DynRecord(vec![
+        Field::F32(vec![-2.0]),
+        Field::U16(vec![7, 8]),
+        Field::I32(vec![100]),
+    ]);
+
+    let mut bytes = vec![];
+    record.encode(&mut bytes, &schema)?;
+    assert_eq!(DynRecord::decode(&bytes, &schema)?, record);
+    assert_eq!(
+        Point::decode(&bytes, &schema)?,
+        Point {
+            x: -2.0,
+            label: [7, 8],
+            id: 100
+        }
+    );
+
+    let mut line = vec![];
+    record.encode_line(&mut line, &schema)?;
+    assert_eq!(line, b"-2 7 8 100\n");
+    assert_eq!(
+        DynRecord::decode_line(std::str::from_utf8(&line)?, &schema)?,
+        record
+    );
+
+    Ok(())
+}
+
+#[test]
+fn ascii_special_floats() -> Result<()> {
+    let schema = Schema::from_iter([
+        ("a", ValueKind::F32, 2),
+        ("b", ValueKind::F64, 3),
+        ("c", ValueKind::F32, 1),
+    ]);
+
+    let record = DynRecord::decode_line("nan -nan NaN inf -inf 1.5\n", &schema)?;
+    let values: Vec<f64> = record
+        .0
+        .iter()
+        .flat_map(|field| field.to_f64_vec())
+        .collect();
+    assert!(values[..3].iter().all(|value| value.is_nan()));
+    assert_eq!(values[3], f64::INFINITY);
+    assert_eq!(values[4], f64::NEG_INFINITY);
+
+    let record = DynRecord(vec![
+        Field::F32(vec![0.1, 16777216.0]),
+        Field::F64(vec![0.1 + 0.2, 1e300, -0.0]),
+        Field::F32(vec![f32::NAN]),
+    ]);
+    let mut line = vec![];
+    record.encode_line(&mut line, &schema)?;
+    assert_eq!(line, b"0.1 16777216 0.30000000000000004 1e300 -0 nan\n");
+
+    let decoded = DynRecord::decode_line(std::str::from_utf8(&line)?, &schema)?;
+    assert_eq!(decoded.0[..2], record.0[..2]);
+
+    Ok(())
+}
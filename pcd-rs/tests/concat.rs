@@ -0,0 +1,235 @@
+use eyre::Result;
+use pcd_rs::{
+    concat::ConcatInit, DataKind, DynReader, DynRecord, DynWriter, ErrorKind, Field, Schema,
+    ValueKind, ViewPoint, WriterInit,
+};
+use std::{f64::consts::FRAC_1_SQRT_2, fs, io::Cursor};
+
+fn schema() -> Schema {
+    Schema::from_iter([
+        ("x", ValueKind::F32, 1),
+        ("y", ValueKind::F32, 1),
+        ("z", ValueKind::F64, 1),
+        ("label", ValueKind::U8, 2),
+    ])
+}
+
+fn record(x: f32, y: f32, z: f64, label: u8) -> DynRecord {
+    DynRecord(vec![
+        Field::F32(vec![x]),
+        Field::F32(vec![y]),
+        Field::F64(vec![z]),
+        Field::U8(vec![label, label + 1]),
+    ])
+}
+
+fn write_input(
+    path: &str,
+    data_kind: DataKind,
+    viewpoint: ViewPoint,
+    records: &[DynRecord],
+) -> Result<()> {
+    let mut writer: DynWriter<_> = WriterInit {
+        width: records.len() as u64,
+        height: 1,
+        viewpoint,
+        data_kind,
+        schema: Some(schema()),
+    }
+    .create(path)?;
+    for record in records {
+        writer.push(record)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+fn concat_bytes(
+    paths: &[&str],
+    data_kind: DataKind,
+    transform_viewpoint: bool,
+) -> pcd_rs::Result<Vec<u8>> {
+    let mut output = Cursor::new(vec![]);
+    pcd_rs::concat(
+        paths,
+        ConcatInit {
+            writer: &mut output,
+            data_kind,
+            ascii_format: Default::default(),
+            transform_viewpoint,
+        },
+    )?;
+    Ok(output.into_inner())
+}
+
+#[test]
+fn concat_data_kinds() -> Result<()> {
+    let records: Vec<_> = (0..10u8)
+        .map(|index| record(index as f32, -(index as f32), index as f64 * 0.25, index))
+        .collect();
+    let paths = [
+        "test_files/concat_kinds_binary.pcd",
+        "test_files/concat_kinds_ascii.pcd",
+        "test_files/concat_kinds_compressed.pcd",
+    ];
+    write_input(
+        paths[0],
+        DataKind::Binary,
+        Default::default(),
+        &records[..3],
+    )?;
+    write_input(
+        paths[1],
+        DataKind::Ascii,
+        Default::default(),
+        &records[3..5],
+    )?;
+    write_input(
+        paths[2],
+        DataKind::BinaryCompressed,
+        Default::default(),
+        &records[5..],
+    )?;
+
+    for data_kind in [
+        DataKind::Binary,
+        DataKind::BinaryCompressed,
+        DataKind::Ascii,
+    ] {
+        let bytes = concat_bytes(&paths, data_kind, false)?;
+        let reader = DynReader::from_bytes(&bytes)?;
+        let meta = reader.meta().clone();
+        let loaded: Vec<DynRecord> = reader.collect::<Result<_, _>>()?;
+
+        assert_eq!(meta.data, data_kind);
+        assert_eq!((meta.width, meta.height, meta.num_points), (10, 1, 10));
+        assert_eq!(loaded, records);
+    }
+
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn concat_schema_mismatch() -> Result<()> {
+    let path = "test_files/concat_mismatch.pcd";
+    write_input(path, DataKind::Binary, Default::default(), &[])?;
+
+    let error =
+        concat_bytes(&[path, "test_files/binary.pcd"], DataKind::Binary, false).unwrap_err();
+    fs::remove_file(path)?;
+
+    assert_eq!(error.kind(), ErrorKind::SchemaMismatch);
+    let pcd_rs::Error::ReaderSchemaMismatchError { diff } = error else {
+        panic!("unexpected error {error}");
+    };
+    assert_eq!(diff.missing.len(), 1);
+    assert_eq!(diff.extra.len(), 1);
+    assert_eq!(diff.kind_mismatches.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn concat_viewpoints() -> Result<()> {
+    let paths = [
+        "test_files/concat_viewpoint_translated.pcd",
+        "test_files/concat_viewpoint_rotated.pcd",
+    ];
+    let point = record(1.0, 0.0, 2.0, 7);
+    write_input(
+        paths[0],
+        DataKind::Binary,
+        ViewPoint {
+            tx: 1.0,
+            ty: 2.0,
+            tz: 3.0,
+            ..Default::default()
+        },
+        std::slice::from_ref(&point),
+    )?;
+    // A quarter turn around the z axis.
+    write_input(
+        paths[1],
+        DataKind::Ascii,
+        ViewPoint {
+            qw: FRAC_1_SQRT_2,
+            qz: FRAC_1_SQRT_2,
+            ..Default::default()
+        },
+        &[point],
+    )?;
+
+    let error = concat_bytes(&paths, DataKind::Binary, false).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+
+    let bytes = concat_bytes(&paths, DataKind::Binary, true)?;
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+
+    let reader = DynReader::from_bytes(&bytes)?;
+    assert_eq!(reader.meta().viewpoint, ViewPoint::default());
+    let loaded: Vec<DynRecord> = reader.collect::<Result<_, _>>()?;
+
+    let expected = [[2.0, 2.0, 5.0], [0.0, 1.0, 2.0]];
+    for (record, expected) in loaded.iter().zip(expected) {
+        let xyz = [0, 1, 2].map(|index| record.0[index].to_f64_vec()[0]);
+        for (value, expected) in xyz.into_iter().zip(expected) {
+            assert!((value - expected).abs() < 1e-6, "{value} != {expected}");
+        }
+        assert_eq!(record.0[3], Field::U8(vec![7, 8]));
+    }
+    Ok(())
+}
+
+#[test]
+fn concat_broken_input() -> Result<()> {
+    let records: Vec<_> = (0..5u8)
+        .map(|index| record(index as f32, 0.0, 0.0, index))
+        .collect();
+    let paths = [
+        "test_files/concat_broken_first.pcd",
+        "test_files/concat_broken_second.pcd",
+    ];
+    write_input(
+        paths[0],
+        DataKind::Binary,
+        Default::default(),
+        &records[..3],
+    )?;
+    write_input(
+        paths[1],
+        DataKind::Binary,
+        Default::default(),
+        &records[3..],
+    )?;
+
+    // Cut the last point of the second input short.
+    let bytes = fs::read(paths[1])?;
+    fs::write(paths[1], &bytes[..bytes.len() - 4])?;
+
+    let mut output = Cursor::new(vec![]);
+    let result = pcd_rs::concat(
+        paths,
+        ConcatInit {
+            writer: &mut output,
+            data_kind: DataKind::BinaryCompressed,
+            ascii_format: Default::default(),
+            transform_viewpoint: false,
+        },
+    );
+    for path in paths {
+        fs::remove_file(path)?;
+    }
+
+    // The input error is returned, and the points before it are kept.
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::Io);
+    let reader = DynReader::from_bytes(output.get_ref())?;
+    let meta = reader.meta().clone();
+    let loaded: Vec<DynRecord> = reader.collect::<Result<_, _>>()?;
+    assert_eq!((meta.width, meta.height, meta.num_points), (3, 1, 3));
+    assert_eq!(loaded, records[..3]);
+    Ok(())
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "derive")]
+
+use eyre::Result;
+use pcd_rs::{DynReader, DynRecord, Error, Field, PcdDeserialize, PcdSerialize, Schema, ValueKind};
+
+#[derive(Debug, PcdDeserialize, PcdSerialize, PartialEq)]
+pub struct Point {
+    #[pcd(rename = "new_x")]
+    x: f32,
+    y: [u8; 3],
+    z: i32,
+}
+
+#[derive(Debug, PcdDeserialize, PartialEq)]
+pub struct Histogram {
+    label: u32,
+    bins: Vec<f32>,
+}
+
+#[derive(Debug, PcdDeserialize, PartialEq)]
+pub struct XyzPoint {
+    z: f32,
+    x: f32,
+}
+
+#[test]
+fn dyn_record_round_trip() -> Result<()> {
+    let point = Point {
+        x: 1.5,
+        y: [2, 1, 7],
+        z: -5,
+    };
+
+    let record = DynRecord::from(&point);
+    assert_eq!(
+        record,
+        DynRecord(vec![
+            Field::F32(vec![1.5]),
+            Field::U8(vec![2, 1, 7]),
+            Field::I32(vec![-5]),
+        ])
+    );
+
+    let converted = Point::try_from(&record)?;
+    assert_eq!(converted, point);
+
+    Ok(())
+}
+
+#[test]
+fn dyn_record_by_name() -> Result<()> {
+    let reader = DynReader::open("test_files/ascii.pcd")?;
+    let schema = reader.meta().field_defs.clone();
+    let records: Vec<DynRecord> = reader.collect::<Result<_, _>>()?;
+
+    let points: Vec<XyzPoint> = records
+        .iter()
+        .map(|record| XyzPoint::try_from((record, &schema)))
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(points.len(), 213);
+    assert_eq!(points[0].x, 0.93773);
+    assert_eq!(points[0].z, 0.0);
+
+    Ok(())
+}
+
+#[test]
+fn dyn_record_vec_field() -> Result<()> {
+    let record = DynRecord(vec![Field::U32(vec![3]), Field::F32(vec![0.5, 1.5, 2.5])]);
+    let histogram = Histogram::try_from(&record)?;
+    assert_eq!(histogram.label, 3);
+    assert_eq!(histogram.bins, vec![0.5, 1.5, 2.5]);
+    Ok(())
+}
+
+#[test]
+fn dyn_record_conversion_errors() {
+    let record = DynRecord(vec![
+        Field::F64(vec![1.5]),
+        Field::U8(vec![2, 1, 7]),
+        Field::I32(vec![-5]),
+    ]);
+    match Point::try_from(&record) {
+        Err(Error::FieldKindMismatchError {
+            field_name,
+            expect,
+            found,
+        }) => {
+            assert_eq!(field_name, "new_x");
+            assert_eq!(expect, ValueKind::F32);
+            assert_eq!(found, ValueKind::F64);
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+
+    let record = DynRecord(vec![
+        Field::F32(vec![1.5]),
+        Field::U8(vec![2, 1]),
+        Field::I32(vec![-5]),
+    ]);
+    match Point::try_from(&record) {
+        Err(Error::FieldSizeMismatchError {
+            field_name,
+            expect,
+            found,
+        }) => {
+            assert_eq!(field_name, "y");
+            assert_eq!(expect, 3);
+            assert_eq!(found, 2);
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+
+    let schema = Schema::from_iter([("x", ValueKind::F32, 1), ("y", ValueKind::F32, 1)]);
+    let record = DynRecord(vec![Field::F32(vec![1.0]), Field::F32(vec![2.0])]);
+    match XyzPoint::try_from((&record, &schema)) {
+        Err(Error::FieldNotFoundError { field_name }) => assert_eq!(field_name, "z"),
+        result => panic!("unexpected result {result:?}"),
+    }
+}
@@ -0,0 +1,158 @@
+use eyre::Result;
+use pcd_rs::{DataKind, DynReader, DynRecord, DynWriter, ErrorKind, PcdMeta, ValueKind, ViewPoint};
+use std::fs;
+
+fn data_section(path: &str) -> Result<Vec<u8>> {
+    let (_, data_offset) = PcdMeta::open(path)?;
+    Ok(fs::read(path)?.split_off(data_offset as usize))
+}
+
+#[test]
+fn edit_in_place() -> Result<()> {
+    let path = "test_files/edit_in_place.pcd";
+    fs::copy("test_files/binary.pcd", path)?;
+    let len = fs::metadata(path)?.len();
+    let points: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+
+    pcd_rs::edit_header(path, |meta| {
+        meta.width = 16;
+        meta.height = 1809;
+        meta.viewpoint.tz = 2.0;
+        meta.field_defs.fields[3].name = "c".into();
+    })?;
+
+    let (meta, _) = PcdMeta::open(path)?;
+    let loaded: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+    let data_unchanged = data_section(path)? == data_section("test_files/binary.pcd")?;
+    let new_len = fs::metadata(path)?.len();
+    fs::remove_file(path)?;
+
+    assert_eq!((meta.width, meta.height), (16, 1809));
+    assert_eq!(meta.viewpoint.tz, 2.0);
+    assert_eq!(meta.field_defs[3].name, "c");
+    assert_eq!(loaded, points);
+    assert!(data_unchanged);
+    // The header is padded instead of moving the data.
+    assert_eq!(new_len, len);
+    Ok(())
+}
+
+#[test]
+fn edit_longer_header() -> Result<()> {
+    // The binary data spans several blocks of the shift.
+    for (input, path) in [
+        ("test_files/ascii.pcd", "test_files/edit_longer_ascii.pcd"),
+        ("test_files/binary.pcd", "test_files/edit_longer_binary.pcd"),
+    ] {
+        fs::copy(input, path)?;
+        let points: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+
+        let viewpoint = ViewPoint {
+            tx: 0.123456789,
+            ty: -98765.4321,
+            tz: 1e-7,
+            qw: 0.6,
+            qx: 0.0,
+            qy: 0.8,
+            qz: 0.0,
+        };
+        pcd_rs::edit_header(path, |meta| {
+            meta.viewpoint = viewpoint.clone();
+            meta.field_defs.fields[0].name = "a_much_longer_field_name".into();
+        })?;
+
+        let (meta, _) = PcdMeta::open(path)?;
+        let loaded: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+        let data_unchanged = data_section(path)? == data_section(input)?;
+        let text = fs::read(path)?;
+        fs::remove_file(path)?;
+
+        assert_eq!(meta.viewpoint, viewpoint);
+        assert_eq!(meta.field_defs[0].name, "a_much_longer_field_name");
+        assert_eq!(loaded, points);
+        assert!(data_unchanged);
+        // The comment line is kept.
+        assert!(text.starts_with(b"# .PCD v"));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn edit_through_symlink() -> Result<()> {
+    let path = "test_files/edit_symlink_target.pcd";
+    let link = "test_files/edit_symlink.pcd";
+    fs::copy("test_files/ascii.pcd", path)?;
+    let _ = fs::remove_file(link);
+    std::os::unix::fs::symlink("edit_symlink_target.pcd", link)?;
+
+    pcd_rs::edit_header(link, |meta| {
+        meta.field_defs.fields[0].name = "a_much_longer_field_name".into();
+    })?;
+
+    let is_symlink = fs::symlink_metadata(link)?.file_type().is_symlink();
+    let (meta, _) = PcdMeta::open(path)?;
+    fs::remove_file(link)?;
+    fs::remove_file(path)?;
+
+    assert!(is_symlink);
+    assert_eq!(meta.field_defs[0].name, "a_much_longer_field_name");
+    Ok(())
+}
+
+#[test]
+fn edit_keeps_append_room() -> Result<()> {
+    let path = "test_files/edit_append.pcd";
+    let text = "\
+# .PCD v.7 - Point Cloud Data file format
+VERSION .7
+FIELDS x
+SIZE 4
+TYPE F
+COUNT 1
+WIDTH 9
+HEIGHT 1
+VIEWPOINT 0.0 0.0 0.0 1.0 0.0 0.0 0.0 # spelled long enough for padding
+POINTS 9
+DATA ascii
+";
+    let mut text = text.to_owned();
+    for index in 0..9 {
+        text += &format!("{index}\n");
+    }
+    fs::write(path, text)?;
+
+    pcd_rs::edit_header(path, |meta| meta.viewpoint.tx = 1.0)?;
+
+    // The padded WIDTH and POINTS can hold more than 9 points.
+    let record = DynRecord(vec![pcd_rs::Field::F32(vec![9.0])]);
+    let mut writer = DynWriter::append(path)?;
+    writer.push(&record)?;
+    writer.finish()?;
+
+    let (meta, _) = PcdMeta::open(path)?;
+    fs::remove_file(path)?;
+    assert_eq!((meta.width, meta.num_points), (10, 10));
+    assert_eq!(meta.viewpoint.tx, 1.0);
+    Ok(())
+}
+
+#[test]
+fn reject_layout_changes() -> Result<()> {
+    let path = "test_files/edit_reject.pcd";
+    fs::copy("test_files/binary.pcd", path)?;
+
+    let data_kind = pcd_rs::edit_header(path, |meta| meta.data = DataKind::Ascii).unwrap_err();
+    let field_kind =
+        pcd_rs::edit_header(path, |meta| meta.field_defs.fields[0].kind = ValueKind::I32)
+            .unwrap_err();
+    let version = pcd_rs::edit_header(path, |meta| meta.version = "0.8".into()).unwrap_err();
+    let unchanged = fs::read(path)? == fs::read("test_files/binary.pcd")?;
+    fs::remove_file(path)?;
+
+    assert_eq!(data_kind.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(field_kind.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(version.kind(), ErrorKind::Parse);
+    assert!(unchanged);
+    Ok(())
+}
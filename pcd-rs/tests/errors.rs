@@ -0,0 +1,105 @@
+use eyre::Result;
+use pcd_rs::{DynReader, Error, ErrorKind};
+use std::io;
+
+const HEADER: &str = "\
+VERSION .7
+FIELDS x y id
+SIZE 4 4 4
+TYPE F F U
+COUNT 1 1 1
+WIDTH 4
+HEIGHT 1
+VIEWPOINT 0 0 0 1 0 0 0
+POINTS 4
+";
+
+fn ascii_pcd(data: &str) -> String {
+    format!("{HEADER}DATA ascii\n{data}")
+}
+
+#[test]
+fn ascii_point_context() -> Result<()> {
+    let text = ascii_pcd("1.5 2.5 0\n3.5 4.5 1\n5.5 oops 2\n7.5 8.5 3\n");
+    let error = DynReader::from_bytes(text.as_bytes())?
+        .collect::<pcd_rs::Result<Vec<_>>>()
+        .unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Parse);
+    assert_eq!(error.point_index(), Some(2));
+    assert_eq!(error.byte_offset(), Some(20));
+    assert_eq!(error.field_name(), Some("y"));
+    assert!(error.to_string().contains("point 2"));
+    Ok(())
+}
+
+#[test]
+fn ascii_token_count() -> Result<()> {
+    let text = ascii_pcd("1.5 2.5 0\n3.5 4.5\n");
+    let error = DynReader::from_bytes(text.as_bytes())?
+        .collect::<pcd_rs::Result<Vec<_>>>()
+        .unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::TokenCount);
+    assert_eq!(error.point_index(), Some(1));
+    assert_eq!(error.byte_offset(), Some(10));
+    assert_eq!(error.field_name(), None);
+    Ok(())
+}
+
+#[test]
+fn header_line_context() {
+    let text = ascii_pcd("").replace("WIDTH 4", "WIDTH four");
+    let error = DynReader::from_bytes(text.as_bytes()).err().unwrap();
+
+    assert_eq!(error.kind(), ErrorKind::Parse);
+    assert_eq!(error.header_line(), Some(6));
+    assert_eq!(error.point_index(), None);
+}
+
+#[test]
+fn binary_point_context() -> Result<()> {
+    let mut bytes = format!("{HEADER}DATA binary\n").into_bytes();
+    bytes.extend([0; 12 * 3 + 5]);
+
+    let mut reader = DynReader::from_bytes(&bytes)?;
+    for _ in 0..3 {
+        reader.next().unwrap()?;
+    }
+    let error = reader.next().unwrap().unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::Io);
+    assert_eq!(error.point_index(), Some(3));
+    assert_eq!(error.byte_offset(), Some(36));
+    assert_eq!(io::Error::from(error).kind(), io::ErrorKind::UnexpectedEof);
+    Ok(())
+}
+
+#[test]
+fn lzf_error() {
+    let mut bytes = format!("{HEADER}DATA binary_compressed\n").into_bytes();
+    bytes.extend(8u32.to_le_bytes());
+    bytes.extend(48u32.to_le_bytes());
+    // A back reference before the start of the output.
+    bytes.extend([0xff; 8]);
+
+    let error = DynReader::from_bytes(&bytes).err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::Lzf);
+    assert!(matches!(error, Error::LzfError { .. }));
+    assert_eq!(io::Error::from(error).kind(), io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn io_error_conversion() {
+    let error = Error::new_invalid_argument_error("bad argument");
+    assert_eq!(io::Error::from(error).kind(), io::ErrorKind::InvalidInput);
+
+    let error = Error::new_truncated_data_error(16, 8);
+    assert_eq!(io::Error::from(error).kind(), io::ErrorKind::UnexpectedEof);
+
+    let error = Error::from(io::Error::from(io::ErrorKind::PermissionDenied));
+    assert_eq!(
+        io::Error::from(error).kind(),
+        io::ErrorKind::PermissionDenied
+    );
+}
@@ -0,0 +1,66 @@
+use pcd_rs::{Error, Field, ValueKind};
+
+#[test]
+fn field_slices() {
+    let mut field = Field::from(vec![1.5f32, 2.5, 3.5]);
+    assert_eq!(field.kind(), ValueKind::F32);
+    assert_eq!(field.as_slice::<f32>(), Some(&[1.5, 2.5, 3.5][..]));
+    assert_eq!(field.as_slice::<f64>(), None);
+
+    field.as_mut_slice::<f32>().unwrap()[1] = -1.0;
+    assert_eq!(field, Field::F32(vec![1.5, -1.0, 3.5]));
+    assert!(field.as_mut_slice::<u8>().is_none());
+
+    assert_eq!(Field::U16(vec![7]).to_value::<u16>(), Some(7));
+    assert_eq!(Field::U16(vec![7, 8]).to_value::<u16>(), None);
+    assert_eq!(Field::zeros(ValueKind::I16, 3), Field::I16(vec![0, 0, 0]));
+    assert_eq!(Field::I32(vec![-3, 4]).to_f64_vec(), vec![-3.0, 4.0]);
+}
+
+#[test]
+fn field_cast() {
+    let field = Field::F64(vec![1.7, -1.7, 255.9]);
+    assert_eq!(
+        field.cast(ValueKind::I16).unwrap(),
+        Field::I16(vec![1, -1, 255])
+    );
+    assert_eq!(
+        field.cast(ValueKind::F32).unwrap(),
+        Field::F32(vec![1.7, -1.7, 255.9])
+    );
+
+    match field.cast(ValueKind::U8) {
+        Err(Error::ValueCastError { value, from, to }) => {
+            assert_eq!(value, "-1.7");
+            assert_eq!(from, ValueKind::F64);
+            assert_eq!(to, ValueKind::U8);
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+    assert!(Field::F64(vec![1e300]).cast(ValueKind::F32).is_err());
+    assert!(Field::F32(vec![f32::NAN]).cast(ValueKind::I32).is_err());
+    assert!(Field::F32(vec![f32::INFINITY])
+        .cast(ValueKind::F64)
+        .unwrap()
+        .as_slice::<f64>()
+        .unwrap()[0]
+        .is_infinite());
+}
+
+#[test]
+fn field_cast_saturating() {
+    let field = Field::F64(vec![-1.7, 300.0, f64::NAN, 1e300, 12.5]);
+    assert_eq!(
+        field.cast_saturating(ValueKind::U8),
+        Field::U8(vec![0, 255, 0, 255, 12])
+    );
+    assert_eq!(
+        Field::I32(vec![-70000, 70000]).cast_saturating(ValueKind::I16),
+        Field::I16(vec![i16::MIN, i16::MAX])
+    );
+
+    let field = field.cast_saturating(ValueKind::F32);
+    let values = field.as_slice::<f32>().unwrap();
+    assert!(values[2].is_nan());
+    assert_eq!(values[3], f32::MAX);
+}
@@ -0,0 +1,115 @@
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+use eyre::Result;
+use pcd_rs::{
+    DataKind, ErrorKind, PcdDeserialize, PcdSerialize, Reader, Schema, ValueKind, WriterInit,
+};
+use std::io::{prelude::*, Cursor};
+
+/// A record implemented by hand against the reader- and writer-based
+/// trait methods only.
+#[derive(Debug, Clone, PartialEq)]
+struct Point {
+    x: f32,
+    label: u16,
+}
+
+impl PcdDeserialize for Point {
+    fn is_dynamic() -> bool {
+        false
+    }
+
+    fn read_spec() -> Vec<(Option<String>, ValueKind, Option<usize>)> {
+        vec![
+            (Some("x".into()), ValueKind::F32, Some(1)),
+            (Some("label".into()), ValueKind::U16, Some(1)),
+        ]
+    }
+
+    fn read_chunk<R: BufRead>(reader: &mut R, _field_defs: &Schema) -> pcd_rs::Result<Self> {
+        Ok(Self {
+            x: reader.read_f32::<LittleEndian>()?,
+            label: reader.read_u16::<LittleEndian>()?,
+        })
+    }
+
+    fn read_line<R: BufRead>(reader: &mut R, _field_defs: &Schema) -> pcd_rs::Result<Self> {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let mut tokens = line.split_ascii_whitespace();
+        let mut next = || {
+            tokens
+                .next()
+                .ok_or_else(|| pcd_rs::Error::new_text_token_mismatch_error(2, 1))
+        };
+        Ok(Self {
+            x: next()?.parse()?,
+            label: next()?.parse()?,
+        })
+    }
+}
+
+impl PcdSerialize for Point {
+    fn is_dynamic() -> bool {
+        false
+    }
+
+    fn write_spec() -> Schema {
+        Schema::from_iter([("x", ValueKind::F32, 1), ("label", ValueKind::U16, 1)])
+    }
+
+    fn write_chunk<R: Write>(&self, writer: &mut R, _spec: &Schema) -> pcd_rs::Result<()> {
+        writer.write_f32::<LittleEndian>(self.x)?;
+        writer.write_u16::<LittleEndian>(self.label)?;
+        Ok(())
+    }
+
+    fn write_line<R: Write>(&self, writer: &mut R, _spec: &Schema) -> pcd_rs::Result<()> {
+        writeln!(writer, "{} {}", self.x, self.label)?;
+        Ok(())
+    }
+}
+
+#[test]
+fn legacy_impl_round_trip() -> Result<()> {
+    let points: Vec<_> = (0..5u16)
+        .map(|index| Point {
+            x: index as f32 * 0.5,
+            label: index * 3,
+        })
+        .collect();
+
+    for data_kind in [
+        DataKind::Ascii,
+        DataKind::Binary,
+        DataKind::BinaryCompressed,
+    ] {
+        let mut buffer = vec![];
+        let mut writer = WriterInit {
+            width: points.len() as u64,
+            height: 1,
+            viewpoint: Default::default(),
+            data_kind,
+            schema: None,
+        }
+        .build_from_writer(Cursor::new(&mut buffer))?;
+        for point in &points {
+            writer.push(point)?;
+        }
+        writer.finish()?;
+
+        let reader = Reader::<Point, _>::from_bytes(&buffer)?;
+        assert_eq!(reader.meta().field_defs, Point::write_spec());
+        let loaded: Vec<Point> = reader.collect::<Result<_, _>>()?;
+        assert_eq!(loaded, points);
+    }
+    Ok(())
+}
+
+#[test]
+fn legacy_impl_schema_mismatch() -> Result<()> {
+    let Err(error) = Reader::<Point, _>::open("test_files/binary.pcd") else {
+        panic!("the schema must not match");
+    };
+    assert_eq!(error.kind(), ErrorKind::SchemaMismatch);
+    Ok(())
+}
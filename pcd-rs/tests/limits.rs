@@ -0,0 +1,111 @@
+use eyre::Result;
+use pcd_rs::{DynReader, DynRecord, Error, LimitKind, ReaderLimits};
+
+fn limit_error<T>(result: pcd_rs::Result<T>) -> (LimitKind, u64, u64) {
+    let Err(error) = result else {
+        panic!("the limit is not checked");
+    };
+    match *error.root() {
+        Error::LimitExceededError { kind, limit, found } => (kind, limit, found),
+        _ => panic!("unexpected error {error:?}"),
+    }
+}
+
+#[test]
+fn header_limits() -> Result<()> {
+    let path = "test_files/binary.pcd";
+    assert!(DynReader::open_with_limits(path, ReaderLimits::default()).is_ok());
+
+    let limits = ReaderLimits {
+        max_points: 1000,
+        ..Default::default()
+    };
+    assert_eq!(
+        limit_error(DynReader::open_with_limits(path, limits)),
+        (LimitKind::Points, 1000, 28944)
+    );
+
+    let limits = ReaderLimits {
+        max_fields: 3,
+        ..Default::default()
+    };
+    assert_eq!(
+        limit_error(DynReader::open_with_limits(path, limits)),
+        (LimitKind::Fields, 3, 4)
+    );
+
+    let limits = ReaderLimits {
+        max_header_lines: 5,
+        ..Default::default()
+    };
+    assert_eq!(
+        limit_error(DynReader::open_with_limits(path, limits)),
+        (LimitKind::HeaderLines, 5, 6)
+    );
+
+    let limits = ReaderLimits {
+        max_line_length: 16,
+        ..Default::default()
+    };
+    assert_eq!(
+        limit_error(DynReader::open_with_limits(path, limits)),
+        (LimitKind::LineLength, 16, 17)
+    );
+
+    Ok(())
+}
+
+#[test]
+fn data_limits() -> Result<()> {
+    let header = "\
+VERSION .7
+FIELDS x
+SIZE 4
+TYPE F
+COUNT 1
+WIDTH 2
+HEIGHT 1
+VIEWPOINT 0 0 0 1 0 0 0
+POINTS 2
+";
+    let limits = ReaderLimits {
+        max_decompressed_bytes: 1 << 20,
+        max_line_length: 24,
+        ..Default::default()
+    };
+
+    // The sizes are checked before the data is allocated.
+    let mut bytes = format!("{header}DATA binary_compressed\n").into_bytes();
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+    assert_eq!(
+        limit_error(DynReader::from_reader_with_limits(bytes.as_slice(), limits)),
+        (LimitKind::DecompressedBytes, 1 << 20, u32::MAX as u64)
+    );
+
+    let huge_point = header.replace("COUNT 1", "COUNT 1000000000");
+    let bytes = format!("{huge_point}DATA binary\n");
+    assert_eq!(
+        limit_error(DynReader::from_reader_with_limits(bytes.as_bytes(), limits)),
+        (LimitKind::DecompressedBytes, 1 << 20, 4_000_000_000)
+    );
+
+    // A point whose size overflows is rejected even without limits.
+    let overflow = header.replace("COUNT 1", "COUNT 4611686018427387904");
+    let bytes = format!("{overflow}DATA binary\n");
+    let Err(error) = DynReader::from_reader(bytes.as_bytes()) else {
+        panic!("the point size overflow is not checked");
+    };
+    assert!(matches!(error.root(), Error::ParseError { .. }));
+
+    let long_line = format!("{header}DATA ascii\n1.5\n{}1\n", " ".repeat(30));
+    let mut reader = DynReader::from_reader_with_limits(long_line.as_bytes(), limits)?;
+    let first: DynRecord = reader.next().unwrap()?;
+    assert_eq!(first.0[0].to_value::<f32>(), Some(1.5));
+    assert_eq!(
+        limit_error(reader.next().unwrap()),
+        (LimitKind::LineLength, 24, 25)
+    );
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use eyre::Result;
+use pcd_rs::{DataKind, DynReader, ErrorKind, PcdMeta, ValueKind, ViewPoint};
+use std::{
+    fs,
+    io::{BufReader, Cursor, Read},
+};
+
+#[test]
+fn open_header_only() -> Result<()> {
+    let (meta, data_offset) = PcdMeta::open("test_files/binary.pcd")?;
+    assert_eq!(meta.version, "0.7");
+    assert_eq!(meta.data, DataKind::Binary);
+    assert_eq!((meta.width, meta.height), (1809, 16));
+    assert_eq!(meta.num_points, 28944);
+    assert_eq!(meta.point_size(), 16);
+
+    // The offset points right after the DATA line.
+    let bytes = fs::read("test_files/binary.pcd")?;
+    assert!(bytes[..data_offset as usize].ends_with(b"DATA binary\n"));
+
+    let reader = DynReader::open("test_files/binary.pcd")?;
+    assert_eq!(reader.meta(), &meta);
+    Ok(())
+}
+
+#[test]
+fn read_from_stops_after_data() -> Result<()> {
+    let file = fs::File::open("test_files/single_compressed.pcd")?;
+    let mut reader = BufReader::new(file);
+    let (meta, data_offset) = PcdMeta::read_from(&mut reader)?;
+    assert_eq!(meta.data, DataKind::BinaryCompressed);
+
+    // The rest of the reader is the data section.
+    let mut data = vec![];
+    reader.read_to_end(&mut data)?;
+    let bytes = fs::read("test_files/single_compressed.pcd")?;
+    assert_eq!(&bytes[data_offset as usize..], data.as_slice());
+    Ok(())
+}
+
+#[test]
+fn header_round_trip() -> Result<()> {
+    for path in [
+        "test_files/ascii.pcd",
+        "test_files/binary.pcd",
+        "test_files/single_compressed.pcd",
+        "test_files/test_v05_ascii.pcd",
+        "test_files/test_v06_binary.pcd",
+    ] {
+        let (meta, _) = PcdMeta::open(path)?;
+
+        let mut header = vec![];
+        meta.write_header(&mut header)?;
+        let (parsed, data_offset) = PcdMeta::read_from(Cursor::new(&header))?;
+        assert_eq!(parsed, meta, "{path}");
+        assert_eq!(data_offset, header.len() as u64);
+
+        let parsed: PcdMeta = meta.to_string().parse()?;
+        assert_eq!(parsed, meta, "{path}");
+    }
+    Ok(())
+}
+
+#[test]
+fn header_display() -> Result<()> {
+    let meta = PcdMeta {
+        version: "0.7".into(),
+        width: 2,
+        height: 1,
+        viewpoint: ViewPoint {
+            tx: 1.5,
+            ..Default::default()
+        },
+        num_points: 2,
+        data: DataKind::BinaryCompressed,
+        field_defs: [("x", ValueKind::F32, 1), ("label", ValueKind::U16, 2)]
+            .into_iter()
+            .collect(),
+    };
+
+    assert_eq!(
+        meta.to_string(),
+        "\
+VERSION 0.7
+FIELDS x label
+SIZE 4 2
+TYPE F U
+COUNT 1 2
+WIDTH 2
+HEIGHT 1
+VIEWPOINT 1.5 0 0 1 0 0 0
+POINTS 2
+DATA binary_compressed
+"
+    );
+    assert_eq!(meta.point_size(), 8);
+
+    let error = format!("{meta}1 2\n").parse::<PcdMeta>().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+
+    let error = "VERSION 0.7\nFIELDS x\n".parse::<PcdMeta>().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Parse);
+    Ok(())
+}
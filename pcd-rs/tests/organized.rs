@@ -0,0 +1,199 @@
+use eyre::Result;
+use pcd_rs::{
+    DataKind, DynReader, DynRecord, DynWriter, ErrorKind, Field, LimitKind, OrganizedCloud, Schema,
+    ValueKind, WriterInit,
+};
+use std::io::Cursor;
+
+const WIDTH: u64 = 4;
+const HEIGHT: u64 = 3;
+
+fn record(row: u64, col: u64) -> DynRecord {
+    // The point at row 1, column 2 has no measurement.
+    let z = if (row, col) == (1, 2) { f32::NAN } else { 1.0 };
+    DynRecord(vec![
+        Field::F32(vec![col as f32]),
+        Field::F32(vec![row as f32]),
+        Field::F32(vec![z]),
+        Field::U8(vec![(row * WIDTH + col) as u8]),
+    ])
+}
+
+fn organized_writer(
+    data_kind: DataKind,
+    buffer: &mut Vec<u8>,
+) -> Result<DynWriter<Cursor<&mut Vec<u8>>>> {
+    let mut writer = WriterInit {
+        width: WIDTH,
+        height: HEIGHT,
+        viewpoint: Default::default(),
+        data_kind,
+        schema: Some(Schema::from_iter([
+            ("x", ValueKind::F32, 1),
+            ("y", ValueKind::F32, 1),
+            ("z", ValueKind::F32, 1),
+            ("label", ValueKind::U8, 1),
+        ])),
+    }
+    .build_from_writer(Cursor::new(buffer))?;
+    writer.set_organized()?;
+    Ok(writer)
+}
+
+#[test]
+fn organized_round_trip() -> Result<()> {
+    for data_kind in [
+        DataKind::Ascii,
+        DataKind::Binary,
+        DataKind::BinaryCompressed,
+    ] {
+        let mut buffer = vec![];
+        let mut writer = organized_writer(data_kind, &mut buffer)?;
+        for row in 0..HEIGHT {
+            let points: Vec<_> = (0..WIDTH).map(|col| record(row, col)).collect();
+            writer.push_row(&points)?;
+        }
+        writer.finish()?;
+
+        let cloud = OrganizedCloud::from_reader(DynReader::from_bytes(&buffer)?)?;
+        assert_eq!((cloud.width(), cloud.height()), (4, 3));
+        assert_eq!(cloud.get(2, 3).unwrap().0[3], Field::U8(vec![11]));
+        assert!(cloud.get(3, 0).is_none());
+        assert!(cloud.get(0, 4).is_none());
+
+        // The NaN point stays in place, but it is invalid.
+        assert!(cloud.get(1, 2).is_some());
+        assert!(cloud.get_valid(1, 2).is_none());
+        assert!(!cloud.is_valid(1, 2));
+        assert!(cloud.is_valid(1, 1));
+
+        let labels: Vec<Vec<u8>> = cloud
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|point| point.0[3].to_value().unwrap())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(labels, [[0, 1, 2, 3], [4, 5, 6, 7], [8, 9, 10, 11]]);
+    }
+    Ok(())
+}
+
+#[test]
+fn organized_windows() -> Result<()> {
+    let points = (0..HEIGHT * WIDTH)
+        .map(|index| record(index / WIDTH, index % WIDTH))
+        .collect();
+    let mut cloud = OrganizedCloud::new(WIDTH as usize, HEIGHT as usize, points)?;
+    // Points created by OrganizedCloud::new() are valid until marked.
+    assert!(cloud.is_valid(1, 2));
+    assert!(cloud.set_valid(1, 2, false));
+    assert!(!cloud.set_valid(3, 0, false));
+
+    let positions = |row, col, radius| -> Vec<(usize, usize)> {
+        cloud
+            .window(row, col, radius)
+            .map(|(row, col, _)| (row, col))
+            .collect()
+    };
+    assert_eq!(positions(0, 0, 1), [(0, 0), (0, 1), (1, 0), (1, 1)]);
+    assert_eq!(
+        positions(1, 2, 1),
+        [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (1, 1),
+            (1, 3),
+            (2, 1),
+            (2, 2),
+            (2, 3)
+        ]
+    );
+    assert_eq!(positions(2, 3, 0), [(2, 3)]);
+    assert_eq!(positions(1, 2, 0), []);
+    assert_eq!(positions(1, 1, 9).len(), 11);
+    assert_eq!(positions(1, 1, usize::MAX).len(), 11);
+
+    let error = OrganizedCloud::new(5, 2, vec![0u8; 9]).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Corrupt);
+    Ok(())
+}
+
+#[test]
+fn organized_point_counts() -> Result<()> {
+    // Too few points
+    let mut buffer = vec![];
+    let mut writer = organized_writer(DataKind::Binary, &mut buffer)?;
+    writer.push(&record(0, 0))?;
+    let error = writer.finish().unwrap_err();
+    let pcd_rs::Error::PointCountMismatchError {
+        width,
+        height,
+        points,
+    } = error
+    else {
+        panic!("unexpected error {error}");
+    };
+    assert_eq!((width, height, points), (4, 3, 1));
+    // The output is patched to an unorganized point cloud.
+    let reader = DynReader::from_bytes(&buffer)?;
+    let meta = reader.meta().clone();
+    assert_eq!((meta.width, meta.height, meta.num_points), (1, 1, 1));
+    assert_eq!(reader.count(), 1);
+
+    // Too many points
+    let mut buffer = vec![];
+    let mut writer = organized_writer(DataKind::Ascii, &mut buffer)?;
+    for index in 0..HEIGHT * WIDTH {
+        writer.push(&record(index / WIDTH, index % WIDTH))?;
+    }
+    let error = writer.push(&record(0, 0)).unwrap_err();
+    assert!(matches!(
+        error,
+        pcd_rs::Error::LimitExceededError {
+            kind: LimitKind::Points,
+            limit: 12,
+            found: 13
+        }
+    ));
+    writer.finish()?;
+
+    // Misaligned rows
+    let mut buffer = vec![];
+    let mut writer = organized_writer(DataKind::Binary, &mut buffer)?;
+    let row: Vec<_> = (0..WIDTH).map(|col| record(0, col)).collect();
+    let short = writer.push_row(&row[..3]).unwrap_err();
+    writer.push(&row[0])?;
+    let misaligned = writer.push_row(&row).unwrap_err();
+    assert_eq!(short.kind(), ErrorKind::InvalidArgument);
+    assert_eq!(misaligned.kind(), ErrorKind::InvalidArgument);
+    assert!(writer.finish().is_err());
+    let meta = DynReader::from_bytes(&buffer)?.meta().clone();
+    assert_eq!((meta.width, meta.height, meta.num_points), (1, 1, 1));
+    Ok(())
+}
+
+#[test]
+fn organized_huge_points() -> Result<()> {
+    let mut bytes = b"\
+VERSION .7
+FIELDS x y z
+SIZE 4 4 4
+TYPE F F F
+COUNT 1 1 1
+WIDTH 1000000000000000
+HEIGHT 1
+VIEWPOINT 0 0 0 1 0 0 0
+POINTS 1000000000000000
+DATA binary
+"
+    .to_vec();
+    bytes.extend_from_slice(&[0; 24]);
+
+    // The points are not allocated by the POINTS of the header.
+    let result = OrganizedCloud::from_reader(DynReader::from_bytes(&bytes)?);
+    assert!(result.is_err());
+    Ok(())
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "parallel")]
+
+use eyre::Result;
+use pcd_rs::{
+    parallel::ParallelOptions, DataKind, DynReader, DynRecord, DynWriter, Field, WriterInit,
+};
+use std::io::Cursor;
+
+#[test]
+fn par_collect_matches_sequential() -> Result<()> {
+    let options = ParallelOptions {
+        num_threads: Some(2),
+        chunk_points: 3,
+    };
+
+    for path in ["test_files/ascii.pcd", "test_files/binary.pcd"] {
+        let expect: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+        let records = DynReader::open(path)?.par_collect(&options)?;
+        assert_eq!(records, expect);
+
+        // Continue after a few sequentially read points.
+        let mut reader = DynReader::open(path)?;
+        let head: Vec<DynRecord> = reader.by_ref().take(2).collect::<Result<_, _>>()?;
+        let tail = reader.par_collect(&options)?;
+        assert_eq!([head, tail].concat(), expect);
+        assert!(reader.next().is_none());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn par_collect_columns_compressed() -> Result<()> {
+    let mut reader = DynReader::open("test_files/binary.pcd")?;
+    let schema = reader.meta().field_defs.clone();
+    let records: Vec<DynRecord> = reader.by_ref().collect::<Result<_, _>>()?;
+
+    let mut buffer = vec![];
+    let mut writer: DynWriter<_> = WriterInit {
+        width: records.len() as u64,
+        height: 1,
+        viewpoint: Default::default(),
+        data_kind: DataKind::BinaryCompressed,
+        schema: Some(schema),
+    }
+    .build_from_writer(Cursor::new(&mut buffer))?;
+    for record in &records {
+        writer.push(record)?;
+    }
+    writer.finish()?;
+
+    let cloud = DynReader::from_bytes(&buffer)?.par_collect_columns(&ParallelOptions {
+        num_threads: None,
+        chunk_points: 7,
+    })?;
+    assert_eq!(cloud.len(), records.len());
+
+    let xs: Vec<f32> = records
+        .iter()
+        .map(|record| record.0[0].to_value().unwrap())
+        .collect();
+    assert_eq!(cloud.column("x"), Some(&Field::F32(xs)));
+    assert!(cloud.column("w").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn par_collect_columns_ascii() -> Result<()> {
+    let text = "\
+VERSION .7
+FIELDS x label
+SIZE 4 2
+TYPE F U
+COUNT 1 2
+WIDTH 3
+HEIGHT 1
+VIEWPOINT 0 0 0 1 0 0 0
+POINTS 3
+DATA ascii
+0.5 1 2
+-1 3 4
+2 5 6";
+
+    let cloud = DynReader::from_bytes(text.as_bytes())?.par_collect_columns(&ParallelOptions {
+        num_threads: Some(1),
+        chunk_points: 2,
+    })?;
+    assert_eq!(
+        cloud.columns(),
+        [
+            Field::F32(vec![0.5, -1.0, 2.0]),
+            Field::U16(vec![1, 2, 3, 4, 5, 6]),
+        ]
+    );
+
+    let truncated = &text[..text.len() - 6];
+    assert!(DynReader::from_bytes(truncated.as_bytes())?
+        .par_collect(&ParallelOptions::default())
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn par_collect_huge_points() -> Result<()> {
+    let mut bytes = b"\
+VERSION .7
+FIELDS x y
+SIZE 4 4
+TYPE F F
+COUNT 1 1
+WIDTH 4611686018427387904
+HEIGHT 1
+VIEWPOINT 0 0 0 1 0 0 0
+POINTS 4611686018427387904
+DATA binary
+"
+    .to_vec();
+    bytes.extend_from_slice(&[0; 16]);
+
+    // The data size of POINTS overflows.
+    let options = ParallelOptions::default();
+    let error = DynReader::from_bytes(&bytes)?
+        .par_collect(&options)
+        .unwrap_err();
+    assert_eq!(error.kind(), pcd_rs::ErrorKind::Truncated);
+    Ok(())
+}
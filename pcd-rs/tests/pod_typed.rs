@@ -0,0 +1,142 @@
+#![cfg(feature = "derive")]
+
+use eyre::Result;
+use itertools::Itertools as _;
+use pcd_rs::{DataKind, PcdDeserialize, PcdSerialize, Reader, WriterInit};
+use std::io::Cursor;
+
+#[derive(Debug, Clone, Copy, PcdDeserialize, PcdSerialize, PartialEq)]
+#[pcd(pod)]
+#[repr(C)]
+pub struct PodPoint {
+    x: f32,
+    y: f32,
+    z: f32,
+    rgb: u32,
+}
+
+#[derive(Debug, Clone, Copy, PcdDeserialize, PcdSerialize, PartialEq)]
+#[pcd(pod)]
+#[repr(C, align(16))]
+pub struct AlignedPoint {
+    x: f32,
+    y: f32,
+    z: f32,
+    rgb: u32,
+}
+
+#[derive(Debug, PcdDeserialize, PartialEq)]
+pub struct PlainPoint {
+    x: f32,
+    y: f32,
+    z: f32,
+    rgb: u32,
+}
+
+#[derive(Debug, Clone, Copy, PcdDeserialize, PcdSerialize, PartialEq)]
+#[pcd(pod)]
+#[repr(C)]
+pub struct PodArrayPoint {
+    normal: [f32; 3],
+    label: [u16; 2],
+}
+
+#[test]
+fn read_batch_pod() -> Result<()> {
+    let expect: Vec<PlainPoint> = Reader::open("test_files/binary.pcd")?.try_collect()?;
+
+    let mut reader = Reader::<PodPoint, _>::open("test_files/binary.pcd")?;
+    let mut points = vec![];
+    loop {
+        let batch = reader.read_batch(1000)?;
+        if batch.is_empty() {
+            break;
+        }
+        points.extend(batch);
+    }
+
+    assert_eq!(points.len(), expect.len());
+    assert!(points.iter().zip(&expect).all(|(lhs, rhs)| {
+        lhs.x.to_bits() == rhs.x.to_bits()
+            && lhs.y.to_bits() == rhs.y.to_bits()
+            && lhs.z.to_bits() == rhs.z.to_bits()
+            && lhs.rgb == rhs.rgb
+    }));
+    assert!(reader.next().is_none());
+
+    // The alignment of repr(C) does not disable the pod path.
+    let mut reader = Reader::<AlignedPoint, _>::open("test_files/binary.pcd")?;
+    let aligned = reader.read_batch(usize::MAX)?;
+    assert_eq!(aligned.len(), points.len());
+    assert!(aligned.iter().zip(&points).all(|(lhs, rhs)| {
+        lhs.x.to_bits() == rhs.x.to_bits()
+            && lhs.y.to_bits() == rhs.y.to_bits()
+            && lhs.z.to_bits() == rhs.z.to_bits()
+            && lhs.rgb == rhs.rgb
+    }));
+
+    Ok(())
+}
+
+#[test]
+fn push_batch_pod() -> Result<()> {
+    let points: Vec<_> = (0..100)
+        .map(|idx| PodArrayPoint {
+            normal: [idx as f32, -(idx as f32), 0.5],
+            label: [idx as u16, 7],
+        })
+        .collect();
+
+    for data_kind in [
+        DataKind::Binary,
+        DataKind::BinaryCompressed,
+        DataKind::Ascii,
+    ] {
+        let mut buffer = vec![];
+        let mut writer = WriterInit {
+            width: points.len() as u64,
+            height: 1,
+            viewpoint: Default::default(),
+            data_kind,
+            schema: None,
+        }
+        .build_from_writer(Cursor::new(&mut buffer))?;
+        writer.push_batch(&points[..40])?;
+        writer.push_batch(&points[40..])?;
+        writer.finish()?;
+
+        let mut reader = Reader::<PodArrayPoint, _>::from_bytes(&buffer)?;
+        let mut loaded = reader.read_batch(30)?;
+        let rest = reader.by_ref().collect::<Result<Vec<_>, _>>()?;
+        loaded.extend(rest);
+
+        assert_eq!(loaded, points);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn read_batch_huge_points() -> Result<()> {
+    let mut bytes = b"\
+VERSION .7
+FIELDS x y z rgb
+SIZE 4 4 4 4
+TYPE F F F U
+COUNT 1 1 1 1
+WIDTH 1000000000000000
+HEIGHT 1
+VIEWPOINT 0 0 0 1 0 0 0
+POINTS 1000000000000000
+DATA binary
+"
+    .to_vec();
+    bytes.extend_from_slice(&[0; 20]);
+
+    // The batch is not allocated by the POINTS of the header.
+    let mut reader = Reader::<PodPoint, _>::from_bytes(&bytes)?;
+    let error = reader.read_batch(usize::MAX).unwrap_err();
+    assert_eq!(error.kind(), pcd_rs::ErrorKind::Truncated);
+    assert!(reader.read_batch(usize::MAX)?.is_empty());
+    Ok(())
+}
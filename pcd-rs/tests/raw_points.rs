@@ -0,0 +1,142 @@
+use eyre::Result;
+use pcd_rs::{DataKind, DynReader, DynRecord, DynWriter, Error, Schema, ValueKind, WriterInit};
+use std::io::Cursor;
+
+#[test]
+fn raw_points_match_records() -> Result<()> {
+    for path in ["test_files/ascii.pcd", "test_files/binary.pcd"] {
+        let records: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+
+        let mut reader = DynReader::open(path)?;
+        let mut points = reader.raw_points();
+        let mut count = 0;
+
+        while let Some(point) = points.next() {
+            let point = point?;
+            let record = &records[count];
+            assert_eq!(&point.to_record()?, record);
+            assert_eq!(
+                point.get_by_name::<f32>("x")?,
+                record.0[0].to_value::<f32>().unwrap()
+            );
+            count += 1;
+        }
+
+        assert_eq!(count, records.len());
+        assert!(points.next().is_none());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn raw_point_accessors() -> Result<()> {
+    let mut reader = DynReader::open("test_files/binary.pcd")?;
+    let mut points = reader.raw_points();
+    let point = points.next().unwrap()?;
+
+    assert_eq!(point.bytes().len(), 16);
+    assert_eq!(point.field_bytes(3).unwrap().len(), 4);
+    assert!(point.field_bytes(4).is_none());
+    assert_eq!(point.get::<f32>(0)?, point.get_element::<f32>(0, 0)?);
+
+    match point.get::<u32>(0) {
+        Err(Error::FieldKindMismatchError {
+            field_name,
+            expect,
+            found,
+        }) => {
+            assert_eq!(field_name, "x");
+            assert_eq!(expect, ValueKind::U32);
+            assert_eq!(found, ValueKind::F32);
+        }
+        result => panic!("unexpected result {result:?}"),
+    }
+    assert!(matches!(
+        point.get_by_name::<f32>("w"),
+        Err(Error::FieldNotFoundError { .. })
+    ));
+    assert!(point.get::<f32>(4).is_err());
+    assert!(point.get_element::<f32>(0, 1).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn push_raw_filtered() -> Result<()> {
+    let mut reader = DynReader::open("test_files/binary.pcd")?;
+    let schema = reader.meta().field_defs.clone();
+    let expect: Vec<DynRecord> = DynReader::open("test_files/binary.pcd")?
+        .filter_map(|record| {
+            let record = record.unwrap();
+            (record.0[0].to_value::<f32>().unwrap() > 0.0).then_some(record)
+        })
+        .collect();
+
+    let data_kinds = [
+        DataKind::Ascii,
+        DataKind::Binary,
+        DataKind::BinaryCompressed,
+    ];
+    let mut buffers = vec![vec![]; data_kinds.len()];
+    let mut writers: Vec<DynWriter<_>> = buffers
+        .iter_mut()
+        .zip(data_kinds)
+        .map(|(buffer, data_kind)| {
+            WriterInit {
+                width: 0,
+                height: 1,
+                viewpoint: Default::default(),
+                data_kind,
+                schema: Some(schema.clone()),
+            }
+            .build_from_writer(Cursor::new(buffer))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut points = reader.raw_points();
+    while let Some(point) = points.next() {
+        let point = point?;
+        if point.get::<f32>(0)? > 0.0 {
+            for writer in &mut writers {
+                writer.push_raw(&point)?;
+            }
+        }
+    }
+
+    for writer in writers {
+        writer.finish()?;
+    }
+
+    for buffer in &buffers {
+        let records: Vec<DynRecord> = DynReader::from_bytes(buffer)?.collect::<Result<_, _>>()?;
+        assert_eq!(records, expect);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn push_raw_schema_mismatch() -> Result<()> {
+    let mut reader = DynReader::open("test_files/binary.pcd")?;
+    let mut points = reader.raw_points();
+    let point = points.next().unwrap()?;
+
+    let schema = Schema::from_iter([("x", ValueKind::F32, 1), ("y", ValueKind::F32, 1)]);
+    let mut writer: DynWriter<_> = WriterInit {
+        width: 1,
+        height: 1,
+        viewpoint: Default::default(),
+        data_kind: DataKind::Binary,
+        schema: Some(schema),
+    }
+    .build_from_writer(Cursor::new(vec![]))?;
+
+    assert!(matches!(
+        writer.push_raw(&point),
+        Err(Error::WriterSchemaMismatchError { .. })
+    ));
+    writer.finish()?;
+
+    Ok(())
+}
@@ -2,7 +2,7 @@
 
 use eyre::Result;
 use itertools::Itertools as _;
-use pcd_rs::{PcdDeserialize, Reader};
+use pcd_rs::{Error, PcdDeserialize, Reader};
 
 #[derive(PcdDeserialize)]
 pub struct PointAscii {
@@ -37,3 +37,41 @@ fn load_binary_typed() -> Result<()> {
     assert_eq!(points.len(), 28944);
     Ok(())
 }
+
+#[test]
+fn static_fields_ignored() -> Result<()> {
+    use pcd_rs::{Schema, StaticFieldDef, ValueKind};
+
+    assert_eq!(
+        PointAscii::FIELDS[0],
+        StaticFieldDef::new(None, ValueKind::F32, Some(1))
+    );
+    assert_eq!(Schema::from(PointAscii::FIELDS)[0].name, "unknown_field_0");
+    assert_eq!(
+        PointBinary::FIELDS[3],
+        StaticFieldDef::new(None, ValueKind::U32, Some(1))
+    );
+    Ok(())
+}
+
+#[derive(Debug, PcdDeserialize)]
+pub struct PointMismatch {
+    pub x: f32,
+    pub z: f64,
+    pub y: f32,
+}
+
+#[test]
+fn read_schema_mismatch() {
+    let Err(Error::ReaderSchemaMismatchError { diff }) =
+        Reader::<PointMismatch, _>::open("test_files/ascii.pcd")
+    else {
+        panic!("the schema mismatch is not detected");
+    };
+
+    assert!(diff.missing.is_empty());
+    assert_eq!(diff.extra.len(), 1);
+    assert_eq!(diff.extra[0].field.name.as_deref(), Some("rgb"));
+    assert_eq!(diff.kind_mismatches.len(), 1);
+    assert_eq!(diff.moved.len(), 2);
+}
@@ -0,0 +1,75 @@
+use eyre::Result;
+use pcd_rs::{DynReader, DynRecord, Error, Field, Schema, ValueKind};
+
+#[test]
+fn record_view_by_name() -> Result<()> {
+    let schema = Schema::from_iter([
+        ("intensity", ValueKind::U8, 1),
+        ("x", ValueKind::F32, 1),
+        ("y", ValueKind::F32, 1),
+        ("z", ValueKind::F32, 1),
+        ("normal", ValueKind::F32, 3),
+        ("label", ValueKind::U32, 1),
+    ]);
+    let mut record = DynRecord(vec![
+        Field::U8(vec![200]),
+        Field::F32(vec![1.0]),
+        Field::F32(vec![2.0]),
+        Field::F32(vec![3.0]),
+        Field::F32(vec![0.0, 0.0, 1.0]),
+        Field::U32(vec![0]),
+    ]);
+
+    let view = record.view(&schema);
+    assert_eq!(view.get::<u8>("intensity")?, 200);
+    assert_eq!(view.xyz::<f32>()?, [1.0, 2.0, 3.0]);
+    assert_eq!(view.get_slice::<f32>("normal")?, &[0.0, 0.0, 1.0]);
+    assert!(matches!(
+        view.get::<f32>("normal"),
+        Err(Error::FieldSizeMismatchError { .. })
+    ));
+    assert!(matches!(
+        view.get::<f64>("x"),
+        Err(Error::FieldKindMismatchError { .. })
+    ));
+    assert!(matches!(
+        view.get::<f32>("w"),
+        Err(Error::FieldNotFoundError { .. })
+    ));
+
+    let mut view = record.view_mut(&schema);
+    view.set("label", 3u32)?;
+    view.set_slice("normal", &[1.0f32, 0.0, 0.0])?;
+    assert!(matches!(
+        view.set("label", 3i32),
+        Err(Error::FieldKindMismatchError { .. })
+    ));
+    assert!(matches!(
+        view.set_slice("normal", &[1.0f32]),
+        Err(Error::FieldSizeMismatchError { .. })
+    ));
+
+    assert_eq!(record.0[4], Field::F32(vec![1.0, 0.0, 0.0]));
+    assert_eq!(record.0[5], Field::U32(vec![3]));
+
+    Ok(())
+}
+
+#[test]
+fn reader_views() -> Result<()> {
+    let mut reader = DynReader::open("test_files/binary.pcd")?;
+    let mut count = 0;
+
+    for view in reader.views() {
+        let view = view?;
+        let [x, y, z] = view.xyz::<f32>()?;
+        assert_eq!(view.record().to_xyz::<f32>(), Some([x, y, z]));
+        view.get::<u32>("rgb")?;
+        count += 1;
+    }
+
+    assert_eq!(count, 28944);
+    assert!(reader.next().is_none());
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use eyre::Result;
+use pcd_rs::{
+    DataKind, DynReader, DynRecord, DynWriter, Error, ErrorKind, Field, ReaderInit,
+    RecoveryOptions, Schema, ValueKind, WriterInit,
+};
+use std::io::{self, Cursor};
+
+fn write_points(data_kind: DataKind, count: usize) -> Result<Vec<u8>> {
+    let schema = Schema::from_iter([("x", ValueKind::F32, 1), ("id", ValueKind::U32, 1)]);
+    let mut buffer = vec![];
+    let mut writer: DynWriter<_> = WriterInit {
+        width: count as u64,
+        height: 1,
+        viewpoint: Default::default(),
+        data_kind,
+        schema: Some(schema),
+    }
+    .build_from_writer(Cursor::new(&mut buffer))?;
+    for index in 0..count {
+        writer.push(&DynRecord(vec![
+            Field::F32(vec![index as f32 + 0.5]),
+            Field::U32(vec![index as u32]),
+        ]))?;
+    }
+    writer.finish()?;
+    Ok(buffer)
+}
+
+fn ids(records: &[DynRecord]) -> Vec<u32> {
+    records
+        .iter()
+        .map(|record| record.0[1].to_value().unwrap())
+        .collect()
+}
+
+#[test]
+fn recover_complete_file() -> Result<()> {
+    for data_kind in [
+        DataKind::Ascii,
+        DataKind::Binary,
+        DataKind::BinaryCompressed,
+    ] {
+        let bytes = write_points(data_kind, 10)?;
+        let (records, report) = DynReader::from_bytes(&bytes)?.recover(Default::default());
+        assert_eq!(ids(&records), (0..10).collect::<Vec<_>>());
+        assert_eq!(report.last_good_index, Some(9));
+        assert!(report.cause.is_none());
+        assert!(report.skipped_lines.is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn recover_truncated_binary() -> Result<()> {
+    let bytes = write_points(DataKind::Binary, 10)?;
+    let truncated = &bytes[..bytes.len() - 20];
+
+    let (records, report) = DynReader::from_bytes(truncated)?.recover(Default::default());
+    assert_eq!(ids(&records), (0..7).collect::<Vec<_>>());
+    assert_eq!(report.last_good_index, Some(6));
+    assert_eq!(report.byte_offset, 56);
+    let cause = report.cause.unwrap();
+    assert_eq!(cause.point_index(), Some(7));
+    match cause.root() {
+        Error::IoError(error) => assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof),
+        root => panic!("unexpected cause {root:?}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn recover_malformed_ascii() -> Result<()> {
+    let bytes = write_points(DataKind::Ascii, 6)?;
+    let text = String::from_utf8(bytes)?;
+    let (header, data) = text.split_at(text.find("DATA ascii\n").unwrap() + 11);
+    let mut lines: Vec<&str> = data.lines().collect();
+    lines[2] = "2.5";
+    let corrupted = format!("{header}{}\n5.5", lines[..5].join("\n"));
+
+    let (records, report) =
+        DynReader::from_bytes(corrupted.as_bytes())?.recover(Default::default());
+    assert_eq!(ids(&records), [0, 1]);
+    assert_eq!(report.last_good_index, Some(1));
+    assert_eq!(report.byte_offset, 12);
+    assert_eq!(report.cause.unwrap().kind(), ErrorKind::TokenCount);
+
+    let options = RecoveryOptions {
+        skip_malformed_lines: true,
+    };
+    let (records, report) = DynReader::from_bytes(corrupted.as_bytes())?.recover(options);
+    assert_eq!(ids(&records), [0, 1, 3, 4]);
+    assert_eq!(report.last_good_index, Some(4));
+    assert!(report.cause.is_none());
+
+    let skipped: Vec<_> = report
+        .skipped_lines
+        .iter()
+        .map(|line| (line.index, line.byte_offset))
+        .collect();
+    assert_eq!(skipped, [(2, 12), (5, 28)]);
+    Ok(())
+}
+
+#[test]
+fn recover_truncated_compressed() -> Result<()> {
+    let bytes = write_points(DataKind::BinaryCompressed, 10)?;
+    let truncated = &bytes[..bytes.len() - 10];
+
+    // Other readers fail on decompression.
+    assert!(DynReader::from_bytes(truncated).is_err());
+
+    let reader: DynReader<_> = ReaderInit::default().build_for_recovery(truncated)?;
+    let (records, report) = reader.recover(Default::default());
+    let num_records = records.len();
+    assert!(num_records > 0 && num_records < 10);
+    assert_eq!(ids(&records), (0..num_records as u32).collect::<Vec<_>>());
+    assert_eq!(report.last_good_index, Some(num_records - 1));
+    assert_eq!(report.byte_offset, num_records as u64 * 8);
+    let cause = report.cause.unwrap();
+    assert_eq!(cause.point_index(), Some(num_records));
+    assert_eq!(cause.kind(), ErrorKind::Lzf);
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use eyre::Result;
+use pcd_rs::{
+    DataKind, DynRecord, DynWriter, Error, ErrorKind, Field, Schema, ValueKind, WriterInit,
+};
+use std::io::Cursor;
+
+#[test]
+fn schema_builder() -> Result<()> {
+    let schema = Schema::builder()
+        .field::<f32>("x")
+        .field::<f32>("y")
+        .field::<f32>("z")
+        .array::<f32, 33>("fpfh")
+        .field_def("label", ValueKind::U32, 1)
+        .build()?;
+
+    assert_eq!(
+        schema,
+        Schema::from_iter([
+            ("x", ValueKind::F32, 1),
+            ("y", ValueKind::F32, 1),
+            ("z", ValueKind::F32, 1),
+            ("fpfh", ValueKind::F32, 33),
+            ("label", ValueKind::U32, 1),
+        ])
+    );
+
+    let result = Schema::builder()
+        .field::<f32>("x")
+        .field::<f32>("x")
+        .build();
+    assert!(matches!(result, Err(Error::InvalidArgumentError { .. })));
+
+    let result = Schema::builder().field::<f32>("bad name").build();
+    assert!(matches!(result, Err(Error::InvalidArgumentError { .. })));
+
+    let result = Schema::builder().array::<f32, 0>("empty").build();
+    assert!(matches!(result, Err(Error::InvalidArgumentError { .. })));
+
+    Ok(())
+}
+
+#[test]
+fn schema_operations() -> Result<()> {
+    let schema = Schema::builder()
+        .field::<u8>("intensity")
+        .field::<f32>("x")
+        .field::<f32>("y")
+        .field::<f32>("z")
+        .array::<f64, 2>("stamp")
+        .build()?;
+
+    assert_eq!(
+        schema.find("x").map(|field| field.kind),
+        Some(ValueKind::F32)
+    );
+    assert_eq!(schema.index_of("z"), Some(3));
+    assert_eq!(schema.index_of("w"), None);
+    assert_eq!(schema.point_size(), 29);
+    assert_eq!(schema.offsets(), vec![0, 1, 5, 9, 13]);
+
+    let xyz = schema.select(&["x", "y", "z"])?;
+    assert_eq!(xyz.len(), 3);
+    assert_eq!(xyz, schema.without(&["intensity", "stamp"])?);
+    assert!(matches!(
+        schema.select(&["w"]),
+        Err(Error::FieldNotFoundError { .. })
+    ));
+    assert_eq!(
+        schema.select(&["x", "y", "x"]).unwrap_err().kind(),
+        ErrorKind::InvalidArgument
+    );
+
+    let renamed = schema.rename("intensity", "i")?;
+    assert_eq!(renamed.index_of("i"), Some(0));
+    assert!(renamed.is_compatible_with(&schema));
+    assert!(!xyz.is_compatible_with(&schema));
+    assert!(schema.rename("x", "y").is_err());
+
+    let union = xyz.union(&schema)?;
+    assert_eq!(union.index_of("intensity"), Some(3));
+    assert_eq!(union.len(), schema.len());
+
+    let conflict = Schema::builder().field::<u16>("intensity").build()?;
+    assert!(schema.union(&conflict).is_err());
+
+    assert_eq!(
+        schema.to_string(),
+        "FIELDS intensity x y z stamp\n\
+         SIZE 1 4 4 4 8\n\
+         TYPE U F F F F\n\
+         COUNT 1 1 1 1 2\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn schema_diff() {
+    let expected = Schema::from_iter([
+        ("x", ValueKind::F32, 1),
+        ("y", ValueKind::F32, 1),
+        ("z", ValueKind::F32, 1),
+        ("rgb", ValueKind::F32, 1),
+    ]);
+    let found = Schema::from_iter([
+        ("x", ValueKind::F32, 1),
+        ("z", ValueKind::F64, 1),
+        ("y", ValueKind::F32, 1),
+        ("normal", ValueKind::F32, 3),
+    ]);
+
+    let diff = expected.diff(&found);
+    assert!(!diff.is_empty());
+    assert_eq!(
+        diff.missing
+            .iter()
+            .map(|entry| (entry.position, entry.field.name.as_deref()))
+            .collect::<Vec<_>>(),
+        [(3, Some("rgb"))]
+    );
+    assert_eq!(
+        diff.extra
+            .iter()
+            .map(|entry| (entry.position, entry.field.name.as_deref()))
+            .collect::<Vec<_>>(),
+        [(3, Some("normal"))]
+    );
+    assert_eq!(diff.kind_mismatches.len(), 1);
+    assert_eq!(diff.kind_mismatches[0].found.kind, ValueKind::F64);
+    assert!(diff.count_mismatches.is_empty());
+    assert_eq!(
+        diff.moved
+            .iter()
+            .map(|pair| (pair.expected_position, pair.found_position))
+            .collect::<Vec<_>>(),
+        [(1, 2), (2, 1)]
+    );
+
+    assert_eq!(
+        diff.to_string(),
+        r#"missing "rgb" (F32 x 1) at position 3; extra "normal" (F32 x 3) at position 3; field "z" at position 1 expects kind F32, but found F64; field "y" at position 2 is expected at position 1; field "z" at position 1 is expected at position 2"#
+    );
+    assert!(expected.diff(&expected).is_empty());
+}
+
+#[test]
+fn dyn_writer_schema_mismatch() -> Result<()> {
+    let schema = Schema::from_iter([("x", ValueKind::F32, 1), ("label", ValueKind::U32, 1)]);
+    let record = DynRecord(vec![Field::F32(vec![1.0]), Field::U8(vec![1, 2])]);
+
+    for data_kind in [DataKind::Binary, DataKind::Ascii] {
+        let mut writer: DynWriter<_> = WriterInit {
+            width: 1,
+            height: 1,
+            viewpoint: Default::default(),
+            data_kind,
+            schema: Some(schema.clone()),
+        }
+        .build_from_writer(Cursor::new(vec![]))?;
+
+        let Err(Error::WriterSchemaMismatchError { diff }) = writer.push(&record) else {
+            panic!("the schema mismatch is not detected");
+        };
+        assert_eq!(diff.kind_mismatches.len(), 1);
+        assert_eq!(diff.count_mismatches.len(), 1);
+        assert_eq!(diff.count_mismatches[0].found.count, Some(2));
+        assert_eq!(
+            diff.to_string(),
+            r#"field "label" at position 1 expects kind U32, but found U8; field "label" at position 1 expects count 1, but found 2"#
+        );
+
+        writer.push(&DynRecord(vec![Field::F32(vec![1.0]), Field::U32(vec![1])]))?;
+        writer.finish()?;
+    }
+    Ok(())
+}
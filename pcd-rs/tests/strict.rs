@@ -0,0 +1,183 @@
+use eyre::Result;
+use pcd_rs::{
+    DataKind, DynReader, DynRecord, DynWriter, Error, ReaderInit, Schema, ValueKind, WriterInit,
+};
+use std::io::Cursor;
+
+fn strict() -> ReaderInit {
+    ReaderInit {
+        strict: true,
+        ..Default::default()
+    }
+}
+
+fn write_points(data_kind: DataKind, width: u64, count: usize) -> Result<Vec<u8>> {
+    let schema = Schema::from_iter([("x", ValueKind::F32, 1), ("y", ValueKind::F32, 1)]);
+    let mut buffer = vec![];
+    let mut writer: DynWriter<_> = WriterInit {
+        width,
+        height: 1,
+        viewpoint: Default::default(),
+        data_kind,
+        schema: Some(schema),
+    }
+    .build_from_writer(Cursor::new(&mut buffer))?;
+    for index in 0..count {
+        writer.push(&DynRecord(vec![
+            pcd_rs::Field::F32(vec![index as f32]),
+            pcd_rs::Field::F32(vec![-(index as f32)]),
+        ]))?;
+    }
+    writer.finish()?;
+    Ok(buffer)
+}
+
+fn open_strict(bytes: &[u8]) -> pcd_rs::Result<DynReader<Cursor<&[u8]>>> {
+    strict().build_from_seekable_reader(Cursor::new(bytes))
+}
+
+#[test]
+fn strict_accepts_consistent_files() -> Result<()> {
+    for data_kind in [
+        DataKind::Ascii,
+        DataKind::Binary,
+        DataKind::BinaryCompressed,
+    ] {
+        let bytes = write_points(data_kind, 10, 10)?;
+        let records: Vec<DynRecord> = open_strict(&bytes)?.collect::<Result<_, _>>()?;
+        assert_eq!(records.len(), 10);
+    }
+    Ok(())
+}
+
+#[test]
+fn strict_binary_length() -> Result<()> {
+    let bytes = write_points(DataKind::Binary, 10, 10)?;
+
+    match open_strict(&bytes[..bytes.len() - 3]) {
+        Err(Error::TruncatedDataError { expected, actual }) => {
+            assert_eq!(expected, 80);
+            assert_eq!(actual, 77);
+        }
+        result => panic!("unexpected result {:?}", result.err()),
+    }
+
+    let mut padded = bytes.clone();
+    padded.extend_from_slice(&[0; 5]);
+    assert!(matches!(
+        open_strict(&padded),
+        Err(Error::TrailingDataError { trailing: 5 })
+    ));
+
+    // The lenient reader accepts the trailing bytes.
+    let records: Vec<DynRecord> = DynReader::from_bytes(&padded)?.collect::<Result<_, _>>()?;
+    assert_eq!(records.len(), 10);
+
+    assert!(matches!(
+        strict().open::<DynRecord, _>("test_files/binary.pcd"),
+        Err(Error::TrailingDataError { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn strict_compressed_length() -> Result<()> {
+    let mut bytes = write_points(DataKind::BinaryCompressed, 10, 10)?;
+    bytes.push(0);
+    assert!(matches!(
+        open_strict(&bytes),
+        Err(Error::TrailingDataError { trailing: 1 })
+    ));
+    Ok(())
+}
+
+#[test]
+fn strict_point_count() -> Result<()> {
+    let bytes = write_points(DataKind::Ascii, 4, 10)?;
+    match open_strict(&bytes) {
+        Err(Error::PointCountMismatchError {
+            width,
+            height,
+            points,
+        }) => assert_eq!((width, height, points), (4, 1, 10)),
+        result => panic!("unexpected result {:?}", result.err()),
+    }
+    assert!(DynReader::from_bytes(&bytes).is_ok());
+    Ok(())
+}
+
+#[test]
+fn strict_ascii_trailing_data() -> Result<()> {
+    let bytes = write_points(DataKind::Ascii, 3, 3)?;
+
+    // Whitespace after the points is accepted.
+    let mut blank = bytes.clone();
+    blank.extend_from_slice(b"\n  \n");
+    let records: Vec<DynRecord> = open_strict(&blank)?.collect::<Result<_, _>>()?;
+    assert_eq!(records.len(), 3);
+
+    let mut extra = bytes.clone();
+    extra.extend_from_slice(b"3 -3\n");
+    let results: Vec<_> = open_strict(&extra)?.collect();
+    assert_eq!(results.len(), 4);
+    assert!(matches!(
+        results.last(),
+        Some(Err(Error::TrailingDataError { trailing: 5 }))
+    ));
+
+    let mut reader = open_strict(&extra)?;
+    assert_eq!(reader.read_batch(10)?.len(), 3);
+    assert!(matches!(
+        reader.read_batch(10),
+        Err(Error::TrailingDataError { trailing: 5 })
+    ));
+
+    // The lenient reader ignores the extra line.
+    let records: Vec<DynRecord> = DynReader::from_bytes(&extra)?.collect::<Result<_, _>>()?;
+    assert_eq!(records.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn strict_unseekable_length() -> Result<()> {
+    for data_kind in [DataKind::Binary, DataKind::BinaryCompressed] {
+        let mut bytes = write_points(data_kind, 10, 10)?;
+        bytes.extend_from_slice(&[0; 5]);
+
+        // The length is checked after the last point.
+        let reader: DynReader<_> = strict().build_from_reader(bytes.as_slice())?;
+        let results: Vec<_> = reader.collect();
+        assert_eq!(results.len(), 11);
+        assert!(matches!(
+            results.last(),
+            Some(Err(Error::TrailingDataError { trailing: 5 }))
+        ));
+    }
+
+    let bytes = write_points(DataKind::Binary, 10, 10)?;
+    let reader: DynReader<_> = strict().build_from_reader(&bytes[..bytes.len() - 3])?;
+    let results: Vec<_> = reader.collect();
+    assert_eq!(results.len(), 10);
+    assert!(results.last().unwrap().is_err());
+    Ok(())
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn strict_parallel_trailing_data() -> Result<()> {
+    let options = pcd_rs::parallel::ParallelOptions::default();
+    for (data_kind, extra) in [
+        (DataKind::Ascii, &b"3 -3\n"[..]),
+        (DataKind::Binary, &[0; 5][..]),
+    ] {
+        let mut bytes = write_points(data_kind, 3, 3)?;
+        bytes.extend_from_slice(extra);
+        let mut reader: DynReader<_> = strict().build_from_reader(bytes.as_slice())?;
+        assert!(matches!(
+            reader.par_collect(&options),
+            Err(Error::TrailingDataError { trailing: 5 })
+        ));
+    }
+    Ok(())
+}
@@ -1,6 +1,6 @@
 //! Tests for binary_compressed PCD format support
 
-use pcd_rs::{DataKind, DynReader, DynRecord, DynWriter, Field, Schema, ValueKind, WriterInit};
+use pcd_rs::{DataKind, DynReader, DynRecord, Field, Schema, ValueKind, WriterInit};
 use std::io::Cursor;
 
 #[test]
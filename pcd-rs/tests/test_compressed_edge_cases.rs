@@ -6,8 +6,6 @@ use std::{fs, io::Write};
 #[test]
 fn test_empty_point_cloud() -> pcd_rs::Result<()> {
     // Test writing and reading an empty compressed point cloud
-    let points: Vec<DynRecord> = vec![];
-
     let schema = Schema::from_iter([
         ("x", ValueKind::F32, 1),
         ("y", ValueKind::F32, 1),
@@ -17,7 +15,7 @@ fn test_empty_point_cloud() -> pcd_rs::Result<()> {
     let path = "test_files/empty_compressed.pcd";
 
     {
-        let mut writer = WriterInit {
+        let writer = WriterInit {
             width: 0,
             height: 1,
             viewpoint: Default::default(),
@@ -171,8 +169,8 @@ fn test_mixed_data_types() -> pcd_rs::Result<()> {
             Field::I32(vec![-42]),
         ]),
         DynRecord(vec![
-            Field::F32(vec![3.14]),
-            Field::F64(vec![2.718]),
+            Field::F32(vec![3.25]),
+            Field::F64(vec![2.5]),
             Field::U8(vec![128]),
             Field::I32(vec![12345]),
         ]),
@@ -1,6 +1,6 @@
 //! Tests for legacy PCD version support (0.5 and 0.6)
 
-use pcd_rs::{DataKind, DynReader, DynRecord, Field, Schema, ValueKind, WriterInit};
+use pcd_rs::{DataKind, DynReader, DynRecord, Field};
 use std::{fs, io::Write};
 
 #[test]
@@ -0,0 +1,196 @@
+use eyre::Result;
+use pcd_rs::{
+    DataKind, DynReader, DynRecord, DynWriter, Error, Field, Schema, ValueKind, WriterInit,
+};
+use std::{
+    collections::HashSet,
+    io::{self, Cursor, Seek, SeekFrom, Write},
+    sync::{Arc, Mutex},
+    thread::{self, ThreadId},
+};
+
+#[test]
+fn prefetching_matches_sequential() -> Result<()> {
+    for path in ["test_files/ascii.pcd", "test_files/binary.pcd"] {
+        let expect: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+
+        let prefetching = DynReader::open(path)?.prefetching(2);
+        assert_eq!(prefetching.meta().num_points as usize, expect.len());
+        let records: Vec<DynRecord> = prefetching.collect::<Result<_, _>>()?;
+        assert_eq!(records, expect);
+
+        // Dropping early stops the worker.
+        let mut prefetching = DynReader::open(path)?.prefetching(0);
+        assert_eq!(prefetching.next().transpose()?.as_ref(), expect.first());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn prefetching_error() -> Result<()> {
+    let bytes = std::fs::read("test_files/binary.pcd")?;
+    let truncated = bytes[..bytes.len() - 5000].to_vec();
+
+    let results: Vec<_> = DynReader::from_reader(Cursor::new(truncated))?
+        .prefetching(1)
+        .collect();
+    assert!(results.last().unwrap().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn threaded_writer_round_trip() -> Result<()> {
+    let schema = Schema::from_iter([("x", ValueKind::F32, 1), ("id", ValueKind::U32, 1)]);
+    let records: Vec<DynRecord> = (0..5000)
+        .map(|index| {
+            DynRecord(vec![
+                Field::F32(vec![index as f32 * 0.5]),
+                Field::U32(vec![index]),
+            ])
+        })
+        .collect();
+
+    for (data_kind, path) in [
+        (DataKind::Ascii, "test_files/threaded_ascii.pcd"),
+        (DataKind::Binary, "test_files/threaded_binary.pcd"),
+        (
+            DataKind::BinaryCompressed,
+            "test_files/threaded_compressed.pcd",
+        ),
+    ] {
+        let mut writer = WriterInit {
+            width: records.len() as u64,
+            height: 1,
+            viewpoint: Default::default(),
+            data_kind,
+            schema: Some(schema.clone()),
+        }
+        .create::<DynRecord, _>(path)?
+        .threaded();
+
+        for record in &records {
+            writer.push(record)?;
+        }
+        writer.finish()?;
+
+        let loaded: Vec<DynRecord> = DynReader::open(path)?.collect::<Result<_, _>>()?;
+        std::fs::remove_file(path)?;
+        assert_eq!(loaded, records);
+    }
+
+    Ok(())
+}
+
+/// Fails on writing after the limit is reached.
+struct LimitedWriter {
+    inner: Cursor<Vec<u8>>,
+    limit: u64,
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.inner.position() + buf.len() as u64 > self.limit {
+            return Err(io::Error::new(io::ErrorKind::StorageFull, "full"));
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for LimitedWriter {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn threaded_writer_error() -> Result<()> {
+    let schema = Schema::from_iter([("x", ValueKind::F64, 1)]);
+    let writer: DynWriter<_> = WriterInit {
+        width: 0,
+        height: 1,
+        viewpoint: Default::default(),
+        data_kind: DataKind::Binary,
+        schema: Some(schema),
+    }
+    .build_from_writer(LimitedWriter {
+        inner: Cursor::new(vec![]),
+        limit: 4096,
+    })?;
+
+    let mut writer = writer.threaded();
+    let mut result = Ok(());
+    for index in 0..100_000 {
+        result = writer.push_owned(DynRecord(vec![Field::F64(vec![index as f64])]));
+        if result.is_err() {
+            break;
+        }
+    }
+    let result = result.and_then(|()| writer.finish());
+
+    match result {
+        Err(Error::IoError(error)) => assert_eq!(error.kind(), io::ErrorKind::StorageFull),
+        result => panic!("unexpected result {result:?}"),
+    }
+
+    Ok(())
+}
+
+/// Records the threads writing to it.
+struct ThreadRecorder {
+    inner: Cursor<Vec<u8>>,
+    threads: Arc<Mutex<HashSet<ThreadId>>>,
+}
+
+impl Write for ThreadRecorder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.threads.lock().unwrap().insert(thread::current().id());
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for ThreadRecorder {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn threaded_writer_finish() -> Result<()> {
+    let threads = Arc::new(Mutex::new(HashSet::new()));
+    let writer: DynWriter<_> = WriterInit {
+        width: 3,
+        height: 1,
+        viewpoint: Default::default(),
+        data_kind: DataKind::BinaryCompressed,
+        schema: Some(Schema::from_iter([("x", ValueKind::F64, 1)])),
+    }
+    .build_from_writer(ThreadRecorder {
+        inner: Cursor::new(vec![]),
+        threads: threads.clone(),
+    })?;
+    // The header is written on the calling thread.
+    threads.lock().unwrap().clear();
+
+    let mut writer = writer.threaded();
+    for index in 0..3 {
+        writer.push_owned(DynRecord(vec![Field::F64(vec![index as f64])]))?;
+    }
+    writer.finish()?;
+
+    // The data is compressed and written on finish, which runs on the
+    // background thread.
+    let threads = threads.lock().unwrap();
+    assert!(!threads.is_empty());
+    assert!(!threads.contains(&thread::current().id()));
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use eyre::Result;
+use pcd_rs::{transcode::TranscodeInit, DataKind, DynReader, DynRecord, ErrorKind, PcdMeta};
+use std::io::Cursor;
+
+fn transcode_bytes(
+    input: &[u8],
+    data_kind: DataKind,
+    upgrade_version: bool,
+) -> pcd_rs::Result<Vec<u8>> {
+    let mut output = Cursor::new(vec![]);
+    pcd_rs::transcode(
+        DynReader::from_bytes(input)?,
+        TranscodeInit {
+            writer: &mut output,
+            data_kind,
+            ascii_format: Default::default(),
+            upgrade_version,
+        },
+    )?;
+    Ok(output.into_inner())
+}
+
+fn load(bytes: &[u8]) -> Result<(PcdMeta, Vec<DynRecord>)> {
+    let reader = DynReader::from_bytes(bytes)?;
+    let meta = reader.meta().clone();
+    let records = reader.collect::<Result<_, _>>()?;
+    Ok((meta, records))
+}
+
+#[test]
+fn transcode_between_kinds() -> Result<()> {
+    let text = "\
+VERSION .7
+FIELDS x y label
+SIZE 4 8 2
+TYPE F F U
+COUNT 1 1 2
+WIDTH 2
+HEIGHT 2
+VIEWPOINT 1.5 -2 3 0.6 0 0.8 0
+POINTS 4
+DATA ascii
+0.1 1e-300 1 2
+-0 0.30000000000000004 3 4
+3.4028235e38 -7.25 5 6
+1e-45 123456789.125 7 65535
+";
+    let (meta, records) = load(text.as_bytes())?;
+
+    let mut bytes = text.as_bytes().to_vec();
+    for data_kind in [
+        DataKind::Binary,
+        DataKind::BinaryCompressed,
+        DataKind::Ascii,
+        DataKind::Binary,
+    ] {
+        bytes = transcode_bytes(&bytes, data_kind, false)?;
+        let (new_meta, new_records) = load(&bytes)?;
+
+        assert_eq!(
+            new_meta,
+            PcdMeta {
+                data: data_kind,
+                ..meta.clone()
+            }
+        );
+        assert_eq!(new_records, records);
+    }
+    Ok(())
+}
+
+#[test]
+fn transcode_binary_bytes() -> Result<()> {
+    let input = std::fs::read("test_files/binary.pcd")?;
+    let (meta, data_offset) = PcdMeta::read_from(input.as_slice())?;
+    let data_len = meta.num_points as usize * meta.point_size();
+
+    let ascii = transcode_bytes(&input, DataKind::Ascii, false)?;
+    let binary = transcode_bytes(&ascii, DataKind::Binary, false)?;
+    let (_, new_offset) = PcdMeta::read_from(binary.as_slice())?;
+
+    // The points survive an ASCII round trip bit by bit.
+    let data = &input[data_offset as usize..][..data_len];
+    assert_eq!(&binary[new_offset as usize..], data);
+    Ok(())
+}
+
+#[test]
+fn transcode_legacy_version() -> Result<()> {
+    let input = std::fs::read("test_files/test_v05_ascii.pcd")?;
+    let (_, records) = load(&input)?;
+
+    let binary = transcode_bytes(&input, DataKind::Binary, false)?;
+    let (meta, loaded) = load(&binary)?;
+    assert_eq!(meta.version, "0.5");
+    assert_eq!(loaded, records);
+
+    let error = transcode_bytes(&input, DataKind::BinaryCompressed, false).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidArgument);
+
+    let compressed = transcode_bytes(&input, DataKind::BinaryCompressed, true)?;
+    let (meta, loaded) = load(&compressed)?;
+    assert_eq!(meta.version, "0.7");
+    assert_eq!(meta.data, DataKind::BinaryCompressed);
+    assert_eq!(loaded, records);
+    Ok(())
+}
+
+#[test]
+fn transcode_broken_input() -> Result<()> {
+    let input = std::fs::read("test_files/binary.pcd")?;
+    let (meta, data_offset) = PcdMeta::read_from(input.as_slice())?;
+    // Keep 20000 points and a part of the next one.
+    let truncated = &input[..data_offset as usize + 20000 * meta.point_size() + 1];
+
+    let mut output = Cursor::new(vec![]);
+    let error = pcd_rs::transcode(
+        DynReader::from_bytes(truncated)?,
+        TranscodeInit {
+            writer: &mut output,
+            data_kind: DataKind::Ascii,
+            ascii_format: Default::default(),
+            upgrade_version: false,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(error.point_index(), Some(20000));
+
+    // The points before the error are kept as an unorganized cloud.
+    let (new_meta, records) = load(output.get_ref())?;
+    let (_, expected) = load(&input)?;
+    assert_eq!(
+        (new_meta.width, new_meta.height, new_meta.num_points),
+        (20000, 1, 20000)
+    );
+    assert_eq!(records, expected[..20000]);
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use eyre::Result;
+use pcd_rs::{
+    validate::{Check, Severity},
+    ValidationReport,
+};
+
+fn checks(report: &ValidationReport, severity: Severity) -> Vec<Check> {
+    report
+        .findings
+        .iter()
+        .filter(|finding| finding.severity == severity)
+        .map(|finding| finding.check)
+        .collect()
+}
+
+const HEADER: &str = "\
+VERSION .7
+FIELDS x y label
+SIZE 4 4 4
+TYPE F F U
+COUNT 1 1 1
+WIDTH 3
+HEIGHT 1
+VIEWPOINT 0 0 0 1 0 0 0
+POINTS 3
+";
+
+#[test]
+fn validate_test_files() -> Result<()> {
+    for path in [
+        "test_files/ascii.pcd",
+        "test_files/binary.pcd",
+        "test_files/single_compressed.pcd",
+        "test_files/test_v05_ascii.pcd",
+    ] {
+        let report = pcd_rs::validate(path)?;
+        assert!(report.is_valid(), "{path}: {:?}", report.findings);
+    }
+
+    // The file has bytes after the last point.
+    let report = pcd_rs::validate("test_files/binary.pcd")?;
+    assert_eq!(checks(&report, Severity::Warning), [Check::DataLength]);
+    assert_eq!(report.max_severity(), Some(Severity::Warning));
+    Ok(())
+}
+
+#[test]
+fn validate_header() -> Result<()> {
+    let text = "\
+VERSION .7
+SIZE 4 2 4
+FIELDS x y y
+TYPE F F U
+WIDTH 2
+HEIGHT 2
+VIEWPOINT 0 0 0 2 0 0 0
+POINTS 3
+DATA ascii
+";
+    let report = pcd_rs::validate_reader(text.as_bytes())?;
+    assert!(!report.is_valid());
+    assert_eq!(
+        checks(&report, Severity::Error),
+        [
+            Check::HeaderOrder,
+            Check::FieldName,
+            Check::FieldType,
+            Check::Dimensions
+        ]
+    );
+    assert_eq!(
+        checks(&report, Severity::Warning),
+        [Check::HeaderDefault, Check::Viewpoint]
+    );
+
+    let order = &report.findings[0];
+    assert_eq!(order.line, Some(3));
+    assert_eq!(order.message, "FIELDS must come before SIZE");
+
+    let field_type = report
+        .errors()
+        .find(|finding| finding.check == Check::FieldType)
+        .unwrap();
+    assert_eq!(field_type.field.as_deref(), Some("y"));
+    assert_eq!(field_type.line, Some(2));
+    Ok(())
+}
+
+#[test]
+fn validate_ascii_data() -> Result<()> {
+    let text = format!("{HEADER}DATA ascii\n1 nan 0\n2 3\ninf x -1\n");
+    let report = pcd_rs::validate_reader(text.as_bytes())?;
+
+    assert_eq!(
+        checks(&report, Severity::Error),
+        [Check::TokenCount, Check::TokenParse]
+    );
+    assert_eq!(checks(&report, Severity::Warning), [Check::NanInf]);
+    assert_eq!(checks(&report, Severity::Info), [Check::NanInf]);
+
+    let count = report.errors().next().unwrap();
+    assert_eq!(count.point_index, Some(1));
+    let parse = report.errors().nth(1).unwrap();
+    assert_eq!(parse.point_index, Some(2));
+    assert_eq!(parse.field.as_deref(), Some("y"));
+    assert_eq!(
+        parse.message,
+        r#"2 tokens cannot be parsed, the first: "x" is not a valid F32"#
+    );
+
+    let text = format!("{HEADER}DATA ascii\n1 2 0\n");
+    let report = pcd_rs::validate_reader(text.as_bytes())?;
+    assert_eq!(checks(&report, Severity::Error), [Check::DataLength]);
+    assert_eq!(report.findings[0].point_index, Some(1));
+    Ok(())
+}
+
+#[test]
+fn validate_binary_data() -> Result<()> {
+    let mut bytes = format!("{HEADER}DATA binary\n").into_bytes();
+    bytes.extend([0; 12 * 2 + 4]);
+
+    let report = pcd_rs::validate_reader(bytes.as_slice())?;
+    assert_eq!(checks(&report, Severity::Error), [Check::DataLength]);
+    assert_eq!(report.findings[0].point_index, Some(2));
+
+    // The data size of POINTS overflows.
+    let header = HEADER.replace("POINTS 3", "POINTS 18446744073709551615");
+    let mut bytes = format!("{header}DATA binary\n").into_bytes();
+    bytes.extend([0; 12]);
+
+    let report = pcd_rs::validate_reader(bytes.as_slice())?;
+    assert!(checks(&report, Severity::Error).contains(&Check::DataLength));
+    Ok(())
+}
+
+#[test]
+fn validate_compressed_data() -> Result<()> {
+    let mut bytes = format!("{HEADER}DATA binary_compressed\n").into_bytes();
+    bytes.extend(8u32.to_le_bytes());
+    bytes.extend(36u32.to_le_bytes());
+    bytes.extend([0xff; 8]);
+
+    let report = pcd_rs::validate_reader(bytes.as_slice())?;
+    assert_eq!(checks(&report, Severity::Error), [Check::Lzf]);
+
+    let mut bytes = format!("{HEADER}DATA binary_compressed\n").into_bytes();
+    bytes.extend(8u32.to_le_bytes());
+    bytes.extend(36u32.to_le_bytes());
+    bytes.extend([0; 4]);
+
+    let report = pcd_rs::validate_reader(bytes.as_slice())?;
+    assert_eq!(checks(&report, Severity::Error), [Check::DataLength]);
+    Ok(())
+}
+
+#[test]
+fn validate_overflowing_sizes() -> Result<()> {
+    // A field size overflows.
+    let field = HEADER.replace("COUNT 1 1 1", "COUNT 4611686018427387904 1 1");
+    // The sum of the field sizes overflows.
+    let point = HEADER.replace(
+        "COUNT 1 1 1",
+        "COUNT 2305843009213693952 2305843009213693952 1",
+    );
+
+    for header in [field, point] {
+        for kind in ["ascii", "binary", "binary_compressed"] {
+            let mut bytes = format!("{header}DATA {kind}\n").into_bytes();
+            bytes.extend([0; 16]);
+
+            let report = pcd_rs::validate_reader(bytes.as_slice())?;
+            assert_eq!(checks(&report, Severity::Error), [Check::FieldType]);
+            assert_eq!(checks(&report, Severity::Info), [Check::DataLength]);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn report_to_json() -> Result<()> {
+    let text = HEADER.replace("POINTS 3", "POINTS 4") + "DATA ascii\n";
+    let report = pcd_rs::validate_reader(text.as_bytes())?;
+
+    let json = serde_json::to_value(&report)?;
+    let finding = &json["findings"][0];
+    assert_eq!(finding["severity"], "error");
+    assert_eq!(finding["check"], "dimensions");
+    assert_eq!(finding["line"], 9);
+
+    let parsed: ValidationReport = serde_json::from_value(json)?;
+    assert_eq!(parsed, report);
+    Ok(())
+}
@@ -17,7 +17,7 @@ fn write_ascii_typed() -> Result<()> {
     let path = "test_files/dump_ascii_typed.pcd";
     let dump_points = vec![
         Point {
-            x: 3.14159,
+            x: 3.5,
             y: [2, 1, 7],
             z: -5,
         },
@@ -63,7 +63,7 @@ fn write_binary_typed() -> Result<()> {
 
     let dump_points = vec![
         Point {
-            x: 3.14159,
+            x: 3.5,
             y: [2, 1, 7],
             z: -5,
         },
@@ -102,3 +102,31 @@ fn write_binary_typed() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn static_fields_typed() -> Result<()> {
+    use pcd_rs::{Schema, StaticFieldDef, ValueKind};
+
+    const FIELDS: &[StaticFieldDef] = <Point as PcdSerialize>::FIELDS;
+    assert_eq!(
+        FIELDS,
+        &[
+            StaticFieldDef::new(Some("new_x"), ValueKind::F32, Some(1)),
+            StaticFieldDef::new(Some("y"), ValueKind::U8, Some(3)),
+            StaticFieldDef::new(Some("z"), ValueKind::I32, Some(1)),
+        ]
+    );
+    assert_eq!(FIELDS, <Point as PcdDeserialize>::FIELDS);
+
+    let schema = Schema::from(FIELDS);
+    assert_eq!(
+        schema,
+        Schema::from_iter([
+            ("new_x", ValueKind::F32, 1),
+            ("y", ValueKind::U8, 3),
+            ("z", ValueKind::I32, 1),
+        ])
+    );
+
+    Ok(())
+}
@@ -7,7 +7,7 @@ fn write_ascii_untyped() -> Result<()> {
     let path = "test_files/dump_ascii_untyped.pcd";
     let dump_points = vec![
         DynRecord(vec![
-            Field::F32(vec![3.14159]),
+            Field::F32(vec![3.5]),
             Field::U8(vec![2, 1, 7]),
             Field::I32(vec![-5]),
         ]),
@@ -59,7 +59,7 @@ fn write_binary_untyped() -> Result<()> {
 
     let dump_points = vec![
         DynRecord(vec![
-            Field::F32(vec![3.14159]),
+            Field::F32(vec![3.5]),
             Field::U8(vec![2, 1, 7]),
             Field::I32(vec![-5]),
         ]),
@@ -104,3 +104,10 @@ fn write_binary_untyped() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn dynamic_record_specs() {
+    // The schema of a dynamic record is given at runtime.
+    assert!(<DynRecord as pcd_rs::PcdSerialize>::write_spec().is_empty());
+    assert!(<DynRecord as pcd_rs::PcdDeserialize>::read_spec().is_empty());
+}